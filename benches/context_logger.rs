@@ -0,0 +1,160 @@
+//! Benchmarks for the hot paths of the context logger.
+//!
+//! Run with `cargo bench`.
+
+use std::hint::black_box;
+
+use context_logger::{
+    ContextLogger, ContextLoggerConfig, FutureExt as _, LogContext, LogScope, LogValue,
+};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use log::{Level, Log, Record};
+
+/// An inner logger that only visits the key-values without rendering them,
+/// so mostly the wrapper overhead is measured.
+struct NopLogger;
+
+impl Log for NopLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        struct Visit;
+
+        impl<'kvs> log::kv::VisitSource<'kvs> for Visit {
+            fn visit_pair(
+                &mut self,
+                key: log::kv::Key<'kvs>,
+                value: log::kv::Value<'kvs>,
+            ) -> Result<(), log::kv::Error> {
+                black_box((key, value));
+                Ok(())
+            }
+        }
+
+        black_box(record.key_values().visit(&mut Visit)).unwrap();
+    }
+
+    fn flush(&self) {}
+}
+
+fn log_record(logger: &ContextLogger) {
+    logger.log(
+        &Record::builder()
+            .level(Level::Info)
+            .target("bench")
+            .args(format_args!("benchmark message"))
+            .build(),
+    );
+}
+
+fn context_with_fields(n: u64) -> LogContext {
+    (0..n).fold(LogContext::new(), |context, i| {
+        context.with_local_record(format!("field_{i}"), i)
+    })
+}
+
+fn bench_enter_exit(c: &mut Criterion) {
+    let context = context_with_fields(2);
+    c.bench_function("enter_exit_small_context", |b| {
+        b.iter(|| {
+            let guard = LogScope::enter(black_box(context.clone()));
+            drop(guard);
+        });
+    });
+}
+
+fn bench_log_with_fields(c: &mut Criterion) {
+    let logger = ContextLogger::new(NopLogger).with_default_record("service", "bench");
+
+    let mut group = c.benchmark_group("log_with_context_fields");
+    for n in [0_u64, 4, 16] {
+        let _guard = LogScope::enter(context_with_fields(n));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, _| {
+            b.iter(|| log_record(&logger));
+        });
+    }
+    group.finish();
+}
+
+fn bench_context_disabled(c: &mut Criterion) {
+    let enabled = ContextLogger::new(NopLogger).with_default_record("service", "bench");
+    let disabled = ContextLogger::new(NopLogger)
+        .with_default_record("service", "bench")
+        .with_context_disabled(true);
+    let _guard = LogScope::enter(context_with_fields(4));
+
+    let mut group = c.benchmark_group("context_processing");
+    group.bench_function("enabled", |b| b.iter(|| log_record(&enabled)));
+    group.bench_function("disabled", |b| b.iter(|| log_record(&disabled)));
+    group.finish();
+}
+
+fn bench_outside_scope(c: &mut Criterion) {
+    let pass_through = ContextLogger::new(NopLogger);
+    let enriched = ContextLogger::new(NopLogger).with_default_record("service", "bench");
+
+    let mut group = c.benchmark_group("log_outside_scope");
+    group.bench_function("pass_through", |b| b.iter(|| log_record(&pass_through)));
+    group.bench_function("default_record", |b| b.iter(|| log_record(&enriched)));
+    group.finish();
+}
+
+fn bench_future_poll(c: &mut Criterion) {
+    let context = context_with_fields(2);
+    let waker = futures_util::task::noop_waker();
+    let mut cx = std::task::Context::from_waker(&waker);
+
+    c.bench_function("in_log_context_poll", |b| {
+        b.iter(|| {
+            let mut future = std::pin::pin!(std::future::ready(42).in_log_context(context.clone()));
+            black_box(future.as_mut().poll(&mut cx))
+        });
+    });
+}
+
+fn bench_value_rendering(c: &mut Criterion) {
+    let mut group = c.benchmark_group("value_rendering");
+    let number = LogValue::from(42);
+    let display = LogValue::display("display value");
+    group.bench_function("number", |b| b.iter(|| black_box(&number).to_string()));
+    group.bench_function("display", |b| b.iter(|| black_box(&display).to_string()));
+    group.finish();
+}
+
+fn bench_value_construction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("value_construction");
+    group.bench_function("from_str", |b| {
+        b.iter(|| LogValue::from(black_box("production")));
+    });
+    group.bench_function("static_str", |b| {
+        b.iter(|| LogValue::static_str(black_box("production")));
+    });
+    group.finish();
+}
+
+fn bench_runtime_config(c: &mut Criterion) {
+    // Must run last: once set, the runtime config is read by every logger.
+    let logger = ContextLogger::new(NopLogger);
+    let mut group = c.benchmark_group("runtime_config");
+    context_logger::set_config(ContextLoggerConfig::new());
+    group.bench_function("pass_through", |b| b.iter(|| log_record(&logger)));
+    context_logger::update_config(|config| config.with_default_record("service", "api"));
+    group.bench_function("default_record", |b| b.iter(|| log_record(&logger)));
+    context_logger::set_config(ContextLoggerConfig::new());
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_enter_exit,
+    bench_log_with_fields,
+    bench_context_disabled,
+    bench_outside_scope,
+    bench_future_poll,
+    bench_value_rendering,
+    bench_value_construction,
+    bench_runtime_config
+);
+criterion_main!(benches);
@@ -0,0 +1,70 @@
+use std::sync::{Arc, Mutex, mpsc};
+
+use context_logger::{
+    CapturedContext, ContextEnvelope, ContextLogger, LogContext, LogContextExt as _,
+};
+
+const WORKERS: usize = 3;
+
+fn try_init_logger() -> Result<(), Box<dyn std::error::Error>> {
+    let level = log::LevelFilter::Info;
+
+    let logger = structured_logger::Builder::with_level(level.as_str())
+        .with_target_writer("*", structured_logger::json::new_writer(std::io::stdout()))
+        .build();
+    ContextLogger::new(logger)
+        .with_default_record("instance", "worker_pool")
+        .try_init(level)?;
+
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    try_init_logger()?;
+
+    // The receiver is shared by the workers, each job is handled by one of them.
+    let (sender, receiver) = mpsc::channel::<ContextEnvelope<u64>>();
+    let receiver = Arc::new(Mutex::new(receiver));
+
+    // Workers inherit the context of the code that started the pool.
+    let pool_context = LogContext::new()
+        .with_inherited_record("pool", "example")
+        .in_scope(CapturedContext::with_frames);
+    let workers = (0..WORKERS)
+        .map(|worker| {
+            let receiver = Arc::clone(&receiver);
+            let pool_context = pool_context.clone();
+            std::thread::spawn(move || {
+                let _pool_guard = pool_context.enter_all();
+                loop {
+                    let envelope = receiver.lock().unwrap().recv();
+                    let Ok(envelope) = envelope else {
+                        break;
+                    };
+                    // Each job is processed within the context of its producer.
+                    envelope.in_context(|input| {
+                        log::info!(worker, input; "Processing job");
+                    });
+                }
+                log::info!(worker; "Worker stopped");
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for job_id in 1..=5_u64 {
+        LogContext::new()
+            .with_local_record("job_id", job_id)
+            .in_scope(|| {
+                log::info!("Submitting job");
+                ContextEnvelope::send_with_context(&sender, job_id * 10)
+            })?;
+    }
+    drop(sender);
+
+    for worker in workers {
+        worker.join().expect("worker thread panicked");
+    }
+    log::info!("All jobs processed");
+
+    Ok(())
+}
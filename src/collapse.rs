@@ -0,0 +1,265 @@
+//! Collapsing of identical consecutive log records.
+
+use std::{
+    hash::{DefaultHasher, Hasher},
+    num::NonZeroU64,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// Configuration for collapsing identical consecutive records.
+///
+/// Consecutive records with the same level, target, message and key-values that arrive
+/// within the `window` since the previous one are suppressed. When a different record
+/// arrives, a `previous message repeated N times` summary is emitted before it, or
+/// `previous message repeated 1 time` for a single repeat.
+///
+/// Created by [`CollapseRepeats::new`] or converted from a [`Duration`] window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollapseRepeats {
+    window: Duration,
+    summary_every: Option<NonZeroU64>,
+}
+
+impl CollapseRepeats {
+    /// Creates a configuration that collapses repeats arriving within the given window.
+    #[must_use]
+    pub const fn new(window: Duration) -> Self {
+        Self {
+            window,
+            summary_every: None,
+        }
+    }
+
+    /// Additionally emits a summary after every `n` suppressed repeats, so long runs of
+    /// the same record remain visible. Passing zero disables periodic summaries.
+    #[must_use]
+    pub const fn with_summary_every(mut self, n: u64) -> Self {
+        self.summary_every = NonZeroU64::new(n);
+        self
+    }
+}
+
+impl From<Duration> for CollapseRepeats {
+    fn from(window: Duration) -> Self {
+        Self::new(window)
+    }
+}
+
+/// A summary of suppressed repeats of a record.
+#[derive(Debug)]
+struct Summary {
+    level: log::Level,
+    target: String,
+    repeats: u64,
+}
+
+impl Summary {
+    fn log(&self, inner: &dyn log::Log) {
+        inner.log(
+            &log::Record::builder()
+                .level(self.level)
+                .target(&self.target)
+                .args(format_args!(
+                    "previous message repeated {} {}",
+                    self.repeats,
+                    if self.repeats == 1 { "time" } else { "times" }
+                ))
+                .build(),
+        );
+    }
+}
+
+#[derive(Debug)]
+struct Run {
+    fingerprint: u64,
+    level: log::Level,
+    target: String,
+    message: String,
+    repeats: u64,
+    last_seen: Instant,
+}
+
+impl Run {
+    /// Returns `true` if the record repeats the one that started the run.
+    ///
+    /// Key-values are compared by the fingerprint only, while the level, target and
+    /// message are compared exactly to rule out fingerprint collisions.
+    fn is_repeated_by(&self, record: &log::Record, fingerprint: u64) -> bool {
+        self.fingerprint == fingerprint
+            && self.level == record.level()
+            && self.target == record.target()
+            && is_formatted_as(record.args(), &self.message)
+    }
+
+    fn take_summary(&mut self) -> Option<Summary> {
+        let repeats = std::mem::take(&mut self.repeats);
+        (repeats > 0).then(|| Summary {
+            level: self.level,
+            target: self.target.clone(),
+            repeats,
+        })
+    }
+}
+
+/// Tracks the last emitted record and suppresses its identical repeats.
+#[derive(Debug)]
+pub struct RepeatCollapser {
+    config: CollapseRepeats,
+    run: Mutex<Option<Run>>,
+}
+
+impl RepeatCollapser {
+    pub const fn new(config: CollapseRepeats) -> Self {
+        Self {
+            config,
+            run: Mutex::new(None),
+        }
+    }
+
+    /// Forwards the record to the inner logger unless it repeats the previous one.
+    pub fn log(&self, inner: &dyn log::Log, record: &log::Record) {
+        let fingerprint = fingerprint(record);
+        let now = Instant::now();
+
+        // The lock is released before calling the inner logger, so a reentrant
+        // logging call from the inner logger cannot deadlock.
+        let mut run = self.run.lock().unwrap_or_else(PoisonError::into_inner);
+        let (summary, suppress) = match run.as_mut() {
+            Some(run)
+                if run.is_repeated_by(record, fingerprint)
+                    && now.duration_since(run.last_seen) <= self.config.window =>
+            {
+                run.repeats += 1;
+                run.last_seen = now;
+                let summary = self
+                    .config
+                    .summary_every
+                    .filter(|n| run.repeats % n.get() == 0)
+                    .and_then(|_| run.take_summary());
+                (summary, true)
+            }
+            _ => {
+                let previous = run.replace(Run {
+                    fingerprint,
+                    level: record.level(),
+                    target: record.target().to_owned(),
+                    message: record.args().to_string(),
+                    repeats: 0,
+                    last_seen: now,
+                });
+                (previous.and_then(|mut run| run.take_summary()), false)
+            }
+        };
+        drop(run);
+
+        if let Some(summary) = summary {
+            summary.log(inner);
+        }
+        if !suppress {
+            inner.log(record);
+        }
+    }
+
+    /// Emits the summary of the pending repeats, if any.
+    ///
+    /// Repeats arriving afterwards are still collapsed and counted from zero.
+    pub fn flush(&self, inner: &dyn log::Log) {
+        let summary = self
+            .run
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_mut()
+            .and_then(Run::take_summary);
+        if let Some(summary) = summary {
+            summary.log(inner);
+        }
+    }
+}
+
+/// Returns `true` if the arguments format exactly as the expected text, without
+/// allocating the formatted text.
+fn is_formatted_as(args: &std::fmt::Arguments, expected: &str) -> bool {
+    struct Compare<'a>(&'a str);
+
+    impl std::fmt::Write for Compare<'_> {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            self.0 = self.0.strip_prefix(s).ok_or(std::fmt::Error)?;
+            Ok(())
+        }
+    }
+
+    let mut compare = Compare(expected);
+    std::fmt::Write::write_fmt(&mut compare, *args).is_ok() && compare.0.is_empty()
+}
+
+/// Computes a hash of the record level, target, message and key-values.
+fn fingerprint(record: &log::Record) -> u64 {
+    struct HashWriter(DefaultHasher);
+
+    impl std::fmt::Write for HashWriter {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            self.0.write(s.as_bytes());
+            Ok(())
+        }
+    }
+
+    impl<'kvs> log::kv::VisitSource<'kvs> for HashWriter {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            std::fmt::Write::write_fmt(self, format_args!("\0{key}\0{value}"))?;
+            Ok(())
+        }
+    }
+
+    let mut hasher = HashWriter(DefaultHasher::new());
+    hasher.0.write_usize(record.level() as usize);
+    hasher.0.write(record.target().as_bytes());
+    // Formatting into a hasher cannot fail, as well as visiting the key-values.
+    let _ = std::fmt::Write::write_fmt(&mut hasher, format_args!("\0{}", record.args()));
+    let _ = record.key_values().visit(&mut hasher);
+    hasher.0.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::{Run, is_formatted_as};
+
+    #[test]
+    fn test_is_formatted_as() {
+        assert!(is_formatted_as(&format_args!("retry {}", 1), "retry 1"));
+        assert!(!is_formatted_as(&format_args!("retry {}", 1), "retry 10"));
+        assert!(!is_formatted_as(&format_args!("retry {}", 10), "retry 1"));
+    }
+
+    #[test]
+    fn test_fingerprint_collision_is_not_a_repeat() {
+        let run = Run {
+            fingerprint: 42,
+            level: log::Level::Warn,
+            target: "test".to_owned(),
+            message: "retrying".to_owned(),
+            repeats: 0,
+            last_seen: Instant::now(),
+        };
+        let is_repeated_by = |message: &str| {
+            // The fingerprint of the run is passed to simulate a collision.
+            run.is_repeated_by(
+                &log::Record::builder()
+                    .level(log::Level::Warn)
+                    .target("test")
+                    .args(format_args!("{message}"))
+                    .build(),
+                42,
+            )
+        };
+
+        assert!(is_repeated_by("retrying"));
+        assert!(!is_repeated_by("failed"));
+    }
+}
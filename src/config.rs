@@ -0,0 +1,134 @@
+//! Process-wide configuration that can be changed at runtime.
+
+use std::{
+    borrow::Cow,
+    sync::{
+        Arc, LazyLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use arc_swap::ArcSwap;
+
+use crate::{LogRecords, LogValue};
+
+/// The active configuration, read by every [`ContextLogger`](crate::ContextLogger) on each record.
+static CONFIG: LazyLock<ArcSwap<ContextLoggerConfig>> =
+    LazyLock::new(|| ArcSwap::from_pointee(ContextLoggerConfig::new()));
+
+/// Set once the configuration is changed, so loggers can skip loading the default one.
+static CONFIGURED: AtomicBool = AtomicBool::new(false);
+
+/// Process-wide settings of context loggers that can be changed after initialization.
+///
+/// Unlike the [`ContextLogger`](crate::ContextLogger) builder methods, which are fixed
+/// once the logger is installed, these settings can be changed at any time with
+/// [`update_config`] or [`set_config`] and apply to every context logger. Reading the
+/// configuration on the logging path is a single atomic load.
+///
+/// # Examples
+///
+/// ```
+/// use context_logger::ContextLoggerConfig;
+///
+/// // Tag all subsequent records while a maintenance window is active.
+/// context_logger::update_config(|config| config.with_default_record("maintenance", true));
+/// assert!(!context_logger::config().default_records().is_empty());
+///
+/// context_logger::set_config(ContextLoggerConfig::new());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContextLoggerConfig {
+    default_records: LogRecords,
+    context_enabled: bool,
+}
+
+impl ContextLoggerConfig {
+    /// Creates the default configuration: no runtime default records and scope
+    /// records enabled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            default_records: LogRecords::new(),
+            context_enabled: true,
+        }
+    }
+
+    /// Adds a record to all log entries, after the default records of the logger.
+    #[must_use]
+    pub fn with_default_record(
+        mut self,
+        key: impl Into<Cow<'static, str>>,
+        value: impl Into<LogValue>,
+    ) -> Self {
+        self.default_records.insert(key, value);
+        self
+    }
+
+    /// Removes the runtime default record with the given key.
+    #[must_use]
+    pub fn without_default_record(mut self, key: &str) -> Self {
+        self.default_records
+            .retain(|record_key, _| record_key != key);
+        self
+    }
+
+    /// Enables or disables enrichment with scope records.
+    ///
+    /// Disabling has the same effect as [`ContextLogger::with_context_disabled`], but can
+    /// be toggled at runtime, e.g. to shed load.
+    ///
+    /// [`ContextLogger::with_context_disabled`]: crate::ContextLogger::with_context_disabled
+    #[must_use]
+    pub const fn with_context_enabled(mut self, enabled: bool) -> Self {
+        self.context_enabled = enabled;
+        self
+    }
+
+    /// Returns the runtime default records.
+    #[must_use]
+    pub const fn default_records(&self) -> &LogRecords {
+        &self.default_records
+    }
+
+    /// Returns `true` if records are enriched with scope records.
+    #[must_use]
+    pub const fn is_context_enabled(&self) -> bool {
+        self.context_enabled
+    }
+}
+
+impl Default for ContextLoggerConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns a snapshot of the current runtime configuration.
+///
+/// The snapshot is not affected by later changes.
+#[must_use]
+pub fn config() -> Arc<ContextLoggerConfig> {
+    CONFIG.load_full()
+}
+
+/// Replaces the runtime configuration.
+pub fn set_config(config: ContextLoggerConfig) {
+    CONFIG.store(Arc::new(config));
+    CONFIGURED.store(true, Ordering::Release);
+}
+
+/// Updates the runtime configuration with the given function.
+///
+/// The function receives a copy of the current configuration and may be called more
+/// than once if the configuration is updated concurrently.
+pub fn update_config(f: impl Fn(ContextLoggerConfig) -> ContextLoggerConfig) {
+    CONFIG.rcu(|config| f(ContextLoggerConfig::clone(config)));
+    CONFIGURED.store(true, Ordering::Release);
+}
+
+/// Returns a guard to the current configuration for the logging path, or `None` if
+/// the configuration has never been changed.
+pub fn load() -> Option<arc_swap::Guard<Arc<ContextLoggerConfig>>> {
+    CONFIGURED.load(Ordering::Acquire).then(|| CONFIG.load())
+}
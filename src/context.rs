@@ -1,8 +1,39 @@
 //! Context builder for structured logging.
 
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    hash::{BuildHasher, RandomState},
+    sync::{
+        OnceLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
 
-use crate::{LogValue, records::LogRecords};
+use crate::{ContextKey, ContextView, LogValue, records::LogRecords, scope::stack::SCOPE_STACK};
+
+/// A key of the record added by [`LogContext::with_origin`].
+#[cfg(feature = "origin")]
+const ORIGIN_KEY: &str = "ctx.origin";
+
+/// A key of the record added by [`LogContext::with_correlation_id`].
+const CORRELATION_ID_KEY: &str = "correlation_id";
+
+/// A policy that controls which records of the outer scopes are visible in nested scopes.
+///
+/// See [`ContextLogger::with_inheritance`](crate::ContextLogger::with_inheritance).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Inheritance {
+    /// Only inherited records of the outer scopes are visible, local records of
+    /// a scope are shadowed by nested scopes.
+    #[default]
+    Shadow,
+    /// Records of all active scopes are visible, as if local records were inherited.
+    ///
+    /// Local records of nested scopes are emitted after the ones of outer scopes, and
+    /// the records of the innermost scope, both inherited and local, are emitted last,
+    /// so "last write wins" consumers see the innermost value of a key.
+    Merge,
+}
 
 /// A set of records that can be attached to a logging scope.
 ///
@@ -22,6 +53,8 @@ pub struct LogContext {
     pub local: LogRecords,
     /// Records that automatically flow into all child scopes created within the current scope.
     pub inherited: LogRecords,
+    /// An optional name of the scope, see [`Self::named`].
+    pub name: Option<Cow<'static, str>>,
 }
 
 impl LogContext {
@@ -31,8 +64,73 @@ impl LogContext {
         Self::default()
     }
 
+    /// Creates a new, empty context with space for at least `capacity` local records.
+    ///
+    /// Building a context with a known number of fields this way avoids reallocations.
+    /// Inherited records are not pre-allocated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::LogContext;
+    ///
+    /// let context = LogContext::with_capacity(2)
+    ///     .with_local_record("user_id", 42)
+    ///     .with_local_record("request_id", "req-123");
+    /// ```
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            local: LogRecords::with_capacity(capacity),
+            inherited: LogRecords::new(),
+            name: None,
+        }
+    }
+
+    /// Creates a new, empty context with the given name.
+    ///
+    /// The name is not a record and is not attached to log records by itself. It
+    /// identifies the scope in the path of active scopes, see
+    /// [`ContextLogger::with_breadcrumb_field`](crate::ContextLogger::with_breadcrumb_field).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::LogContext;
+    ///
+    /// let context = LogContext::named("db_query").with_local_record("table", "users");
+    /// assert_eq!(context.name.as_deref(), Some("db_query"));
+    /// ```
+    #[must_use]
+    pub fn named(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new context with a single local record.
+    ///
+    /// This is a shorthand for `LogContext::new().with_local_record(key, value)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{LogContext, LogScope};
+    ///
+    /// let _scope = LogScope::enter(LogContext::with("request_id", "req-123"));
+    /// assert!(LogContext::is_active("request_id"));
+    /// ```
+    #[must_use]
+    pub fn with(key: impl Into<Cow<'static, str>>, value: impl Into<LogValue>) -> Self {
+        Self::new().with_local_record(key, value)
+    }
+
     /// Adds a key-value record to the local records of this context.
     ///
+    /// If a local record with the same key already exists, its value is replaced,
+    /// so each key appears only once per frame with the last value winning.
+    ///
     /// See [`LogRecords`] for more details about log records.
     #[must_use]
     pub fn with_local_record(
@@ -46,6 +144,8 @@ impl LogContext {
 
     /// Adds a key-value record to the inherited records of this context.
     ///
+    /// If an inherited record with the same key already exists, its value is replaced.
+    ///
     /// See [`LogRecords`] for more details about log records.
     #[must_use]
     pub fn with_inherited_record(
@@ -57,9 +157,388 @@ impl LogContext {
         self
     }
 
+    /// Adds a local record with a [typed key](ContextKey).
+    ///
+    /// The value must have the type declared by the key, otherwise the call does not
+    /// compile. The record is stored under the key's name, exactly like a record added
+    /// by [`Self::with_local_record`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{ContextKey, LogContext};
+    ///
+    /// const USER_ID: ContextKey<u64> = ContextKey::new("user_id");
+    ///
+    /// let context = LogContext::new().with_typed_record(USER_ID, 42);
+    /// ```
+    #[must_use]
+    pub fn with_typed_record<T>(self, key: ContextKey<T>, value: T) -> Self
+    where
+        T: Into<LogValue>,
+    {
+        self.with_local_record(key.name(), value)
+    }
+
+    /// Adds a local record with the value computed by the given closure.
+    ///
+    /// The closure is called immediately and only once, unlike [`LogValue::lazy`] which
+    /// is evaluated every time the record is rendered. This allows inline computations in
+    /// the builder chain that move captured data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::LogContext;
+    ///
+    /// let items = vec!["a", "b", "c"];
+    /// let context = LogContext::new()
+    ///     .with_computed_record("items", move || items.join(","));
+    /// ```
+    #[must_use]
+    pub fn with_computed_record<V>(
+        self,
+        key: impl Into<Cow<'static, str>>,
+        f: impl FnOnce() -> V,
+    ) -> Self
+    where
+        V: Into<LogValue>,
+    {
+        self.with_local_record(key, f())
+    }
+
+    /// Tags this context with the location of the caller.
+    ///
+    /// With the `origin` feature enabled, the location is added as a local `ctx.origin`
+    /// record in the `file:line:column` form, which helps to trace which code entered
+    /// a scope. Without the feature this method does nothing and has no runtime cost.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::LogContext;
+    ///
+    /// let context = LogContext::new()
+    ///     .with_local_record("request_id", "req-123")
+    ///     .with_origin();
+    /// ```
+    #[must_use]
+    #[track_caller]
+    #[allow(
+        clippy::missing_const_for_fn,
+        reason = "capturing the caller location is not const"
+    )]
+    pub fn with_origin(self) -> Self {
+        #[cfg(feature = "origin")]
+        {
+            let location = std::panic::Location::caller();
+            self.with_local_record(ORIGIN_KEY, LogValue::display(*location))
+        }
+        #[cfg(not(feature = "origin"))]
+        self
+    }
+
+    /// Adds an inherited record with a newly generated unique id.
+    ///
+    /// The id consists of 16 lowercase hexadecimal characters, e.g. `3f9a0c12d4e5b678`.
+    /// It is derived from a process-wide counter offset randomly per process by a
+    /// bijective mixing function, so ids never repeat within a process and are unlikely
+    /// to collide across processes. This is enough to correlate log records of a request, but the
+    /// ids are not suitable for security purposes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{LogContext, LogContextExt as _};
+    ///
+    /// LogContext::new()
+    ///     .with_generated_id("request_id")
+    ///     .in_scope(|| log::info!("Handling request"));
+    /// ```
+    #[must_use]
+    pub fn with_generated_id(self, key: impl Into<Cow<'static, str>>) -> Self {
+        self.with_inherited_record(key, generate_id())
+    }
+
+    /// Tags this context with a correlation id shared by all nested scopes.
+    ///
+    /// If a scope with a correlation id is already active on the current thread, its id
+    /// is reused, otherwise a new one is [generated](Self::with_generated_id). The id is
+    /// added as an inherited `correlation_id` record, so every nested scope carries it
+    /// as well. This makes it possible to find all records of a logical operation,
+    /// including the ones of nested scopes, without threading the id manually.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{LogContext, LogContextExt as _};
+    ///
+    /// LogContext::new().with_correlation_id().in_scope(|| {
+    ///     // Both records share the same `correlation_id`.
+    ///     log::info!("Handling request");
+    ///     LogContext::new()
+    ///         .with_local_record("stage", "query")
+    ///         .with_correlation_id()
+    ///         .in_scope(|| log::info!("Running query"));
+    /// });
+    /// ```
+    #[must_use]
+    pub fn with_correlation_id(self) -> Self {
+        let active = SCOPE_STACK.with(|stack| {
+            let frame = stack.top()?;
+            frame.context.inherited.get(CORRELATION_ID_KEY).cloned()
+        });
+        let id = active.unwrap_or_else(|| generate_id().into());
+        self.with_inherited_record(CORRELATION_ID_KEY, id)
+    }
+
+    /// Returns `true` if any scope active on the current thread has a record with the
+    /// given key.
+    ///
+    /// Unlike [`LogScope::current_context`](crate::LogScope::current_context), this
+    /// checks local records of the outer scopes as well and does not copy any records.
+    /// Returns `false` if no scope is active.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{LogContext, LogContextExt as _};
+    ///
+    /// LogContext::new().with_local_record("request_id", 1).in_scope(|| {
+    ///     LogContext::new().in_scope(|| {
+    ///         assert!(LogContext::is_active("request_id"));
+    ///         assert!(!LogContext::is_active("user_id"));
+    ///     });
+    /// });
+    /// ```
+    #[must_use]
+    pub fn is_active(key: &str) -> bool {
+        SCOPE_STACK
+            .try_with(|stack| {
+                stack.try_frames().is_some_and(|frames| {
+                    frames.iter().any(|frame| {
+                        frame.context.local.contains_key(key)
+                            || frame.context.inherited.contains_key(key)
+                    })
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    /// Retains only the local and inherited records for which the predicate returns `true`.
+    ///
+    /// Useful to trim a context before entering or forwarding it, e.g. to drop
+    /// internal-only keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::LogContext;
+    ///
+    /// let context = LogContext::new()
+    ///     .with_local_record("request_id", "req-123")
+    ///     .with_inherited_record("internal.shard", 3)
+    ///     .retain(|key, _| !key.starts_with("internal."));
+    /// assert!(context.inherited.is_empty());
+    /// ```
+    #[must_use]
+    pub fn retain(mut self, mut f: impl FnMut(&str, &LogValue) -> bool) -> Self {
+        self.local.retain(&mut f);
+        self.inherited.retain(&mut f);
+        self
+    }
+
+    /// Returns a read-only view of the records of this context.
+    ///
+    /// Inherited records are listed before local ones. See [`ContextView`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::LogContext;
+    ///
+    /// let context = LogContext::new()
+    ///     .with_inherited_record("service", "api")
+    ///     .with_local_record("request_id", "req-123");
+    ///
+    /// let view = context.view();
+    /// assert_eq!(view.len(), 2);
+    /// for (key, value) in view.iter() {
+    ///     println!("{key}={value}");
+    /// }
+    /// ```
+    #[must_use]
+    pub fn view(&self) -> ContextView<'_> {
+        ContextView::from(self)
+    }
+
     /// Returns `true` if both local and inherited records are empty.
     #[must_use]
     pub fn is_empty(&self) -> bool {
         self.local.is_empty() && self.inherited.is_empty()
     }
 }
+
+/// Generates a unique id for [`LogContext::with_generated_id`].
+fn generate_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    static SEED: OnceLock<u64> = OnceLock::new();
+
+    let seed = *SEED.get_or_init(|| RandomState::new().hash_one(0_u64));
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{:016x}", splitmix64(seed.wrapping_add(n)))
+}
+
+/// The `SplitMix64` generator step, a bijection on `u64`, so distinct inputs never
+/// produce the same output.
+const fn splitmix64(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{LogContext, splitmix64};
+    use crate::{ContextKey, LogContextExt as _, LogScope};
+
+    #[test]
+    fn test_is_active() {
+        assert!(!LogContext::is_active("request_id"));
+
+        LogContext::new()
+            .with_local_record("request_id", 1)
+            .with_inherited_record("service", "api")
+            .in_scope(|| {
+                LogContext::new()
+                    .with_local_record("stage", "query")
+                    .in_scope(|| {
+                        // A local record of the outer frame.
+                        assert!(LogContext::is_active("request_id"));
+                        assert!(LogContext::is_active("service"));
+                        assert!(LogContext::is_active("stage"));
+                        assert!(!LogContext::is_active("user_id"));
+                    });
+                assert!(!LogContext::is_active("stage"));
+            });
+
+        assert!(!LogContext::is_active("request_id"));
+    }
+
+    #[test]
+    fn test_computed_record() {
+        let ids = vec![3_u64, 1, 2];
+        let context = LogContext::new()
+            .with_computed_record("max_id", move || ids.into_iter().max().unwrap_or_default());
+
+        assert_eq!(context.local["max_id"].to_string(), "3");
+        assert!(context.inherited.is_empty());
+    }
+
+    #[test]
+    fn test_typed_record() {
+        const REQUEST_ID: ContextKey<&str> = ContextKey::new("request_id");
+        const ATTEMPT: ContextKey<u32> = ContextKey::new("attempt");
+
+        // Borrowed values work with keys declared for `&'static str`.
+        let request_id = format!("req-{}", 123);
+        let context = LogContext::new()
+            .with_typed_record(REQUEST_ID, &request_id)
+            .with_typed_record(ATTEMPT, 2);
+
+        assert_eq!(context.local["request_id"].as_str(), Some("req-123"));
+        assert_eq!(context.local["attempt"].to_string(), "2");
+        assert_eq!(REQUEST_ID.name(), "request_id");
+    }
+
+    #[test]
+    fn test_with_is_new_with_local_record() {
+        let shorthand = LogContext::with("request_id", 42);
+        let expanded = LogContext::new().with_local_record("request_id", 42);
+
+        assert_eq!(format!("{shorthand:?}"), format!("{expanded:?}"));
+        assert_eq!(shorthand.local["request_id"].to_string(), "42");
+        assert!(shorthand.inherited.is_empty());
+        assert_eq!(shorthand.name, None);
+    }
+
+    #[test]
+    fn test_generated_ids_are_distinct() {
+        let ids = (0..1000)
+            .map(|_| {
+                let context = LogContext::new().with_generated_id("request_id");
+                context.inherited.find("request_id").unwrap().to_string()
+            })
+            .collect::<HashSet<_>>();
+
+        assert_eq!(ids.len(), 1000);
+        for id in &ids {
+            assert_eq!(id.len(), 16);
+            assert!(id.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f')));
+        }
+    }
+
+    #[test]
+    fn test_splitmix64_reference_values() {
+        // The first outputs of the reference `SplitMix64` generator seeded with zero.
+        assert_eq!(splitmix64(0), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(splitmix64(0x9e37_79b9_7f4a_7c15), 0x6e78_9e6a_a1b9_65f4);
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        let context = LogContext::with_capacity(8);
+        let capacity = context.local.capacity();
+        assert!(capacity >= 8);
+
+        let context = (0..8).fold(context, |context, i| {
+            context.with_local_record(format!("field_{i}"), i)
+        });
+        assert_eq!(context.local.capacity(), capacity);
+        assert_eq!(context.local.iter().count(), 8);
+        assert_eq!(context.local["field_7"].to_string(), "7");
+        assert!(context.inherited.is_empty());
+    }
+
+    #[test]
+    fn test_retain_by_prefix() {
+        let context = LogContext::new()
+            .with_local_record("http.method", "GET")
+            .with_local_record("db.table", "users")
+            .with_inherited_record("http.path", "/users")
+            .with_inherited_record("service", "api")
+            .retain(|key, _| key.starts_with("http."));
+
+        assert_eq!(context.local.iter().count(), 1);
+        assert_eq!(context.local["http.method"].to_string(), "GET");
+        assert_eq!(context.inherited.iter().count(), 1);
+        assert_eq!(context.inherited["http.path"].to_string(), "/users");
+    }
+
+    #[test]
+    fn test_nested_contexts_share_correlation_id() {
+        let correlation_id = || LogScope::current_context().inherited["correlation_id"].to_string();
+
+        let (outer, nested, tagged) = LogContext::new().with_correlation_id().in_scope(|| {
+            let nested = LogContext::new()
+                .with_local_record("stage", "parse")
+                .in_scope(correlation_id);
+            let tagged = LogContext::new()
+                .with_correlation_id()
+                .in_scope(|| LogContext::new().in_scope(correlation_id));
+            (correlation_id(), nested, tagged)
+        });
+        assert_eq!(nested, outer);
+        assert_eq!(tagged, outer);
+
+        let other = LogContext::new()
+            .with_correlation_id()
+            .in_scope(correlation_id);
+        assert_ne!(other, outer);
+    }
+}
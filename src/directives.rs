@@ -0,0 +1,270 @@
+//! Log level directives that depend on the record target or the active context.
+
+use std::convert::Infallible;
+
+use log::LevelFilter;
+
+use crate::records::LogRecordRef;
+
+/// An error returned when a level directive cannot be parsed.
+///
+/// See [`ContextLogger::try_with_context_directives`] and [`ContextLogger::try_with_filter`].
+///
+/// [`ContextLogger::try_with_context_directives`]: crate::ContextLogger::try_with_context_directives
+/// [`ContextLogger::try_with_filter`]: crate::ContextLogger::try_with_filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectiveError {
+    directive: String,
+}
+
+impl DirectiveError {
+    fn new(directive: &str) -> Self {
+        Self {
+            directive: directive.to_owned(),
+        }
+    }
+
+    /// Returns the invalid directive.
+    #[must_use]
+    pub fn directive(&self) -> &str {
+        &self.directive
+    }
+}
+
+impl std::fmt::Display for DirectiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid directive '{}'", self.directive)
+    }
+}
+
+impl std::error::Error for DirectiveError {}
+
+/// A directive that enables records up to `level` in contexts where `key` equals `value`.
+#[derive(Debug)]
+struct ContextDirective {
+    key: String,
+    value: String,
+    level: LevelFilter,
+}
+
+/// A parsed set of directives, see [`ContextLogger::with_context_directives`].
+///
+/// [`ContextLogger::with_context_directives`]: crate::ContextLogger::with_context_directives
+#[derive(Debug)]
+pub struct ContextDirectives {
+    default_level: Option<LevelFilter>,
+    directives: Vec<ContextDirective>,
+}
+
+impl ContextDirectives {
+    /// Parses a comma-separated list of `key=value:level` directives and an optional
+    /// bare default level, failing on the first invalid directive.
+    pub fn parse(spec: &str) -> Result<Self, DirectiveError> {
+        Self::parse_with(spec, |part| Err(DirectiveError::new(part)))
+    }
+
+    /// Parses the directives like [`Self::parse`], ignoring invalid directives.
+    pub fn parse_lossy(spec: &str) -> Self {
+        let Ok(directives) = Self::parse_with(spec, |_| Ok::<_, Infallible>(()));
+        directives
+    }
+
+    /// Parses the directives, passing the invalid ones to the given closure.
+    fn parse_with<E>(
+        spec: &str,
+        mut invalid: impl FnMut(&str) -> Result<(), E>,
+    ) -> Result<Self, E> {
+        let mut default_level = None;
+        let mut directives = Vec::new();
+        for part in spec
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            let Some((key, rest)) = part.split_once('=') else {
+                match part.parse() {
+                    Ok(level) => default_level = Some(level),
+                    Err(_) => invalid(part)?,
+                }
+                continue;
+            };
+            let (value, level) = match rest.rsplit_once(':') {
+                Some((value, level)) => (value, level.parse()),
+                None => (rest, Ok(LevelFilter::Trace)),
+            };
+            match level {
+                Ok(level) if !key.trim().is_empty() => directives.push(ContextDirective {
+                    key: key.trim().to_owned(),
+                    value: value.trim().to_owned(),
+                    level,
+                }),
+                _ => invalid(part)?,
+            }
+        }
+        Ok(Self {
+            default_level,
+            directives,
+        })
+    }
+
+    /// Returns the most verbose level enabled by any directive.
+    pub fn max_level(&self) -> LevelFilter {
+        self.directives
+            .iter()
+            .map(|directive| directive.level)
+            .chain(self.default_level)
+            .max()
+            .unwrap_or(LevelFilter::Off)
+    }
+
+    /// Returns `true` if a record with the given level and context records is enabled.
+    ///
+    /// Records that do not match any directive are compared with the default level,
+    /// which falls back to `base_level` if the directives do not specify it.
+    pub fn enabled<'a>(
+        &self,
+        level: log::Level,
+        base_level: LevelFilter,
+        mut records: impl Iterator<Item = LogRecordRef<'a>>,
+    ) -> bool {
+        if level <= self.default_level.unwrap_or(base_level) {
+            return true;
+        }
+        records.any(|(key, value)| {
+            self.directives
+                .iter()
+                .filter(|directive| level <= directive.level && **key == directive.key)
+                .any(|directive| value.to_string() == directive.value)
+        })
+    }
+}
+
+/// A parsed set of per-target level directives, see [`ContextLogger::with_filter`].
+///
+/// [`ContextLogger::with_filter`]: crate::ContextLogger::with_filter
+#[derive(Debug)]
+pub struct TargetFilter {
+    default_level: LevelFilter,
+    /// Directives sorted by target length, the longest first.
+    directives: Vec<(String, LevelFilter)>,
+}
+
+impl TargetFilter {
+    /// Parses a comma-separated list of `target=level` directives and an optional bare
+    /// default level in the `env_logger` syntax, failing on the first invalid directive.
+    pub fn parse(spec: &str) -> Result<Self, DirectiveError> {
+        Self::parse_with(spec, |part| Err(DirectiveError::new(part)))
+    }
+
+    /// Parses the directives like [`Self::parse`], ignoring invalid directives.
+    pub fn parse_lossy(spec: &str) -> Self {
+        let Ok(filter) = Self::parse_with(spec, |_| Ok::<_, Infallible>(()));
+        filter
+    }
+
+    /// Parses the directives, passing the invalid ones to the given closure.
+    fn parse_with<E>(
+        spec: &str,
+        mut invalid: impl FnMut(&str) -> Result<(), E>,
+    ) -> Result<Self, E> {
+        let mut default_level = LevelFilter::Trace;
+        let mut directives = Vec::new();
+        for part in spec
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            let parsed = match part.split_once('=') {
+                Some((target, level)) if !target.trim().is_empty() => level
+                    .trim()
+                    .parse()
+                    .map(|level| directives.push((target.trim().to_owned(), level)))
+                    .is_ok(),
+                Some(_) => false,
+                None => part.parse().map(|level| default_level = level).is_ok(),
+            };
+            if !parsed {
+                invalid(part)?;
+            }
+        }
+        directives.sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+        Ok(Self {
+            default_level,
+            directives,
+        })
+    }
+
+    /// Returns `true` if records with the given metadata are enabled.
+    ///
+    /// The directive with the longest matching target prefix wins, records that do not
+    /// match any directive are compared with the default level.
+    pub fn enabled(&self, metadata: &log::Metadata) -> bool {
+        let level = self
+            .directives
+            .iter()
+            .find(|(target, _)| metadata.target().starts_with(target.as_str()))
+            .map_or(self.default_level, |(_, level)| *level);
+        metadata.level() <= level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use log::LevelFilter;
+    use pretty_assertions::assert_eq;
+
+    use super::{ContextDirectives, TargetFilter};
+
+    #[test]
+    fn test_parse_directives() {
+        let directives =
+            ContextDirectives::parse_lossy("info, user_id=12345:debug,tenant=acme,bogus=1:loud,=1");
+
+        assert_eq!(directives.default_level, Some(LevelFilter::Info));
+        let parsed = directives
+            .directives
+            .iter()
+            .map(|directive| (&*directive.key, &*directive.value, directive.level))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            parsed,
+            [
+                ("user_id", "12345", LevelFilter::Debug),
+                ("tenant", "acme", LevelFilter::Trace),
+            ]
+        );
+        assert_eq!(directives.max_level(), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn test_parse_directives_strict() {
+        let error = ContextDirectives::parse("info,bogus=1:loud,=1").unwrap_err();
+        assert_eq!(error.directive(), "bogus=1:loud");
+        assert_eq!(error.to_string(), "invalid directive 'bogus=1:loud'");
+
+        let directives = ContextDirectives::parse("user_id=12345:debug").unwrap();
+        assert_eq!(directives.max_level(), LevelFilter::Debug);
+    }
+
+    #[test]
+    fn test_parse_target_filter() {
+        let filter = TargetFilter::parse_lossy("warn, app=info,app::db=debug,bogus=loud");
+
+        assert_eq!(filter.default_level, LevelFilter::Warn);
+        assert_eq!(
+            filter.directives,
+            [
+                ("app::db".to_owned(), LevelFilter::Debug),
+                ("app".to_owned(), LevelFilter::Info),
+            ]
+        );
+    }
+    #[test]
+    fn test_parse_target_filter_strict() {
+        let error = TargetFilter::parse("warn,app=loud,=info").unwrap_err();
+        assert_eq!(error.directive(), "app=loud");
+
+        let filter = TargetFilter::parse("warn,app=info").unwrap();
+        assert_eq!(filter.default_level, LevelFilter::Warn);
+    }
+}
@@ -0,0 +1,119 @@
+//! Context propagation across channels.
+
+use std::sync::mpsc;
+
+use crate::scope::{CapturedContext, ScopeGroup};
+
+/// A message paired with the log context of the code that produced it.
+///
+/// Producers wrap messages with [`Self::new`] (or [`Self::send_with_context`]),
+/// which captures the current scope stack. Consumers unwrap them with
+/// [`Self::enter`] (or [`Self::recv_and_enter`]) and process the message while the
+/// producer's frames are active, so records logged on the worker thread carry
+/// the producer's properties.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::mpsc;
+///
+/// use context_logger::{ContextEnvelope, LogContext, LogContextExt as _};
+///
+/// let (sender, receiver) = mpsc::channel();
+/// LogContext::new()
+///     .with_local_record("request_id", "req-123")
+///     .in_scope(|| ContextEnvelope::send_with_context(&sender, 42))
+///     .unwrap();
+///
+/// std::thread::spawn(move || {
+///     let (message, _guard) = ContextEnvelope::recv_and_enter(&receiver).unwrap();
+///     log::info!("Processing message {message}");
+/// })
+/// .join()
+/// .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContextEnvelope<T> {
+    message: T,
+    context: CapturedContext,
+}
+
+impl<T> ContextEnvelope<T> {
+    /// Wraps the message together with the current thread's scope stack.
+    #[must_use]
+    pub fn new(message: T) -> Self {
+        Self::with_context(message, CapturedContext::with_frames())
+    }
+
+    /// Wraps the message together with the given captured context.
+    #[must_use]
+    pub const fn with_context(message: T, context: CapturedContext) -> Self {
+        Self { message, context }
+    }
+
+    /// Returns a reference to the wrapped message.
+    #[must_use]
+    pub const fn message(&self) -> &T {
+        &self.message
+    }
+
+    /// Returns the context captured alongside the message.
+    #[must_use]
+    pub const fn context(&self) -> &CapturedContext {
+        &self.context
+    }
+
+    /// Splits the envelope into the message and the captured context.
+    #[must_use]
+    pub fn into_parts(self) -> (T, CapturedContext) {
+        (self.message, self.context)
+    }
+
+    /// Enters the captured context and returns the message along with the guard.
+    ///
+    /// The producer's frames stay active until the returned guard is dropped.
+    #[must_use]
+    pub fn enter(self) -> (T, ScopeGroup) {
+        (self.message, self.context.enter_all())
+    }
+
+    /// Runs the closure with the message while the captured context is active.
+    pub fn in_context<R>(self, f: impl FnOnce(T) -> R) -> R {
+        let (message, _guard) = self.enter();
+        f(message)
+    }
+
+    /// Wraps the message with the current context and sends it to the channel.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the unsent envelope if the receiving half of the
+    /// channel has been dropped.
+    pub fn send_with_context(
+        sender: &mpsc::Sender<Self>,
+        message: T,
+    ) -> Result<(), mpsc::SendError<Self>> {
+        sender.send(Self::new(message))
+    }
+
+    /// Receives the next envelope from the channel and enters its context.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sending half of the channel has been dropped
+    /// and no messages are left.
+    pub fn recv_and_enter(
+        receiver: &mpsc::Receiver<Self>,
+    ) -> Result<(T, ScopeGroup), mpsc::RecvError> {
+        receiver.recv().map(Self::enter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use static_assertions::assert_impl_all;
+
+    use super::*;
+
+    assert_impl_all!(ContextEnvelope<String>: Send, Sync);
+}
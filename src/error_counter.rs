@@ -0,0 +1,113 @@
+//! Counting of error records per context value.
+
+use std::{
+    borrow::Cow,
+    collections::{HashMap, hash_map::Entry},
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use crate::records::LogRecordRef;
+
+/// Counts error and warning records per value of a context key.
+///
+/// Every `error` or `warn` record emitted while a default or scope record with the
+/// counted key is active increments the counter of the record's value, e.g. the number
+/// of errors per `tenant_id`. Records without the key are not counted.
+///
+/// The number of distinct values is bounded: once `max_values` values are tracked,
+/// records with new values are only counted in [`Self::overflow`].
+///
+/// The counter is cheaply cloneable, clones share the same counts. Keep a clone to read
+/// the counts after passing the counter to [`ContextLogger::with_error_counter`].
+///
+/// [`ContextLogger::with_error_counter`]: crate::ContextLogger::with_error_counter
+#[derive(Debug, Clone)]
+pub struct ErrorCounter(Arc<ErrorCounterInner>);
+
+#[derive(Debug)]
+struct ErrorCounterInner {
+    key: Cow<'static, str>,
+    max_values: usize,
+    counts: Mutex<HashMap<String, u64>>,
+    overflow: AtomicU64,
+}
+
+impl ErrorCounter {
+    /// Creates a counter for the given key that tracks at most `max_values` distinct values.
+    #[must_use]
+    pub fn new(key: impl Into<Cow<'static, str>>, max_values: usize) -> Self {
+        Self(Arc::new(ErrorCounterInner {
+            key: key.into(),
+            max_values,
+            counts: Mutex::default(),
+            overflow: AtomicU64::new(0),
+        }))
+    }
+
+    /// Returns the key this counter groups records by.
+    #[must_use]
+    pub fn key(&self) -> &str {
+        &self.0.key
+    }
+
+    /// Returns the number of counted records with the given value of the key.
+    #[must_use]
+    pub fn get(&self, value: &str) -> u64 {
+        self.lock().get(value).copied().unwrap_or_default()
+    }
+
+    /// Returns a snapshot of the counts of all tracked values.
+    #[must_use]
+    pub fn counts(&self) -> HashMap<String, u64> {
+        self.lock().clone()
+    }
+
+    /// Returns the number of counted records whose value was not tracked
+    /// because the limit of distinct values was reached.
+    #[must_use]
+    pub fn overflow(&self) -> u64 {
+        self.0.overflow.load(Ordering::Relaxed)
+    }
+
+    /// Counts the record if its level and context match.
+    pub(crate) fn count<'a>(
+        &self,
+        level: log::Level,
+        records: impl Iterator<Item = LogRecordRef<'a>>,
+    ) {
+        if level > log::Level::Warn {
+            return;
+        }
+        // The last occurrence of the key is the one "last write wins" consumers observe.
+        let Some((_, value)) = records.filter(|(key, _)| **key == self.0.key).last() else {
+            return;
+        };
+
+        let value = value.to_string();
+        let tracked = {
+            let mut counts = self.lock();
+            let tracked_values = counts.len();
+            match counts.entry(value) {
+                Entry::Occupied(mut entry) => {
+                    *entry.get_mut() += 1;
+                    true
+                }
+                Entry::Vacant(entry) if tracked_values < self.0.max_values => {
+                    entry.insert(1);
+                    true
+                }
+                Entry::Vacant(_) => false,
+            }
+        };
+        if !tracked {
+            self.0.overflow.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, u64>> {
+        self.0.counts.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,111 @@
+//! Adapters for inner loggers that can report failures.
+//!
+//! [`log::Log::log`] cannot return errors, so loggers that drop records internally,
+//! e.g. because a channel to a background writer is full, usually do it silently.
+//! Such loggers can implement [`FallibleLog`] instead and be wrapped with
+//! [`FallibleLog::on_error`] to surface every failure to a callback.
+
+/// A logger that reports failures to deliver a record.
+///
+/// A fallible logger is turned into a regular [`log::Log`] by [`Self::on_error`], which
+/// can then be used as the inner logger of a [`ContextLogger`](crate::ContextLogger).
+pub trait FallibleLog: Send + Sync {
+    /// The error returned when a record cannot be delivered.
+    type Error;
+
+    /// Determines if a log message with the specified metadata would be logged.
+    ///
+    /// See [`log::Log::enabled`].
+    fn enabled(&self, metadata: &log::Metadata) -> bool;
+
+    /// Logs the record, returning an error if it could not be delivered.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the record was dropped.
+    fn try_log(&self, record: &log::Record) -> Result<(), Self::Error>;
+
+    /// Flushes any buffered records.
+    fn flush(&self);
+
+    /// Wraps this logger into a [`log::Log`] that passes every failure to the given callback.
+    ///
+    /// The callback is called with the record that could not be delivered, already
+    /// enriched with the context records, so it must not log through the global logger.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::mpsc::{SyncSender, TrySendError, sync_channel};
+    ///
+    /// use context_logger::{ContextLogger, fallible::FallibleLog};
+    ///
+    /// struct ChannelLogger(SyncSender<String>);
+    ///
+    /// impl FallibleLog for ChannelLogger {
+    ///     type Error = TrySendError<String>;
+    ///
+    ///     fn enabled(&self, _metadata: &log::Metadata) -> bool {
+    ///         true
+    ///     }
+    ///
+    ///     fn try_log(&self, record: &log::Record) -> Result<(), Self::Error> {
+    ///         self.0.try_send(record.args().to_string())
+    ///     }
+    ///
+    ///     fn flush(&self) {}
+    /// }
+    ///
+    /// let (sender, _receiver) = sync_channel(1024);
+    /// let logger = ContextLogger::new(ChannelLogger(sender).on_error(|record, error| {
+    ///     eprintln!("dropped {} record: {error}", record.level());
+    /// }));
+    /// ```
+    fn on_error<F>(self, on_error: F) -> OnError<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&log::Record, &Self::Error) + Send + Sync,
+    {
+        OnError {
+            inner: self,
+            on_error,
+        }
+    }
+}
+
+/// A [`log::Log`] adapter that passes failures of a [`FallibleLog`] to a callback.
+///
+/// Created by [`FallibleLog::on_error`].
+#[derive(Debug)]
+pub struct OnError<L, F> {
+    inner: L,
+    on_error: F,
+}
+
+impl<L, F> OnError<L, F> {
+    /// Returns a reference to the wrapped logger.
+    #[must_use]
+    pub const fn get_ref(&self) -> &L {
+        &self.inner
+    }
+}
+
+impl<L, F> log::Log for OnError<L, F>
+where
+    L: FallibleLog,
+    F: Fn(&log::Record, &L::Error) + Send + Sync,
+{
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if let Err(error) = self.inner.try_log(record) {
+            (self.on_error)(record, &error);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
@@ -0,0 +1,57 @@
+//! A background thread that periodically flushes the global logger.
+
+use std::{
+    sync::{Mutex, mpsc},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+/// The shortest interval between flushes, shorter intervals are raised to it.
+const MIN_INTERVAL: Duration = Duration::from_millis(1);
+
+/// The slot of the flush thread, shared between a logger and its handles.
+///
+/// See [`ContextLogger::with_flush_interval`](crate::ContextLogger::with_flush_interval).
+#[derive(Debug, Default)]
+pub struct PeriodicFlush(Mutex<Option<FlushThread>>);
+
+#[derive(Debug)]
+struct FlushThread {
+    stop: mpsc::Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+impl PeriodicFlush {
+    /// Spawns a thread that flushes the global logger every `interval` until stopped.
+    pub fn start(&self, interval: Duration) {
+        // A zero timeout would make the thread spin instead of waiting.
+        let interval = interval.max(MIN_INTERVAL);
+        let (stop, stopped) = mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("context-logger-flush".to_owned())
+            .spawn(move || {
+                // Any message or a dropped sender is the shutdown sentinel.
+                while stopped.recv_timeout(interval) == Err(mpsc::RecvTimeoutError::Timeout) {
+                    log::logger().flush();
+                }
+            });
+        // Periodic flushing is best-effort, so a failure to spawn the thread is ignored.
+        if let Ok(thread) = spawned {
+            if let Ok(mut slot) = self.0.lock() {
+                *slot = Some(FlushThread { stop, thread });
+            }
+        }
+    }
+
+    /// Signals the thread to stop and waits for it to finish.
+    ///
+    /// Returns `false` if no thread was running.
+    pub fn stop(&self) -> bool {
+        let Some(flush_thread) = self.0.lock().ok().and_then(|mut slot| slot.take()) else {
+            return false;
+        };
+        let _ = flush_thread.stop.send(());
+        let _ = flush_thread.thread.join();
+        true
+    }
+}
@@ -0,0 +1,198 @@
+//! Formatting helpers for text-based loggers.
+//!
+//! Many text loggers, such as [`env_logger`] with its default format, do not render
+//! structured key-values. The helpers in this module can be used inside a custom
+//! format function to render the records attached by the [`ContextLogger`](crate::ContextLogger).
+//!
+//! [`env_logger`]: https://docs.rs/env_logger/latest/env_logger
+
+use std::io::Write;
+
+const KEY_STYLE: &str = "\x1b[2m";
+const VALUE_STYLE: &str = "\x1b[1m";
+const RESET_STYLE: &str = "\x1b[0m";
+
+/// Options for [`write_kv`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KvFormat {
+    color: bool,
+}
+
+impl KvFormat {
+    /// Creates options with ANSI coloring enabled unless the
+    /// [`NO_COLOR`](https://no-color.org) environment variable is set to a non-empty value.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            color: color_enabled(std::env::var_os("NO_COLOR")),
+        }
+    }
+
+    /// Explicitly enables or disables ANSI coloring.
+    #[must_use]
+    pub const fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+impl Default for KvFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes the key-values of the given record as ` key=value` pairs.
+///
+/// With coloring enabled, keys are rendered dim and values bright.
+///
+/// # Example
+///
+/// ```
+/// use std::io::Write as _;
+///
+/// use context_logger::{ContextLogger, fmt::{KvFormat, write_kv}};
+///
+/// let env_logger = env_logger::builder()
+///     .format(|buf, record| {
+///         write!(buf, "[{}] {}", record.level(), record.args())?;
+///         write_kv(record, buf, KvFormat::new())?;
+///         writeln!(buf)
+///     })
+///     .build();
+/// let logger = ContextLogger::new(env_logger);
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if writing to the writer fails.
+pub fn write_kv<W>(record: &log::Record, writer: &mut W, format: KvFormat) -> std::io::Result<()>
+where
+    W: Write + ?Sized,
+{
+    struct WriteKv<'w, W: ?Sized> {
+        writer: &'w mut W,
+        format: KvFormat,
+    }
+
+    impl<'kvs, W> log::kv::VisitSource<'kvs> for WriteKv<'_, W>
+    where
+        W: Write + ?Sized,
+    {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            let result = if self.format.color {
+                write!(
+                    self.writer,
+                    " {KEY_STYLE}{key}{RESET_STYLE}={VALUE_STYLE}{value}{RESET_STYLE}"
+                )
+            } else {
+                write!(self.writer, " {key}={value}")
+            };
+            result.map_err(log::kv::Error::boxed)
+        }
+    }
+
+    record
+        .key_values()
+        .visit(&mut WriteKv { writer, format })
+        .map_err(std::io::Error::other)
+}
+
+/// Appends the key-values of the given record, including the injected context records,
+/// as plain ` key=value` pairs.
+///
+/// This is a shorthand for [`write_kv`] without coloring.
+///
+/// # Errors
+///
+/// Returns an error if writing to the writer fails.
+pub fn append_context<W>(record: &log::Record, writer: &mut W) -> std::io::Result<()>
+where
+    W: Write + ?Sized,
+{
+    write_kv(record, writer, KvFormat::new().with_color(false))
+}
+
+/// A ready-made format function for [`env_logger`] that renders context records.
+///
+/// Each record is written as `[LEVEL target] message key=value ...` on its own line.
+/// Pass it to `env_logger::Builder::format` to make sure the records attached by
+/// the [`ContextLogger`](crate::ContextLogger) show up in the output.
+///
+/// # Example
+///
+/// ```
+/// use context_logger::{ContextLogger, fmt::env_logger_format};
+///
+/// let env_logger = env_logger::builder().format(env_logger_format).build();
+/// let logger = ContextLogger::new(env_logger);
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if writing to the writer fails.
+///
+/// [`env_logger`]: https://docs.rs/env_logger/latest/env_logger
+pub fn env_logger_format<W>(writer: &mut W, record: &log::Record) -> std::io::Result<()>
+where
+    W: Write + ?Sized,
+{
+    write!(
+        writer,
+        "[{} {}] {}",
+        record.level(),
+        record.target(),
+        record.args()
+    )?;
+    append_context(record, writer)?;
+    writeln!(writer)
+}
+
+fn color_enabled(no_color: Option<std::ffi::OsString>) -> bool {
+    no_color.is_none_or(|value| value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{KvFormat, color_enabled, write_kv};
+
+    fn render(format: KvFormat) -> String {
+        let key_values = [("user_id", 42)];
+        let record = log::Record::builder()
+            .args(format_args!("message"))
+            .key_values(&key_values)
+            .build();
+
+        let mut buf = Vec::new();
+        write_kv(&record, &mut buf, format).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_write_kv_plain() {
+        assert_eq!(render(KvFormat::new().with_color(false)), " user_id=42");
+    }
+
+    #[test]
+    fn test_write_kv_colored() {
+        assert_eq!(
+            render(KvFormat::new().with_color(true)),
+            " \x1b[2muser_id\x1b[0m=\x1b[1m42\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_no_color_env() {
+        assert!(color_enabled(None));
+        assert!(color_enabled(Some(OsString::new())));
+        assert!(!color_enabled(Some(OsString::from("1"))));
+    }
+}
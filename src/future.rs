@@ -11,7 +11,15 @@ use crate::{LogContext, scope::LogScope};
 /// This trait adds ability to attach a [`LogContext`] for any [`Future`],
 /// ensuring that logs emitted during the future's execution will include
 /// the contextual properties even if the future is polled across different threads.
-pub trait FutureExt: Sized + crate::private::Sealed {
+///
+/// Streams are not futures and cannot be instrumented as a whole. To attach a context
+/// to the futures produced per stream item, use [`map_in_log_context`].
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a future, so a log context cannot be attached to it",
+    label = "not a future",
+    note = "streams cannot be instrumented as a whole, combine `StreamExt::then` with `context_logger::future::map_in_log_context` to attach a context to each item future"
+)]
+pub trait FutureExt: Sized + private::Sealed {
     /// Attaches a log context to this future.
     ///
     /// The attached [context](LogContext) will be activated every time the instrumented
@@ -40,7 +48,78 @@ pub trait FutureExt: Sized + crate::private::Sealed {
     ///     .await;
     /// }
     /// ```
+    ///
+    /// # Concurrent futures
+    ///
+    /// The context is active only while the instrumented future itself is polled. Futures
+    /// raced with `tokio::select!` or joined together therefore never observe the
+    /// context of each other, and no frame is left on the stack once a branch returns
+    /// `Pending` or is dropped. Inherited records of the scope the future is polled in are
+    /// visible during that poll only, so they are not carried over to later polls from
+    /// a different scope.
+    ///
+    /// ```
+    /// use context_logger::{FutureExt, LogContext};
+    ///
+    /// # async fn example() {
+    /// tokio::select! {
+    ///     _ = async { log::info!("Reading from cache") } // Includes only source=cache
+    ///         .in_log_context(LogContext::new().with_local_record("source", "cache")) => {}
+    ///     _ = async { log::info!("Reading from database") } // Includes only source=db
+    ///         .in_log_context(LogContext::new().with_local_record("source", "db")) => {}
+    /// }
+    /// # }
+    /// ```
     fn in_log_context(self, context: LogContext) -> LogContextFuture<Self>;
+
+    /// Attaches a lazily built log context to this future.
+    ///
+    /// Unlike [`Self::in_log_context`], the context is not built until the future
+    /// is polled for the first time. The `build` closure is invoked exactly once,
+    /// and the resulting context is reused for all subsequent polls. If the future is
+    /// dropped before being polled, the closure is never called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::{LogContext, FutureExt};
+    /// use log::info;
+    ///
+    /// async fn handle_request(request_id: u64) {
+    ///     async {
+    ///         info!("Handling request"); // Will include request_id
+    ///     }
+    ///     .in_log_context_with(move || LogContext::new().with_local_record("request_id", request_id))
+    ///     .await;
+    /// }
+    /// ```
+    fn in_log_context_with<B>(self, build: B) -> LazyLogContextFuture<Self, B>
+    where
+        B: FnOnce() -> LogContext;
+
+    /// Attaches the currently active log context to this future.
+    ///
+    /// The context is captured when this method is called and activated every time the
+    /// instrumented future is polled, so the future keeps the context of its creator even
+    /// if it is spawned and polled elsewhere. If there is no active context, an empty
+    /// context is attached, which costs no allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::{FutureExt, LogContext, LogContextExt as _};
+    /// use log::info;
+    ///
+    /// # async fn example() {
+    /// let task = LogContext::new()
+    ///     .with_local_record("request_id", 42)
+    ///     .in_scope(|| async { info!("Background work") }.in_current_log_context());
+    /// tokio::spawn(task).await.unwrap(); // Logs with request_id
+    /// # }
+    /// ```
+    fn in_current_log_context(self) -> LogContextFuture<Self> {
+        self.in_log_context(LogScope::current_context())
+    }
 }
 
 impl<F> FutureExt for F
@@ -53,6 +132,59 @@ where
             log_context: Some(context),
         }
     }
+
+    fn in_log_context_with<B>(self, build: B) -> LazyLogContextFuture<Self, B>
+    where
+        B: FnOnce() -> LogContext,
+    {
+        LazyLogContextFuture {
+            inner: self,
+            state: LazyLogContext::Builder(build),
+        }
+    }
+}
+
+/// Wraps a future-producing closure so every produced future runs in the given context.
+///
+/// This is intended for stream combinators that produce a future per item, such as
+/// `StreamExt::then` of the [`futures`] crate. Each produced future gets its own clone
+/// of the context, which is active only while that future is polled. This differs from
+/// instrumenting the whole stream: code of the stream itself, e.g. the source of the
+/// items, runs without the context, and the per-item futures keep it even when they
+/// are driven concurrently, e.g. by `StreamExt::buffer_unordered`.
+///
+/// Records that differ per item can be added by instrumenting the future inside the
+/// closure with [`FutureExt::in_log_context`] as usual. The nested context follows the
+/// regular scoping rules, so only inherited records of the shared context remain visible.
+///
+/// # Examples
+///
+/// ```
+/// use context_logger::{LogContext, future::map_in_log_context};
+/// use futures_util::{StreamExt as _, stream};
+///
+/// # async fn example() {
+/// let context = LogContext::new().with_local_record("batch_id", 7);
+/// let results = stream::iter([1, 2, 3])
+///     .then(map_in_log_context(context, |item| async move {
+///         log::info!("Processing item {item}"); // Will include batch_id
+///         item * 2
+///     }))
+///     .collect::<Vec<_>>()
+///     .await;
+/// # }
+/// ```
+///
+/// [`futures`]: https://docs.rs/futures
+pub fn map_in_log_context<T, F, Fut>(
+    context: LogContext,
+    mut f: F,
+) -> impl FnMut(T) -> LogContextFuture<Fut>
+where
+    F: FnMut(T) -> Fut,
+    Fut: Future,
+{
+    move |item| f(item).in_log_context(context.clone())
 }
 
 /// A future with an attached logging context.
@@ -70,6 +202,34 @@ pub struct LogContextFuture<F> {
     log_context: Option<LogContext>,
 }
 
+impl<F> LogContextFuture<F> {
+    /// Returns a reference to the wrapped future.
+    #[must_use]
+    pub const fn get_ref(&self) -> &F {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped future.
+    pub const fn get_mut(&mut self) -> &mut F {
+        &mut self.inner
+    }
+
+    /// Returns a pinned mutable reference to the wrapped future.
+    #[must_use]
+    pub fn get_pin_mut(self: std::pin::Pin<&mut Self>) -> std::pin::Pin<&mut F> {
+        self.project().inner
+    }
+
+    /// Consumes this adapter, returning the wrapped future.
+    ///
+    /// The attached log context is dropped. It is safe to call this method after the
+    /// future has been partially polled, since the context is only active during `poll`.
+    #[must_use]
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+}
+
 impl<F> Future for LogContextFuture<F>
 where
     F: Future,
@@ -84,28 +244,103 @@ where
             .take()
             .expect("An attempt to poll panicked future");
 
-        let guard = LogScope::enter(log_context);
-        let result = this.inner.poll(cx);
-        this.log_context.replace(guard.exit());
+        let (log_context, result) = LogScope::reenterable(log_context, || this.inner.poll(cx));
+        this.log_context.replace(log_context);
+
+        result
+    }
+}
+
+/// A future with an attached logging context that is built on the first poll.
+///
+/// This type is created by the [`FutureExt::in_log_context_with`].
+///
+/// # Note
+///
+/// If the wrapped future will panic, the next `poll` invocation will panic unconditionally.
+#[pin_project]
+pub struct LazyLogContextFuture<F, B> {
+    #[pin]
+    inner: F,
+    state: LazyLogContext<B>,
+}
+
+enum LazyLogContext<B> {
+    Builder(B),
+    Context(LogContext),
+    Taken,
+}
+
+impl<F, B> Future for LazyLogContextFuture<F, B>
+where
+    F: Future,
+    B: FnOnce() -> LogContext,
+{
+    type Output = F::Output;
+
+    fn poll(self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        let log_context = match std::mem::replace(this.state, LazyLogContext::Taken) {
+            LazyLogContext::Builder(build) => build(),
+            LazyLogContext::Context(context) => context,
+            LazyLogContext::Taken => panic!("An attempt to poll panicked future"),
+        };
+
+        let (log_context, result) = LogScope::reenterable(log_context, || this.inner.poll(cx));
+        *this.state = LazyLogContext::Context(log_context);
 
         result
     }
 }
 
+impl<F: std::fmt::Debug, B> std::fmt::Debug for LazyLogContextFuture<F, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let log_context = match &self.state {
+            LazyLogContext::Context(context) => Some(context),
+            LazyLogContext::Builder(_) | LazyLogContext::Taken => None,
+        };
+
+        f.debug_struct("LazyLogContextFuture")
+            .field("inner", &self.inner)
+            .field("log_context", &log_context)
+            .finish()
+    }
+}
+
+mod private {
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` is not a future, so a log context cannot be attached to it",
+        label = "not a future",
+        note = "streams cannot be instrumented as a whole, combine `StreamExt::then` with `context_logger::future::map_in_log_context` to attach a context to each item future"
+    )]
+    pub trait Sealed {}
+
+    impl<F: Future> Sealed for F {}
+}
+
 #[cfg(test)]
 mod tests {
-    use std::panic::AssertUnwindSafe;
+    use std::{
+        panic::AssertUnwindSafe,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
 
     use futures_util::FutureExt as _;
     use pretty_assertions::assert_eq;
 
-    use super::FutureExt;
-    use crate::{LogContext, LogValue, scope::stack::SCOPE_STACK};
+    use futures_util::StreamExt as _;
+
+    use super::{FutureExt, map_in_log_context};
+    use crate::{
+        LogContext, LogContextExt as _, LogScope, LogValue,
+        scope::stack::{SCOPE_STACK, ScopeStack},
+    };
 
     fn find_local_value(key: &str) -> Option<String> {
         SCOPE_STACK.with(|stack| {
             let frame = stack.top()?;
-            frame.0.local.find(key).map(ToString::to_string)
+            frame.context.local.find(key).map(ToString::to_string)
         })
     }
 
@@ -131,6 +366,134 @@ mod tests {
         assert_eq!(find_local_value("answer"), None);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_in_current_log_context() {
+        let task = LogContext::new()
+            .with_inherited_record("request_id", "req-1")
+            .with_local_record("user_id", 42)
+            .in_scope(|| {
+                async {
+                    tokio::task::yield_now().await;
+                    LogScope::current_context()
+                }
+                .in_current_log_context()
+            });
+
+        let context = tokio::spawn(task).await.unwrap();
+        assert_eq!(context.inherited["request_id"].to_string(), "req-1");
+        assert_eq!(context.local["user_id"].to_string(), "42");
+
+        let context = async { LogScope::current_context() }
+            .in_current_log_context()
+            .await;
+        assert!(context.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_map_in_log_context_per_item() {
+        let context = LogContext::new().with_inherited_record("batch_id", 7);
+
+        let results = futures_util::stream::iter(0..4)
+            .map(|item| {
+                // The stream itself runs without the context.
+                assert!(LogScope::current_context().is_empty());
+                item
+            })
+            .then(map_in_log_context(context, |item| {
+                async move {
+                    tokio::task::yield_now().await;
+                    let current = LogScope::current_context();
+                    (
+                        current.inherited["batch_id"].to_string(),
+                        current.local["item"].to_string(),
+                    )
+                }
+                .in_log_context(LogContext::new().with_local_record("item", item))
+            }))
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(
+            results,
+            (0..4)
+                .map(|item| ("7".to_owned(), item.to_string()))
+                .collect::<Vec<_>>()
+        );
+        assert!(LogScope::current_context().is_empty());
+    }
+
+    async fn select_branch(branch: &'static str, polls: usize) -> &'static str {
+        for _ in 0..polls {
+            assert_eq!(find_local_value("branch"), Some(branch.to_owned()));
+            let current = LogScope::current_context();
+            assert_eq!(current.inherited["request_id"].to_string(), "req-1");
+            assert_eq!(current.local.iter().count(), 1);
+            tokio::task::yield_now().await;
+        }
+        branch
+    }
+
+    #[tokio::test]
+    async fn test_select_branches_are_isolated() {
+        async {
+            let winner = tokio::select! {
+                branch = select_branch("fast", 2)
+                    .in_log_context(LogContext::new().with_local_record("branch", "fast")) => branch,
+                branch = select_branch("slow", 100)
+                    .in_log_context(LogContext::new().with_local_record("branch", "slow")) => branch,
+            };
+            assert_eq!(winner, "fast");
+            // Only the outer scope remains active.
+            assert_eq!(find_local_value("branch"), None);
+            assert_eq!(SCOPE_STACK.with(ScopeStack::len), 1);
+
+            let mut slow = std::pin::pin!(
+                select_branch("slow", 5)
+                    .in_log_context(LogContext::new().with_local_record("branch", "slow"))
+            );
+            let mut ticks = 0;
+            let winner = loop {
+                tokio::select! {
+                    branch = &mut slow => break branch,
+                    tick = async { find_local_value("branch") }
+                        .in_log_context(LogContext::new().with_local_record("branch", "tick")) => {
+                        assert_eq!(tick.as_deref(), Some("tick"));
+                        ticks += 1;
+                    }
+                }
+            };
+            assert_eq!(winner, "slow");
+            assert!(ticks > 0);
+        }
+        .in_log_context(LogContext::new().with_inherited_record("request_id", "req-1"))
+        .await;
+
+        assert_eq!(SCOPE_STACK.with(ScopeStack::len), 0);
+    }
+
+    #[tokio::test]
+    async fn test_outer_inherited_records_are_not_retained_between_polls() {
+        let mut future = std::pin::pin!(
+            async {
+                let mut rounds = Vec::new();
+                for _ in 0..2 {
+                    let current = LogScope::current_context();
+                    rounds.push(current.inherited["round"].to_string());
+                    tokio::task::yield_now().await;
+                }
+                rounds
+            }
+            .in_log_context(LogContext::new().with_local_record("branch", "a"))
+        );
+
+        for round in 1..=2 {
+            async { assert!(futures_util::poll!(&mut future).is_pending()) }
+                .in_log_context(LogContext::new().with_inherited_record("round", round))
+                .await;
+        }
+        assert_eq!(future.await, ["1", "2"]);
+    }
+
     #[tokio::test]
     async fn test_future_with_context() {
         let context = LogContext::new().with_local_record("answer", 42);
@@ -208,4 +571,60 @@ mod tests {
             result.unwrap();
         }
     }
+
+    #[tokio::test]
+    async fn test_future_accessors() {
+        let mut future = std::future::ready(42).in_log_context(LogContext::new());
+
+        assert_eq!(future.get_ref().clone().await, 42);
+        assert_eq!(future.get_mut().clone().await, 42);
+        assert_eq!(std::pin::Pin::new(&mut future).get_pin_mut().await, 42);
+    }
+
+    #[tokio::test]
+    async fn test_into_inner_after_partial_poll() {
+        let mut future = async {
+            tokio::task::yield_now().await;
+            find_local_value("answer")
+        }
+        .boxed()
+        .in_log_context(LogContext::new().with_local_record("answer", 42));
+        assert!(futures_util::poll!(&mut future).is_pending());
+
+        let inner = future.into_inner();
+        assert_eq!(find_local_value("answer"), None);
+        assert_eq!(inner.await, None);
+    }
+
+    #[tokio::test]
+    async fn test_lazy_context_built_once_on_first_poll() {
+        let calls = AtomicUsize::new(0);
+
+        async {
+            assert_eq!(find_local_value("answer"), Some("42".to_string()));
+            tokio::task::yield_now().await;
+            assert_eq!(find_local_value("answer"), Some("42".to_string()));
+        }
+        .in_log_context_with(|| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            LogContext::new().with_local_record("answer", 42)
+        })
+        .await;
+
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert_eq!(find_local_value("answer"), None);
+    }
+
+    #[test]
+    fn test_lazy_context_not_built_when_dropped_before_poll() {
+        let calls = AtomicUsize::new(0);
+
+        let future = async {}.in_log_context_with(|| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            LogContext::new()
+        });
+        drop(future);
+
+        assert_eq!(calls.load(Ordering::Relaxed), 0);
+    }
 }
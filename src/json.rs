@@ -0,0 +1,171 @@
+//! A minimal JSON-lines logger.
+
+use std::{
+    io::Write,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::ContextLogger;
+
+/// Writes every record as a single JSON object followed by a newline.
+struct JsonLinesLogger<W> {
+    level: log::LevelFilter,
+    writer: Mutex<W>,
+}
+
+impl<W> JsonLinesLogger<W> {
+    const fn new(level: log::LevelFilter, writer: W) -> Self {
+        Self {
+            level,
+            writer: Mutex::new(writer),
+        }
+    }
+}
+
+impl<W: Write + Send> log::Log for JsonLinesLogger<W> {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &log::Record) {
+        struct Collect(serde_json::Map<String, serde_json::Value>);
+
+        impl<'kvs> log::kv::VisitSource<'kvs> for Collect {
+            fn visit_pair(
+                &mut self,
+                key: log::kv::Key<'kvs>,
+                value: log::kv::Value<'kvs>,
+            ) -> Result<(), log::kv::Error> {
+                let value = serde_json::to_value(value).map_err(log::kv::Error::boxed)?;
+                self.0.insert(key.to_string(), value);
+                Ok(())
+            }
+        }
+
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let mut fields = Collect(serde_json::Map::new());
+        // Rendering can only fail inside user provided values, the rest of the fields
+        // are still written.
+        let _ = record.key_values().visit(&mut fields);
+        let mut fields = fields.0;
+        // The record fields are inserted last, so context records cannot overwrite them.
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| {
+                u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+            });
+        fields.insert("timestamp".to_owned(), timestamp.into());
+        fields.insert("level".to_owned(), record.level().as_str().into());
+        fields.insert("target".to_owned(), record.target().into());
+        fields.insert("message".to_owned(), record.args().to_string().into());
+
+        let Ok(mut writer) = self.writer.lock() else {
+            return;
+        };
+        // There is nowhere to report a failed write to.
+        let _ = serde_json::to_writer(&mut *writer, &fields);
+        let _ = writer.write_all(b"\n");
+    }
+
+    fn flush(&self) {
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writer.flush();
+        }
+    }
+}
+
+impl ContextLogger {
+    /// Creates a [`ContextLogger`] that writes records as JSON lines to the standard output.
+    ///
+    /// Each record is written as a single JSON object with the `timestamp` in milliseconds
+    /// since the Unix epoch, the `level`, `target` and `message` of the record, and all
+    /// of its key-values, including default and scope records. Records above the given
+    /// level are discarded.
+    ///
+    /// This is a convenience for applications that just need structured output, not a
+    /// full-featured backend: there is no buffering, no custom formatting and no output
+    /// other than the standard output. Use a dedicated logger, such as
+    /// [`structured-logger`](https://docs.rs/structured-logger), for anything more.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+    /// use log::LevelFilter;
+    ///
+    /// ContextLogger::json_stdout(LevelFilter::Info).init(LevelFilter::Info);
+    ///
+    /// LogContext::new()
+    ///     .with_local_record("request_id", "req-123")
+    ///     .in_scope(|| {
+    ///         // {"level":"INFO","message":"Handling request","request_id":"req-123",...}
+    ///         log::info!("Handling request");
+    ///     });
+    /// ```
+    #[must_use]
+    pub fn json_stdout(level: log::LevelFilter) -> Self {
+        Self::new(JsonLinesLogger::new(level, std::io::stdout()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Write,
+        sync::{Arc, Mutex},
+    };
+
+    use log::{Level, LevelFilter, Log as _};
+    use pretty_assertions::assert_eq;
+
+    use super::JsonLinesLogger;
+    use crate::{ContextLogger, LogContext, LogContextExt as _};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_line_contains_context() {
+        let buffer = Buffer::default();
+        let logger = ContextLogger::new(JsonLinesLogger::new(LevelFilter::Info, buffer.clone()));
+
+        LogContext::new()
+            .with_local_record("request_id", 42)
+            .in_scope(|| {
+                for level in [Level::Info, Level::Debug] {
+                    logger.log(
+                        &log::Record::builder()
+                            .level(level)
+                            .target("app")
+                            .args(format_args!("Handling request"))
+                            .build(),
+                    );
+                }
+            });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1);
+
+        let line: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["target"], "app");
+        assert_eq!(line["message"], "Handling request");
+        assert_eq!(line["request_id"], 42);
+        assert!(line["timestamp"].is_u64());
+    }
+}
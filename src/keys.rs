@@ -0,0 +1,141 @@
+//! Typed record keys and validation of record keys.
+
+use std::{borrow::Cow, marker::PhantomData};
+
+/// A record key bound to the type of its values.
+///
+/// Frequently used keys can be declared once as constants, so a misspelled key or a
+/// value of the wrong type is caught at compile time instead of silently producing a
+/// different field. A typed key is recorded as a plain string key, see
+/// [`LogContext::with_typed_record`](crate::LogContext::with_typed_record).
+///
+/// # Example
+///
+/// ```
+/// use context_logger::{ContextKey, LogContext};
+///
+/// const REQUEST_ID: ContextKey<&str> = ContextKey::new("request_id");
+/// const ATTEMPT: ContextKey<u32> = ContextKey::new("attempt");
+///
+/// let request_id = String::from("req-123");
+/// let context = LogContext::new()
+///     .with_typed_record(REQUEST_ID, &request_id)
+///     .with_typed_record(ATTEMPT, 1);
+/// ```
+pub struct ContextKey<T> {
+    name: &'static str,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T> ContextKey<T> {
+    /// Creates a key with the given name.
+    #[must_use]
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _value: PhantomData,
+        }
+    }
+
+    /// Returns the name of the key.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        self.name
+    }
+}
+
+// Implemented manually to avoid the `T: Clone` and `T: Debug` bounds of the derives.
+impl<T> Clone for ContextKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ContextKey<T> {}
+
+impl<T> std::fmt::Debug for ContextKey<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ContextKey").field(&self.name).finish()
+    }
+}
+
+/// A policy applied to record keys that some backends may reject.
+///
+/// A key is considered invalid if it is empty or contains whitespace or
+/// non-printable characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyPolicy {
+    /// Keys are passed through unchanged.
+    #[default]
+    Allow,
+    /// Invalid characters are replaced with `_`, an empty key becomes `_`.
+    Sanitize,
+    /// Records with invalid keys are dropped. Dropped keys are reported to the observer
+    /// set by [`ContextLogger::with_invalid_key_observer`], if any.
+    ///
+    /// [`ContextLogger::with_invalid_key_observer`]: crate::ContextLogger::with_invalid_key_observer
+    Drop,
+}
+
+impl KeyPolicy {
+    /// Applies the policy to the given key, returning `None` if the record should be dropped.
+    #[allow(
+        clippy::ptr_arg,
+        reason = "cloning the `Cow` keeps static keys borrowed"
+    )]
+    pub(crate) fn apply(self, key: &Cow<'static, str>) -> Option<Cow<'static, str>> {
+        if self == Self::Allow || is_valid_key(key) {
+            return Some(key.clone());
+        }
+
+        match self {
+            Self::Allow => unreachable!(),
+            Self::Sanitize if key.is_empty() => Some(Cow::Borrowed("_")),
+            Self::Sanitize => Some(Cow::Owned(
+                key.chars()
+                    .map(|c| if is_valid_key_char(c) { c } else { '_' })
+                    .collect(),
+            )),
+            Self::Drop => None,
+        }
+    }
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(is_valid_key_char)
+}
+
+fn is_valid_key_char(c: char) -> bool {
+    !c.is_whitespace() && !c.is_control()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use pretty_assertions::assert_eq;
+
+    use super::KeyPolicy;
+
+    #[test]
+    fn test_valid_keys_pass_through() {
+        for policy in [KeyPolicy::Allow, KeyPolicy::Sanitize, KeyPolicy::Drop] {
+            let key = policy.apply(&Cow::Borrowed("user.id")).unwrap();
+            assert_eq!(key, "user.id");
+            assert!(matches!(key, Cow::Borrowed(_)));
+        }
+    }
+
+    #[test]
+    fn test_invalid_keys() {
+        let cases = ["", "user id", "tab\tkey", "bell\u{7}", "line\nbreak"];
+        let sanitized = ["_", "user_id", "tab_key", "bell_", "line_break"];
+
+        for (key, expected) in cases.into_iter().zip(sanitized) {
+            let key = Cow::Borrowed(key);
+            assert_eq!(KeyPolicy::Allow.apply(&key).unwrap(), key);
+            assert_eq!(KeyPolicy::Sanitize.apply(&key).unwrap(), expected);
+            assert_eq!(KeyPolicy::Drop.apply(&key), None);
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Key-value adapters for building custom loggers.
+//!
+//! [`ContextLogger`](crate::ContextLogger) enriches records automatically. The types
+//! in this module give custom [`Log`](log::Log) implementations access to the same
+//! records without wrapping them into a [`ContextLogger`](crate::ContextLogger).
+
+use crate::{LogContext, LogScope, LogValue, SourceWithRecords};
+
+/// The order in which context records and the record's own key-values are visited.
+///
+/// Backends resolve duplicate keys differently: some keep the first occurrence,
+/// others the last one. The order determines which value wins for a given backend.
+///
+/// See [`ContextLogger::with_kv_order`](crate::ContextLogger::with_kv_order).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KvOrder {
+    /// Default and scope records are visited before the record's own key-values.
+    #[default]
+    ContextFirst,
+    /// The record's own key-values are visited before default and scope records.
+    SourceFirst,
+}
+
+/// A [`log::kv::Source`] over the records of a log context.
+///
+/// Inherited records are visited before local ones, so consumers resolving duplicate
+/// keys with "last write wins" semantics see local records shadowing inherited ones.
+/// Single key lookups via [`get`](log::kv::Source::get) follow the same precedence
+/// without visiting every record.
+///
+/// # Examples
+///
+/// ```
+/// use context_logger::{LogContext, LogContextExt as _, kv::ContextSource};
+/// use log::kv::Source as _;
+///
+/// LogContext::new().with_local_record("user_id", 42).in_scope(|| {
+///     let source = ContextSource::current();
+///     assert_eq!(source.count(), 1);
+///     assert_eq!(source.get("user_id".into()).unwrap().to_string(), "42");
+/// });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ContextSource {
+    context: LogContext,
+}
+
+impl ContextSource {
+    /// Creates a source over the records of the currently active scope.
+    ///
+    /// The records are captured when the source is created, so scopes entered or
+    /// exited afterwards do not affect it. Default records of a
+    /// [`ContextLogger`](crate::ContextLogger) are not included.
+    #[must_use]
+    pub fn current() -> Self {
+        Self {
+            context: LogScope::current_context(),
+        }
+    }
+
+    /// Returns the context this source visits.
+    #[must_use]
+    pub const fn context(&self) -> &LogContext {
+        &self.context
+    }
+}
+
+impl From<LogContext> for ContextSource {
+    fn from(context: LogContext) -> Self {
+        Self { context }
+    }
+}
+
+impl log::kv::Source for ContextSource {
+    fn visit<'kvs>(
+        &'kvs self,
+        visitor: &mut dyn log::kv::VisitSource<'kvs>,
+    ) -> Result<(), log::kv::Error> {
+        for (key, value) in self
+            .context
+            .inherited
+            .iter()
+            .chain(self.context.local.iter())
+        {
+            visitor.visit_pair(log::kv::Key::from_str(key), value.as_log_value())?;
+        }
+        Ok(())
+    }
+
+    fn get(&self, key: log::kv::Key<'_>) -> Option<log::kv::Value<'_>> {
+        let key = key.as_str();
+        self.context
+            .local
+            .get(key)
+            .or_else(|| self.context.inherited.get(key))
+            .map(LogValue::as_log_value)
+    }
+
+    fn count(&self) -> usize {
+        self.context.local.len() + self.context.inherited.len()
+    }
+}
+
+/// Passes the given record, enriched with the records of the currently active scope,
+/// to the closure.
+///
+/// This lets libraries that build records manually attach the context without
+/// installing a [`ContextLogger`](crate::ContextLogger) globally. Scope records are
+/// visited before the record's own key-values, as with [`KvOrder::ContextFirst`].
+/// Default records of a [`ContextLogger`](crate::ContextLogger) are not included.
+///
+/// A [`log::RecordBuilder`] cannot be enriched in place, since it can only borrow
+/// key-values that outlive it, so the enriched record is passed to a closure instead.
+///
+/// # Examples
+///
+/// ```
+/// use context_logger::{LogContext, LogContextExt as _, kv};
+/// use log::{Level, Record};
+///
+/// # let logger = env_logger::builder().build();
+/// LogContext::new().with_local_record("request_id", 42).in_scope(|| {
+///     let record = Record::builder()
+///         .level(Level::Info)
+///         .args(format_args!("Built manually"))
+///         .build();
+///     kv::enrich(&record, |record| log::Log::log(&logger, record));
+/// });
+/// ```
+pub fn enrich<R>(record: &log::Record, f: impl FnOnce(&log::Record) -> R) -> R {
+    let context = LogScope::current_context();
+    let source = SourceWithRecords {
+        source: record.key_values(),
+        records: context.inherited.iter().chain(context.local.iter()),
+        order: KvOrder::ContextFirst,
+    };
+    f(&record.to_builder().key_values(&source).build())
+}
+
+#[cfg(test)]
+mod tests {
+    use log::kv::{Key, Source as _, Value, VisitSource};
+    use pretty_assertions::assert_eq;
+
+    use super::{ContextSource, enrich};
+    use crate::{LogContext, LogContextExt as _};
+
+    #[derive(Default)]
+    struct Collect(Vec<(String, String)>);
+
+    impl<'kvs> VisitSource<'kvs> for Collect {
+        fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), log::kv::Error> {
+            self.0.push((key.to_string(), value.to_string()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_context_source_visits_active_scope() {
+        assert_eq!(ContextSource::current().count(), 0);
+
+        LogContext::new()
+            .with_inherited_record("service", "api")
+            .in_scope(|| {
+                LogContext::new()
+                    .with_local_record("user_id", 42)
+                    .in_scope(|| {
+                        let source = ContextSource::current();
+
+                        let mut collect = Collect::default();
+                        source.visit(&mut collect).unwrap();
+                        assert_eq!(
+                            collect.0,
+                            [
+                                ("service".to_owned(), "api".to_owned()),
+                                ("user_id".to_owned(), "42".to_owned()),
+                            ]
+                        );
+                    });
+            });
+    }
+
+    #[test]
+    fn test_enrich_manually_built_record() {
+        let key_values = [("attempt", 3)];
+        let record = log::Record::builder()
+            .level(log::Level::Info)
+            .args(format_args!("Built manually"))
+            .key_values(&key_values)
+            .build();
+
+        let collect = |record: &log::Record| {
+            let mut collect = Collect::default();
+            record.key_values().visit(&mut collect).unwrap();
+            collect.0
+        };
+        let enriched = LogContext::new()
+            .with_inherited_record("service", "api")
+            .with_local_record("request_id", 42)
+            .in_scope(|| enrich(&record, collect));
+
+        assert_eq!(
+            enriched,
+            [
+                ("service".to_owned(), "api".to_owned()),
+                ("request_id".to_owned(), "42".to_owned()),
+                ("attempt".to_owned(), "3".to_owned()),
+            ]
+        );
+        assert_eq!(
+            enrich(&record, collect),
+            [("attempt".to_owned(), "3".to_owned())]
+        );
+    }
+
+    #[test]
+    fn test_context_source_from_context() {
+        let source = ContextSource::from(LogContext::new().with_local_record("attempt", 2));
+        assert_eq!(
+            source.get(Key::from_str("attempt")).unwrap().to_string(),
+            "2"
+        );
+        assert!(source.get(Key::from_str("missing")).is_none());
+    }
+
+    #[test]
+    fn test_context_source_get_prefers_local_records() {
+        let source = ContextSource::from(
+            LogContext::new()
+                .with_inherited_record("stage", "inherited")
+                .with_inherited_record("service", "api")
+                .with_local_record("stage", "local"),
+        );
+
+        assert_eq!(source.count(), 3);
+        assert_eq!(
+            source.get(Key::from_str("stage")).unwrap().to_string(),
+            "local"
+        );
+        assert_eq!(
+            source.get(Key::from_str("service")).unwrap().to_string(),
+            "api"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Composable processing steps applied to enriched records.
+//!
+//! Behaviors such as redaction or sampling can be implemented as a [`ContextLayer`]
+//! and stacked on a [`ContextLogger`](crate::ContextLogger) with
+//! [`with_layer`](crate::ContextLogger::with_layer). Layers run in the order they were
+//! added, each one receiving the record returned by the previous layer, so every
+//! behavior stays a small, separately testable unit.
+
+use std::borrow::Cow;
+
+use crate::{ContextView, LogValue, records::LogRecordRef, serde_owned::OwnedValue};
+
+/// A processing step applied to every enriched record before it reaches the inner logger.
+///
+/// Closures with the signature of [`Self::process`] implement this trait as well.
+///
+/// # Example
+///
+/// ```
+/// use context_logger::{ContextLogger, LogValue, layer::{ContextLayer, OwnedRecord}};
+/// use context_logger::ContextView;
+///
+/// /// Masks the values of the given keys.
+/// struct Redact(&'static [&'static str]);
+///
+/// impl ContextLayer for Redact {
+///     fn process(&self, mut record: OwnedRecord, _context: &ContextView) -> Option<OwnedRecord> {
+///         for (key, value) in record.key_values_mut() {
+///             if self.0.contains(&key.as_ref()) {
+///                 *value = LogValue::static_str("***");
+///             }
+///         }
+///         Some(record)
+///     }
+/// }
+///
+/// let logger = ContextLogger::new(env_logger::builder().build())
+///     .with_layer(Redact(&["password"]))
+///     // Drops debug records of the health checks.
+///     .with_layer(|record: OwnedRecord, context: &ContextView| {
+///         let health_check = context.get("route").and_then(LogValue::as_str) == Some("/health");
+///         (!health_check || record.level() < log::Level::Debug).then_some(record)
+///     });
+/// ```
+pub trait ContextLayer: Send + Sync + 'static {
+    /// Processes the record, returning `None` to drop it.
+    ///
+    /// The record contains the default, scope and own key-values of the original record,
+    /// while `context` gives access to the default and scope records as they were
+    /// before any layer ran.
+    fn process(&self, record: OwnedRecord, context: &ContextView) -> Option<OwnedRecord>;
+}
+
+impl<F> ContextLayer for F
+where
+    F: Fn(OwnedRecord, &ContextView) -> Option<OwnedRecord> + Send + Sync + 'static,
+{
+    fn process(&self, record: OwnedRecord, context: &ContextView) -> Option<OwnedRecord> {
+        self(record, context)
+    }
+}
+
+/// An owned copy of an enriched log record.
+#[derive(Debug, Clone)]
+pub struct OwnedRecord {
+    level: log::Level,
+    target: String,
+    message: String,
+    module_path: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    key_values: Vec<(Cow<'static, str>, LogValue)>,
+}
+
+impl OwnedRecord {
+    /// Copies the record together with the given context records, which precede the
+    /// record's own key-values.
+    pub(crate) fn capture<'a>(
+        record: &log::Record,
+        records: impl Iterator<Item = LogRecordRef<'a>>,
+    ) -> Self {
+        let mut key_values = records
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<Vec<_>>();
+        let _ = record.key_values().visit(&mut OwnedPairs(&mut key_values));
+        Self {
+            level: record.level(),
+            target: record.target().to_owned(),
+            message: record.args().to_string(),
+            module_path: record.module_path().map(ToOwned::to_owned),
+            file: record.file().map(ToOwned::to_owned),
+            line: record.line(),
+            key_values,
+        }
+    }
+
+    /// Returns the level of the record.
+    #[must_use]
+    pub const fn level(&self) -> log::Level {
+        self.level
+    }
+
+    /// Sets the level of the record.
+    pub const fn set_level(&mut self, level: log::Level) {
+        self.level = level;
+    }
+
+    /// Returns the target of the record.
+    #[must_use]
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Returns the formatted message of the record.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Replaces the message of the record.
+    pub fn set_message(&mut self, message: impl Into<String>) {
+        self.message = message.into();
+    }
+
+    /// Returns the value of the last key-value with the given key, if any.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&LogValue> {
+        self.key_values
+            .iter()
+            .rev()
+            .find(|(record_key, _)| record_key == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns the key-values of the record in the order they are passed to the logger.
+    #[must_use]
+    pub fn key_values(&self) -> &[(Cow<'static, str>, LogValue)] {
+        &self.key_values
+    }
+
+    /// Returns the key-values of the record for modification.
+    pub const fn key_values_mut(&mut self) -> &mut Vec<(Cow<'static, str>, LogValue)> {
+        &mut self.key_values
+    }
+
+    /// Builds a [`log::Record`] borrowing from this record and passes it to the closure.
+    pub fn with_record<R>(&self, f: impl FnOnce(&log::Record) -> R) -> R {
+        f(&log::Record::builder()
+            .level(self.level)
+            .target(&self.target)
+            .args(format_args!("{}", self.message))
+            .module_path(self.module_path.as_deref())
+            .file(self.file.as_deref())
+            .line(self.line)
+            .key_values(self)
+            .build())
+    }
+}
+
+impl log::kv::Source for OwnedRecord {
+    fn visit<'kvs>(
+        &'kvs self,
+        visitor: &mut dyn log::kv::VisitSource<'kvs>,
+    ) -> Result<(), log::kv::Error> {
+        for (key, value) in &self.key_values {
+            visitor.visit_pair(log::kv::Key::from_str(key), value.as_log_value())?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects the record's own key-values into owned pairs.
+struct OwnedPairs<'a>(&'a mut Vec<(Cow<'static, str>, LogValue)>);
+
+impl<'kvs> log::kv::VisitSource<'kvs> for OwnedPairs<'_> {
+    fn visit_pair(
+        &mut self,
+        key: log::kv::Key<'kvs>,
+        value: log::kv::Value<'kvs>,
+    ) -> Result<(), log::kv::Error> {
+        // Structured values are copied as a whole, so they reach the backend unchanged.
+        let value = match OwnedValue::capture(&value) {
+            Ok(OwnedValue::Unit) => LogValue::null(),
+            Ok(OwnedValue::Bool(value)) => LogValue::from(value),
+            Ok(OwnedValue::I64(value)) => LogValue::from(value),
+            Ok(OwnedValue::U64(value)) => LogValue::from(value),
+            Ok(OwnedValue::I128(value)) => LogValue::from(value),
+            Ok(OwnedValue::U128(value)) => LogValue::from(value),
+            Ok(OwnedValue::F64(value)) => LogValue::from(value),
+            Ok(OwnedValue::Char(value)) => LogValue::from(value),
+            Ok(OwnedValue::Str(value)) => LogValue::from(value),
+            Ok(value) => LogValue::serde(value),
+            Err(_) => LogValue::from(value.to_string()),
+        };
+        self.0.push((Cow::Owned(key.as_str().to_owned()), value));
+        Ok(())
+    }
+}
@@ -27,26 +27,101 @@
 //! [`env_logger`]: https://docs.rs/env_logger/latest/env_logger
 //! [`log4rs`]: https://docs.rs/log4rs/latest/log4rs
 
-use std::{borrow::Cow, collections::HashMap};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::{
+        Arc, OnceLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use arc_swap::ArcSwap;
 
-use crate::records::LogRecordRef;
+use crate::{
+    collapse::RepeatCollapser,
+    directives::{ContextDirectives, TargetFilter},
+    flush::PeriodicFlush,
+    records::LogRecordRef,
+    replay::DebugBuffer,
+    sampling::KeyedSampler,
+    timing::{Forwarding, SelfTiming},
+};
 
+mod collapse;
+mod config;
 mod context;
+mod directives;
+mod envelope;
+mod error_counter;
+pub mod fallible;
+mod flush;
+pub mod fmt;
 pub mod future;
+#[cfg(feature = "json_stdout")]
+mod json;
+mod keys;
+pub mod kv;
+pub mod layer;
+pub mod mdc;
+mod once;
+#[cfg(feature = "propagation")]
+mod propagation;
+mod quiet;
 mod records;
+mod replay;
+mod sampling;
 mod scope;
+mod serde_depth;
+mod serde_owned;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod timing;
 mod value;
+mod view;
 
 type LogValueFn = Box<dyn Fn(&log::Record) -> LogValue + Send + Sync>;
+type InnerLogger = Arc<ArcSwap<Box<dyn log::Log>>>;
+type DroppedRecordObserver = Box<dyn Fn(&log::Record, Option<&LogContext>) + Send + Sync>;
+type InvalidKeyObserver = Box<dyn Fn(&str) + Send + Sync>;
+type RecordFilter = Box<dyn Fn(&log::Record, &ContextView) -> bool + Send + Sync>;
+type RecordObserver = Box<dyn Fn(&log::Record, &ContextView) + Send + Sync>;
+type ErrorContextCapture = Box<dyn Fn(&log::Record, CapturedContext) + Send + Sync>;
+
+static SOURCE_FILE_KEY: Cow<'static, str> = Cow::Borrowed("src.file");
+static SOURCE_LINE_KEY: Cow<'static, str> = Cow::Borrowed("src.line");
+static SOURCE_MODULE_KEY: Cow<'static, str> = Cow::Borrowed("src.module");
+
+/// A key of the record added by [`ContextLogger::with_level_map`].
+const SEVERITY_KEY: &str = "severity";
+
+/// A process-wide counter for [`ContextLogger::with_sequence_field`].
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
 
 pub use self::{
-    context::LogContext,
+    collapse::CollapseRepeats,
+    config::{ContextLoggerConfig, config, set_config, update_config},
+    context::{Inheritance, LogContext},
+    directives::DirectiveError,
+    envelope::ContextEnvelope,
+    error_counter::ErrorCounter,
     future::FutureExt,
+    keys::{ContextKey, KeyPolicy},
+    quiet::QuietGuard,
     records::LogRecords,
-    scope::{LogContextExt, LogScope},
-    value::LogValue,
+    scope::{
+        CapturedContext, LeakedScopePolicy, LogContextExt, LogScope, MergeGuard,
+        OrphanRecordPolicy, ScopeGroup,
+    },
+    timing::SelfTimingStats,
+    value::{BytesEncoding, LogValue, LogValueKind, NullRepr},
+    view::ContextView,
 };
 
+#[cfg(feature = "propagation")]
+pub use self::propagation::PropagationHeaderError;
+
 /// A logger wrapper that enhances log records with scope records.
 ///
 /// `ContextLogger` wraps an existing logging implementation and adds additional
@@ -59,9 +134,65 @@ pub use self::{
 ///
 /// See [`LogContext`] for more information on how to create and manage scope records.
 pub struct ContextLogger {
-    inner: Box<dyn log::Log>,
+    inner: InnerLogger,
+    max_level: Arc<OnceLock<log::LevelFilter>>,
     default_records: LogRecords,
     dynamic_default_records: HashMap<Cow<'static, str>, LogValueFn>,
+    level_default_records: HashMap<log::Level, LogRecords>,
+    default_override: DefaultOverridePolicy,
+    context_disabled: bool,
+    excluded_targets: Vec<Cow<'static, str>>,
+    source_location: bool,
+    message_field: Option<Cow<'static, str>>,
+    breadcrumb_field: Option<Cow<'static, str>>,
+    key_policy: KeyPolicy,
+    invalid_key_observer: Option<InvalidKeyObserver>,
+    null_repr: NullRepr,
+    max_serde_depth: Option<usize>,
+    collapser: Option<RepeatCollapser>,
+    inheritance: Inheritance,
+    hierarchical_keys: bool,
+    audit_sink: Option<AuditSink>,
+    dropped_record_observer: Option<DroppedRecordObserver>,
+    record_filter: Option<RecordFilter>,
+    record_observer: Option<RecordObserver>,
+    error_context_capture: Option<ErrorContextCapture>,
+    context_directives: Option<ContextDirectives>,
+    target_filter: Option<TargetFilter>,
+    error_counter: Option<ErrorCounter>,
+    keyed_sampler: Option<KeyedSampler>,
+    debug_buffer: Option<DebugBuffer>,
+    layers: Vec<Box<dyn layer::ContextLayer>>,
+    self_timing: Option<SelfTiming>,
+    flush_interval: Option<Duration>,
+    periodic_flush: Arc<PeriodicFlush>,
+    kv_order: kv::KvOrder,
+}
+
+/// A policy that resolves collisions between default records and scope records.
+///
+/// See [`ContextLogger::with_default_override_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DefaultOverridePolicy {
+    /// Both records are passed to the inner logger, default records first. Which value
+    /// is displayed depends on the backend.
+    #[default]
+    KeepBoth,
+    /// A default record is skipped if a visible scope record has the same key.
+    ContextWins,
+}
+
+/// An owned copy of the context of a forwarded record, passed to the observers once the
+/// scope stack is released.
+struct Observation {
+    records: Vec<(Cow<'static, str>, LogValue)>,
+    captured: Option<CapturedContext>,
+}
+
+/// A logger that additionally receives records with a marker key in their context.
+struct AuditSink {
+    key: Cow<'static, str>,
+    logger: Box<dyn log::Log>,
 }
 
 impl ContextLogger {
@@ -74,12 +205,62 @@ impl ContextLogger {
         L: log::Log + 'static,
     {
         Self {
-            inner: Box::new(inner),
+            inner: Arc::new(ArcSwap::from_pointee(Box::new(inner))),
+            max_level: Arc::default(),
             default_records: LogRecords::new(),
             dynamic_default_records: HashMap::new(),
+            level_default_records: HashMap::new(),
+            default_override: DefaultOverridePolicy::KeepBoth,
+            context_disabled: false,
+            excluded_targets: Vec::new(),
+            source_location: false,
+            message_field: None,
+            breadcrumb_field: None,
+            key_policy: KeyPolicy::Allow,
+            invalid_key_observer: None,
+            null_repr: NullRepr::Null,
+            max_serde_depth: None,
+            collapser: None,
+            inheritance: Inheritance::Shadow,
+            hierarchical_keys: false,
+            audit_sink: None,
+            dropped_record_observer: None,
+            record_filter: None,
+            record_observer: None,
+            error_context_capture: None,
+            context_directives: None,
+            target_filter: None,
+            error_counter: None,
+            keyed_sampler: None,
+            debug_buffer: None,
+            layers: Vec::new(),
+            self_timing: None,
+            flush_interval: None,
+            periodic_flush: Arc::default(),
+            kv_order: kv::KvOrder::ContextFirst,
         }
     }
 
+    /// Creates a new [`ContextLogger`] that discards all records.
+    ///
+    /// The records are still enriched with default and scope records, and all attached
+    /// values are rendered before being discarded. This makes it possible to exercise
+    /// context propagation in tests and examples without any backend output.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use log::LevelFilter;
+    /// use context_logger::ContextLogger;
+    ///
+    /// ContextLogger::sink().init(LevelFilter::Trace);
+    /// log::info!("Nobody will see this");
+    /// ```
+    #[must_use]
+    pub fn sink() -> Self {
+        Self::new(SinkLogger)
+    }
+
     /// Initializes the global logger with the context logger.
     ///
     /// This should be called early in the execution of a Rust program. Any log events that occur before initialization will be ignored.
@@ -100,8 +281,59 @@ impl ContextLogger {
     ///
     /// Returns an error if a logger has already been set.
     pub fn try_init(self, max_level: log::LevelFilter) -> Result<(), log::SetLoggerError> {
-        log::set_max_level(max_level);
-        log::set_boxed_logger(Box::new(self))
+        let initialized_level = self.max_level.clone();
+        // Records enabled by context directives must not be filtered out by the macros.
+        let global_level = self
+            .context_directives
+            .as_ref()
+            .map_or(max_level, |directives| {
+                directives.max_level().max(max_level)
+            });
+        // Records below the level must reach the logger to be buffered.
+        let global_level = self
+            .debug_buffer
+            .map_or(global_level, |buffer| buffer.level().max(global_level));
+        let flush_interval = self.flush_interval;
+        let periodic_flush = self.periodic_flush.clone();
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(global_level);
+        initialized_level.get_or_init(|| max_level);
+        if let Some(interval) = flush_interval {
+            periodic_flush.start(interval);
+        }
+        Ok(())
+    }
+
+    /// Consumes the logger and returns the inner logger.
+    ///
+    /// This is intended for loggers that are not installed globally but held by the
+    /// application and used directly, so a buffered backend can be flushed and dropped
+    /// deterministically at shutdown. Once a logger is installed by [`Self::init`], it
+    /// cannot be removed from the global slot. To shut down the backend of the global
+    /// logger, replace it via [`Self::handle`] instead: the previous logger is flushed
+    /// and dropped.
+    ///
+    /// If the logger has [handles](Self::handle), they are left with a logger that
+    /// discards all records.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::ContextLogger;
+    /// use log::Log as _;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build());
+    /// logger.log(&log::Record::builder().args(format_args!("Hello")).build());
+    ///
+    /// let inner = logger.take_inner();
+    /// inner.flush();
+    /// ```
+    #[must_use]
+    pub fn take_inner(self) -> Box<dyn log::Log> {
+        let inner = self.inner.swap(Arc::new(Box::new(NopLogger)));
+        // No records can be in flight through the consumed logger, so the inner logger
+        // is normally not shared anymore. Otherwise, it is returned behind the `Arc`.
+        Arc::try_unwrap(inner).unwrap_or_else(|shared| Box::new(shared))
     }
 
     /// Adds a default record that will be included in all log entries.
@@ -115,7 +347,9 @@ impl ContextLogger {
     /// When logging, default records are added first, followed by records from the current
     /// context. If multiple records with the same key exist, the behavior depends on the
     /// underlying logger implementation. In most implementations, later records with the
-    /// same key will typically replace earlier ones.
+    /// same key will typically replace earlier ones. Use
+    /// [`Self::with_default_override_policy`] to let scope records reliably override
+    /// default ones.
     ///
     /// # Example
     ///
@@ -142,6 +376,64 @@ impl ContextLogger {
         self
     }
 
+    /// Adds a default record that is included only in log entries of the given level.
+    ///
+    /// Can be called several times to add records for multiple levels. Level default
+    /// records are emitted together with the other default records.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use log::{Level, LevelFilter};
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_level_default(Level::Error, "alert", true);
+    /// logger.init(LevelFilter::Info);
+    ///
+    /// log::info!("Request processed"); // No alert record
+    /// log::error!("Request failed"); // Will include alert=true
+    /// ```
+    #[must_use]
+    pub fn with_level_default(
+        mut self,
+        level: log::Level,
+        key: impl Into<Cow<'static, str>>,
+        value: impl Into<LogValue>,
+    ) -> Self {
+        self.level_default_records
+            .entry(level)
+            .or_default()
+            .insert(key, value);
+        self
+    }
+
+    /// Sets how collisions between default records and scope records are resolved.
+    ///
+    /// By default, [`DefaultOverridePolicy::KeepBoth`] is used and the backend decides
+    /// which value of a duplicate key is displayed. With
+    /// [`DefaultOverridePolicy::ContextWins`], a scope can reliably override a default
+    /// record, regardless of the backend.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{ContextLogger, DefaultOverridePolicy, LogContext, LogContextExt as _};
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_default_record("component", "api")
+    ///     .with_default_override_policy(DefaultOverridePolicy::ContextWins);
+    ///
+    /// LogContext::new().with_local_record("component", "worker").in_scope(|| {
+    ///     log::info!("Background loop"); // Only component="worker" is attached
+    /// });
+    /// ```
+    #[must_use]
+    pub const fn with_default_override_policy(mut self, policy: DefaultOverridePolicy) -> Self {
+        self.default_override = policy;
+        self
+    }
+
     /// Adds a dynamic default record computed by the given closure for each log entry.
     ///
     /// Like [`Self::with_default_record`], the record is included in all log entries.
@@ -178,98 +470,1644 @@ impl ContextLogger {
             .insert(key.into(), Box::new(move |record| f(record).into()));
         self
     }
-}
 
-impl std::fmt::Debug for ContextLogger {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ContextLogger").finish_non_exhaustive()
+    /// Adds a default record whose value is computed once, on the first emitted log entry.
+    ///
+    /// This is suitable for values that are expensive to compute, such as ones read from
+    /// a file, and should not be computed at all if nothing is ever logged. The closure is
+    /// called at most once even if records are logged concurrently from several threads,
+    /// and the cached value is used for all subsequent log entries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_lazy_default_record("hostname", || {
+    ///         std::fs::read_to_string("/etc/hostname").unwrap_or_default()
+    ///     });
+    /// ```
+    #[must_use]
+    pub fn with_lazy_default_record<V: Into<LogValue>>(
+        self,
+        key: impl Into<Cow<'static, str>>,
+        f: impl Fn() -> V + Send + Sync + 'static,
+    ) -> Self {
+        let value = OnceLock::new();
+        self.with_default_record_fn(key, move |_record| value.get_or_init(|| f().into()).clone())
     }
-}
 
-impl log::Log for ContextLogger {
-    fn enabled(&self, metadata: &log::Metadata) -> bool {
-        self.inner.enabled(metadata)
+    /// Adds a numeric `severity` record derived from the level to all log entries.
+    ///
+    /// This eases ingestion into systems with their own numeric severity scales, such as
+    /// syslog or GELF. Use [`syslog_severity`] for the standard syslog scale.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_level_map(context_logger::syslog_severity);
+    /// ```
+    #[must_use]
+    pub fn with_level_map(self, f: impl Fn(log::Level) -> i32 + Send + Sync + 'static) -> Self {
+        self.with_default_record_fn(SEVERITY_KEY, move |record| f(record.level()))
     }
 
-    fn log(&self, record: &log::Record) {
-        if !self.enabled(record.metadata()) {
-            return;
+    /// Adds a record with a monotonically increasing sequence number to all log entries.
+    ///
+    /// The number is taken from a single process-wide counter, so it totally orders
+    /// records even if their timestamps collide. Records logged from the same thread
+    /// always get strictly increasing numbers. The counter wraps around to zero on
+    /// overflow, which takes centuries even at billions of records per second.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_sequence_field("seq");
+    /// ```
+    #[must_use]
+    pub fn with_sequence_field(self, key: impl Into<Cow<'static, str>>) -> Self {
+        self.with_default_record_fn(key, |_record| SEQUENCE.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Returns a handle that can replace the inner logger at runtime.
+    ///
+    /// The handle stays valid after the logger has been moved into the global slot by
+    /// [`Self::init`], so the backend can be swapped without re-registering the global
+    /// logger, for example to switch from stderr to a file after startup.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use log::LevelFilter;
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build());
+    /// let handle = logger.handle();
+    /// logger.init(LevelFilter::Info);
+    ///
+    /// // Later on, replace the backend.
+    /// handle.set_inner(env_logger::builder().target(env_logger::Target::Stdout).build());
+    /// ```
+    #[must_use]
+    pub fn handle(&self) -> ContextLoggerHandle {
+        ContextLoggerHandle {
+            inner: self.inner.clone(),
+            max_level: self.max_level.clone(),
+            periodic_flush: self.periodic_flush.clone(),
         }
+    }
 
-        let error = scope::stack::SCOPE_STACK.try_with(|stack| {
-            let dynamic_default_records = self
-                .dynamic_default_records
-                .iter()
-                .map(|(key, f)| (key, f(record)))
-                .collect::<Vec<_>>();
-            let default_records = self
-                .default_records
-                .iter()
-                .chain(dynamic_default_records.iter().map(|(k, v)| (*k, v)));
-
-            // Only the top frame is read here intentionally: inherited records from
-            // outer scopes are copied into each newly entered frame on `enter()`,
-            // so the top frame always contains a complete, flat view of active records.
-            if let Some(top) = stack.top() {
-                self.inner.log(
-                    &record
-                        .to_builder()
-                        .key_values(&SourceWithRecords {
-                            source: &record.key_values(),
-                            records: default_records.chain(top.records()),
-                        })
-                        .build(),
-                );
-            } else {
-                self.inner.log(
-                    &record
-                        .to_builder()
-                        .key_values(&SourceWithRecords {
-                            source: &record.key_values(),
-                            records: default_records,
-                        })
-                        .build(),
-                );
-            }
-        });
+    /// Disables context processing entirely.
+    ///
+    /// When disabled, records are forwarded to the inner logger unchanged: neither
+    /// default records nor scope records are attached. This makes the wrapper behave
+    /// exactly like the inner logger, which is useful for measuring the overhead of
+    /// context enrichment or for temporarily turning it off without removing the wrapper.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_default_record("service", "api")
+    ///     .with_context_disabled(true);
+    /// ```
+    #[must_use]
+    pub const fn with_context_disabled(mut self, disabled: bool) -> Self {
+        self.context_disabled = disabled;
+        self
+    }
 
-        if let Err(err) = error {
-            // If the context stack is not available, log the original record.
-            self.inner.log(record);
-            // We can't use `log::error!` here because we are in the middle of logging and
-            // this invocation becomes recursive.
-            eprintln!("Error accessing context stack: {err}");
-        }
+    /// Disables context processing for records with the given target prefixes.
+    ///
+    /// Records whose target starts with one of the prefixes are forwarded to the inner
+    /// logger unchanged, as if [context processing was disabled](Self::with_context_disabled).
+    /// Like in `env_logger` filters, a prefix matches the target itself as well as
+    /// all targets starting with it, so `hyper` matches `hyper::proto::h1` too.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_default_record("service", "api")
+    ///     .with_context_excluded_targets(["hyper", "h2"]);
+    /// ```
+    #[must_use]
+    pub fn with_context_excluded_targets<I>(mut self, prefixes: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Cow<'static, str>>,
+    {
+        self.excluded_targets
+            .extend(prefixes.into_iter().map(Into::into));
+        self
     }
 
-    fn flush(&self) {
-        self.inner.flush();
+    /// Adds the caller location to all log entries.
+    ///
+    /// When enabled, the `src.file`, `src.line` and `src.module` records are taken from
+    /// the [`log::Record`] metadata and added to each log entry. This is useful for
+    /// structured backends that do not render the source location by default.
+    /// Fields missing from the record are omitted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_source_location(true);
+    /// ```
+    #[must_use]
+    pub const fn with_source_location(mut self, enabled: bool) -> Self {
+        self.source_location = enabled;
+        self
     }
-}
 
-struct SourceWithRecords<'a, I> {
-    source: &'a dyn log::kv::Source,
-    records: I,
-}
+    /// Adds the formatted message to all log entries as a record with the given key,
+    /// usually `message`.
+    ///
+    /// This keeps the message available to backends that only consume key-values and
+    /// ignore the textual message. The record is skipped if the log statement already
+    /// has a key-value with the same key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_message_field("message");
+    /// ```
+    #[must_use]
+    pub fn with_message_field(mut self, key: impl Into<Cow<'static, str>>) -> Self {
+        self.message_field = Some(key.into());
+        self
+    }
 
-impl<'a, I> log::kv::Source for SourceWithRecords<'a, I>
-where
-    I: Iterator<Item = LogRecordRef<'a>> + Clone,
-{
-    fn visit<'kvs>(
-        &'kvs self,
-        visitor: &mut dyn log::kv::VisitSource<'kvs>,
-    ) -> Result<(), log::kv::Error> {
-        for (key, value) in self.records.clone() {
-            visitor.visit_pair(log::kv::Key::from_str(key), value.as_log_value())?;
-        }
-        self.source.visit(visitor)
+    /// Adds the path of the active named scopes to all log entries as a record with the
+    /// given key.
+    ///
+    /// The names of the scopes created by [`LogContext::named`] are joined by `>` from the
+    /// outermost to the innermost one, e.g. `request>db_query`, which makes the nesting
+    /// easy to follow in flat logs. Unnamed scopes are skipped, and the record is omitted
+    /// if no named scope is active.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_breadcrumb_field("scope");
+    /// logger.init(log::LevelFilter::Info);
+    ///
+    /// LogContext::named("request").in_scope(|| {
+    ///     LogContext::named("db_query").in_scope(|| {
+    ///         log::info!("Query executed"); // Includes scope="request>db_query"
+    ///     });
+    /// });
+    /// ```
+    #[must_use]
+    pub fn with_breadcrumb_field(mut self, key: impl Into<Cow<'static, str>>) -> Self {
+        self.breadcrumb_field = Some(key.into());
+        self
+    }
+
+    /// Sets the policy applied to the keys of default and scope records.
+    ///
+    /// Some backends reject keys that are empty or contain whitespace or control
+    /// characters. With [`KeyPolicy::Sanitize`] such keys are rewritten, with
+    /// [`KeyPolicy::Drop`] the offending records are skipped. Keys of records passed
+    /// directly to the logging macros are not affected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{ContextLogger, KeyPolicy};
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_default_record("service name", "api") // emitted as `service_name`
+    ///     .with_strict_keys(KeyPolicy::Sanitize);
+    /// ```
+    #[must_use]
+    pub const fn with_strict_keys(mut self, policy: KeyPolicy) -> Self {
+        self.key_policy = policy;
+        self
+    }
+
+    /// Sets a function called with every key dropped by [`KeyPolicy::Drop`].
+    ///
+    /// The library never reports dropped keys on its own, so the function decides where
+    /// the warning goes. It is called while a record is being logged, so it must not log
+    /// through this logger.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{ContextLogger, KeyPolicy};
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_strict_keys(KeyPolicy::Drop)
+    ///     .with_invalid_key_observer(|key| eprintln!("dropped record with invalid key {key:?}"));
+    /// ```
+    #[must_use]
+    pub fn with_invalid_key_observer<F>(mut self, observer: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.invalid_key_observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Collapses identical consecutive records into a single one followed by a summary.
+    ///
+    /// Records with the same level, target, message and key-values (including the
+    /// attached context) that arrive within the configured window since the previous
+    /// one are suppressed. Before the next different record, a
+    /// `previous message repeated N times` summary with the same level and target is
+    /// emitted. See [`CollapseRepeats`] for the available options. A pending summary is
+    /// also emitted by [`log::Log::flush`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use context_logger::{CollapseRepeats, ContextLogger};
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_collapse_repeats(CollapseRepeats::new(Duration::from_secs(1)).with_summary_every(100));
+    /// ```
+    #[must_use]
+    pub fn with_collapse_repeats(mut self, config: impl Into<CollapseRepeats>) -> Self {
+        self.collapser = Some(RepeatCollapser::new(config.into()));
+        self
+    }
+
+    /// Prefixes the keys of local records with the path of the scope they belong to.
+    ///
+    /// By default, a key set in several nested scopes is resolved according to the
+    /// [inheritance](Self::with_inheritance) policy. With hierarchical keys, local records
+    /// of every active scope on the current thread are emitted, and their keys are
+    /// prefixed with `scope{N}.`, where `N` is the depth of the scope starting from `0`
+    /// for the outermost one. This keeps overlapping keys of different scopes distinct.
+    /// Default records and inherited records are emitted under their own keys, and the
+    /// inheritance policy is ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_hierarchical_keys(true);
+    ///
+    /// LogContext::new().with_local_record("op", "request").in_scope(|| {
+    ///     LogContext::new().with_local_record("op", "query").in_scope(|| {
+    ///         // Attached as `scope0.op=request` and `scope1.op=query`.
+    ///         log::info!("Nested scope");
+    ///     });
+    /// });
+    /// ```
+    #[must_use]
+    pub const fn with_hierarchical_keys(mut self, enabled: bool) -> Self {
+        self.hierarchical_keys = enabled;
+        self
+    }
+
+    /// Sets how null values of default and scope records are passed to the inner logger.
+    ///
+    /// By default, null values are passed as is and each backend renders them in its
+    /// own way. The record's own key-values are not affected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{ContextLogger, NullRepr};
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_null_repr(NullRepr::Omit);
+    /// ```
+    #[must_use]
+    pub const fn with_null_repr(mut self, repr: NullRepr) -> Self {
+        self.null_repr = repr;
+        self
+    }
+
+    /// Limits the nesting depth of `serde` values of default and scope records.
+    ///
+    /// Sequences, maps and structs nested deeper than `depth` levels are replaced with
+    /// a `"..."` string when the value is serialized, which protects the backend from
+    /// pathological, e.g. recursive, structures. With a depth of zero, every `serde`
+    /// structure is replaced. The depth is unlimited by default. The record's own
+    /// key-values are not affected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build()).with_max_serde_depth(8);
+    /// ```
+    #[must_use]
+    pub const fn with_max_serde_depth(mut self, depth: usize) -> Self {
+        self.max_serde_depth = Some(depth);
+        self
+    }
+
+    /// Sets the policy that controls which records of the outer scopes are emitted
+    /// with records logged in nested scopes.
+    ///
+    /// By default, [`Inheritance::Shadow`] is used: only inherited records flow into
+    /// nested scopes. With [`Inheritance::Merge`], local records of all active scopes
+    /// on the current thread are emitted as well.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{ContextLogger, Inheritance, LogContext, LogContextExt as _};
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_inheritance(Inheritance::Merge);
+    ///
+    /// LogContext::new().with_local_record("request_id", 42).in_scope(|| {
+    ///     LogContext::new().with_local_record("user_id", 7).in_scope(|| {
+    ///         // Both `request_id` and `user_id` are attached to this record.
+    ///         log::info!("Nested scope");
+    ///     });
+    /// });
+    /// ```
+    #[must_use]
+    pub const fn with_inheritance(mut self, inheritance: Inheritance) -> Self {
+        self.inheritance = inheritance;
+        self
+    }
+
+    /// Sets the order in which context records and the record's own key-values are emitted.
+    ///
+    /// By default, context records come first, so backends that keep the last occurrence
+    /// of a key prefer the values passed to the logging macros, while backends that keep
+    /// the first occurrence prefer the context. Setting the order explicitly gives a
+    /// deterministic precedence for a known backend.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{ContextLogger, kv::KvOrder};
+    ///
+    /// // Let the record's own key-values win in a backend that keeps the first occurrence.
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_kv_order(KvOrder::SourceFirst);
+    /// ```
+    #[must_use]
+    pub const fn with_kv_order(mut self, order: kv::KvOrder) -> Self {
+        self.kv_order = order;
+        self
+    }
+
+    /// Sets a function that observes records rejected by the inner logger.
+    ///
+    /// The function is called for every record the inner logger is not
+    /// [enabled](log::Log::enabled) for, together with the context of the active scope,
+    /// if any. This makes it possible to keep recent context around even for suppressed
+    /// records, e.g. in a ring buffer dumped on error. Records filtered out by
+    /// [`log::max_level`] never reach the logger and are not observed.
+    ///
+    /// The function is called while the scope stack of the current thread is borrowed,
+    /// so it must not enter or modify scopes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_dropped_record_observer(|record, context| {
+    ///         let fields = context.map_or(0, |context| context.local.iter().count());
+    ///         eprintln!("suppressed {} record with {fields} fields", record.level());
+    ///     });
+    /// ```
+    #[must_use]
+    pub fn with_dropped_record_observer<F>(mut self, observer: F) -> Self
+    where
+        F: Fn(&log::Record, Option<&LogContext>) + Send + Sync + 'static,
+    {
+        self.dropped_record_observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Sets a function that decides whether an enriched record is passed to the inner logger.
+    ///
+    /// The function is called with the record and a view of the default and scope records
+    /// it is enriched with, so routing decisions can depend on the context. Returning
+    /// `false` drops the record. Records of [disabled](Self::with_context_disabled) or
+    /// [excluded](Self::with_context_excluded_targets) targets are checked with an
+    /// empty view.
+    ///
+    /// The function is called while the scope stack of the current thread is borrowed,
+    /// so it must not enter or modify scopes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::ContextLogger;
+    ///
+    /// // Drop everything below `warn` unless debugging is enabled for the current user.
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_record_filter(|record, context| {
+    ///         record.level() <= log::Level::Warn
+    ///             || context
+    ///                 .get("debug_user")
+    ///                 .is_some_and(|value| value.to_string() == "true")
+    ///     });
+    /// ```
+    #[must_use]
+    pub fn with_record_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&log::Record, &ContextView) -> bool + Send + Sync + 'static,
+    {
+        self.record_filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Sets a function that observes every record passed to the inner logger.
+    ///
+    /// The function is called with the record and a view of the default and scope records
+    /// it is enriched with, right after the record is forwarded. Unlike
+    /// [`Self::with_record_filter`], it cannot drop or change records, which makes it a
+    /// non-intrusive tap for exporters, e.g. counting records per tenant. Records of
+    /// [disabled](Self::with_context_disabled) or
+    /// [excluded](Self::with_context_excluded_targets) targets are observed with an
+    /// empty view.
+    ///
+    /// The function is called on the logging thread after the scope stack is released,
+    /// so it may use the scope API, e.g. [`LogScope::add_record`]. The view is an owned
+    /// copy of the records, which is only made if an observer is set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    ///
+    /// use context_logger::ContextLogger;
+    ///
+    /// static TENANT_RECORDS: AtomicU64 = AtomicU64::new(0);
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_on_record(|_record, context| {
+    ///         if context.contains_key("tenant") {
+    ///             TENANT_RECORDS.fetch_add(1, Ordering::Relaxed);
+    ///         }
+    ///     });
+    /// ```
+    #[must_use]
+    pub fn with_on_record<F>(mut self, observer: F) -> Self
+    where
+        F: Fn(&log::Record, &ContextView) + Send + Sync + 'static,
+    {
+        self.record_observer = Some(Box::new(observer));
+        self
+    }
+
+    /// Sets a function that receives a snapshot of the active context for every error
+    /// and warning record passed to the inner logger.
+    ///
+    /// The snapshot contains every frame of the current thread's scope stack and is
+    /// owned and [`Send`], so it can be attached to a report handed over to an error
+    /// reporting integration. Other records are not captured, so the cost of the copy
+    /// is only paid for errors and warnings. Records of
+    /// [disabled](Self::with_context_disabled) or
+    /// [excluded](Self::with_context_excluded_targets) targets come with an empty snapshot.
+    ///
+    /// The snapshot is taken when the record is logged, but the function is called on the
+    /// logging thread after the record is forwarded and the scope stack is released, so
+    /// it may use the scope API.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::mpsc;
+    ///
+    /// use context_logger::ContextLogger;
+    ///
+    /// let (reports, _receiver) = mpsc::channel();
+    /// let reports = std::sync::Mutex::new(reports);
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_error_context_capture(move |record, context| {
+    ///         let report = (record.args().to_string(), context);
+    ///         let _ = reports.lock().unwrap().send(report);
+    ///     });
+    /// ```
+    #[must_use]
+    pub fn with_error_context_capture<F>(mut self, capture: F) -> Self
+    where
+        F: Fn(&log::Record, CapturedContext) + Send + Sync + 'static,
+    {
+        self.error_context_capture = Some(Box::new(capture));
+        self
+    }
+
+    /// Sets log level directives that depend on the active context.
+    ///
+    /// The directives are a comma-separated list in the style of `RUST_LOG`: each
+    /// `key=value:level` directive enables records up to `level` if the default or scope
+    /// records contain `key` with the value rendered as `value`. A directive without a
+    /// level enables all levels. A bare level sets the default level for records that
+    /// do not match any directive, otherwise the level passed to [`Self::init`] is used.
+    /// Invalid directives are ignored, use [`Self::try_with_context_directives`] to
+    /// reject them.
+    ///
+    /// When the logger is installed with [`Self::init`], the global maximum level is
+    /// raised to the most verbose directive, so the records reach the logger. The inner
+    /// logger itself must be configured to accept them.
+    ///
+    /// This makes it possible to debug a single user or tenant without changing the
+    /// verbosity of the whole application.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+    /// use log::LevelFilter;
+    ///
+    /// let env_logger = env_logger::builder().filter_level(LevelFilter::Trace).build();
+    /// ContextLogger::new(env_logger)
+    ///     .with_context_directives("user_id=12345:debug")
+    ///     .init(LevelFilter::Info);
+    ///
+    /// LogContext::new().with_local_record("user_id", 12345).in_scope(|| {
+    ///     log::debug!("Emitted only for this user");
+    /// });
+    /// ```
+    #[must_use]
+    pub fn with_context_directives(mut self, directives: &str) -> Self {
+        self.context_directives = Some(ContextDirectives::parse_lossy(directives));
+        self
+    }
+
+    /// Sets log level directives that depend on the active context, failing on invalid
+    /// directives.
+    ///
+    /// See [`Self::with_context_directives`] for the syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for the first directive that cannot be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build());
+    /// let error = logger.try_with_context_directives("user_id=12345:loud").unwrap_err();
+    /// assert_eq!(error.directive(), "user_id=12345:loud");
+    /// ```
+    pub fn try_with_context_directives(mut self, directives: &str) -> Result<Self, DirectiveError> {
+        self.context_directives = Some(ContextDirectives::parse(directives)?);
+        Ok(self)
+    }
+
+    /// Sets per-target level directives checked before records reach the inner logger.
+    ///
+    /// Directives use the `env_logger` syntax: a comma-separated list of `target=level`
+    /// directives and an optional bare default level. A record is matched by the
+    /// directive with the longest target prefix, records that match no directive are
+    /// compared with the default level, which is `trace` if not specified. Invalid
+    /// directives are ignored, use [`Self::try_with_filter`] to reject them.
+    ///
+    /// This allows finer control than the filter of the inner logger without
+    /// reconfiguring it. The directives cannot enable records above the level passed
+    /// to [`Self::init`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use log::LevelFilter;
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().filter_level(LevelFilter::Trace).build())
+    ///     .with_filter("warn,my_app=info,my_app::db=debug");
+    /// logger.init(LevelFilter::Debug);
+    /// ```
+    #[must_use]
+    pub fn with_filter(mut self, directives: &str) -> Self {
+        self.target_filter = Some(TargetFilter::parse_lossy(directives));
+        self
+    }
+
+    /// Sets per-target level directives, failing on invalid directives.
+    ///
+    /// See [`Self::with_filter`] for the syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for the first directive that cannot be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build());
+    /// let error = logger.try_with_filter("warn,my_app=loud").unwrap_err();
+    /// assert_eq!(error.directive(), "my_app=loud");
+    /// ```
+    pub fn try_with_filter(mut self, directives: &str) -> Result<Self, DirectiveError> {
+        self.target_filter = Some(TargetFilter::parse(directives)?);
+        Ok(self)
+    }
+
+    /// Sets a counter of error and warning records per value of a context key.
+    ///
+    /// Records are counted once they have passed the record filter, sampling and the
+    /// layers, so records dropped on the way are not counted. Records replayed from the
+    /// [debug buffer](Self::with_debug_buffer) are counted when they are replayed. See
+    /// [`ErrorCounter`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{ContextLogger, ErrorCounter};
+    ///
+    /// let errors_per_tenant = ErrorCounter::new("tenant_id", 1_000);
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_error_counter(errors_per_tenant.clone());
+    ///
+    /// // Later, e.g. in a metrics exporter.
+    /// for (tenant_id, errors) in errors_per_tenant.counts() {
+    ///     println!("{tenant_id}: {errors}");
+    /// }
+    /// ```
+    #[must_use]
+    pub fn with_error_counter(mut self, counter: ErrorCounter) -> Self {
+        self.error_counter = Some(counter);
+        self
+    }
+
+    /// Emits only a fraction of the records per value of a context key.
+    ///
+    /// Sampling decisions are made separately for every distinct value of the default or
+    /// scope record with the given key, e.g. per `endpoint`, so a hot value is thinned out
+    /// to the given `rate` while rarely seen values stay visible: the first record of every
+    /// value is always emitted. The `rate` is clamped to the `0.0..=1.0` range. Warnings,
+    /// errors and records without the key are not sampled.
+    ///
+    /// Values are told apart by a hash of their text, so in rare cases two values may
+    /// share a budget. At most 1024 distinct values are sampled separately, records with
+    /// further values share a single budget.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::ContextLogger;
+    ///
+    /// // Emit every tenth record per endpoint.
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_keyed_sampling("endpoint", 0.1);
+    /// ```
+    #[must_use]
+    pub fn with_keyed_sampling(mut self, key: impl Into<Cow<'static, str>>, rate: f64) -> Self {
+        self.keyed_sampler = Some(KeyedSampler::new(key.into(), rate));
+        self
+    }
+
+    /// Measures the time spent logging each record and periodically reports aggregate
+    /// statistics to the sink.
+    ///
+    /// This is meant for performance investigations, e.g. to find out whether the logger
+    /// is a bottleneck. Records rejected by the target filter or by the inner logger's
+    /// `enabled` check are not measured. The measured time covers the rest of the
+    /// [`log::Log::log`] call, excluding the time spent in the inner logger and the audit
+    /// sink. The statistics are reported once at least `interval` has passed since the
+    /// previous report. The sink is called on the logging thread and must not log
+    /// through this logger. Self timing is disabled by default and costs nothing in that
+    /// case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build()).with_self_timing(
+    ///     Duration::from_secs(10),
+    ///     |stats| eprintln!("logged {} records, mean {:?}", stats.records(), stats.mean()),
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_self_timing<F>(mut self, interval: Duration, sink: F) -> Self
+    where
+        F: Fn(&SelfTimingStats) + Send + Sync + 'static,
+    {
+        self.self_timing = Some(SelfTiming::new(interval, Box::new(sink)));
+        self
+    }
+
+    /// Flushes the logger from a background thread every `interval`.
+    ///
+    /// Buffered backends may hold records for a long time during quiet periods. With
+    /// this option, [`Self::init`] spawns a thread that calls [`log::Log::flush`] on the
+    /// installed logger periodically, so buffered records are delivered in time without
+    /// manual flushing. The thread is only started if the logger is installed globally
+    /// and runs until [`ContextLoggerHandle::stop_periodic_flush`] is called. Intervals
+    /// shorter than a millisecond, including [`Duration::ZERO`], are raised to one
+    /// millisecond.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_flush_interval(Duration::from_secs(1));
+    /// let handle = logger.handle();
+    /// logger.init(log::LevelFilter::Info);
+    ///
+    /// log::info!("Delivered within a second");
+    /// handle.stop_periodic_flush();
+    /// ```
+    #[must_use]
+    pub const fn with_flush_interval(mut self, interval: Duration) -> Self {
+        self.flush_interval = Some(interval);
+        self
+    }
+
+    /// Adds a layer that processes every enriched record before it reaches the inner logger.
+    ///
+    /// Layers run in the order they were added: the first added layer receives the
+    /// enriched record, each next one receives the record returned by the previous layer.
+    /// If a layer drops the record, the remaining layers are skipped. The record passed
+    /// to the inner logger is rebuilt from the record returned by the last layer.
+    /// Records replayed from the [debug buffer](Self::with_debug_buffer) are processed
+    /// when they are replayed, so they are redacted like any other record.
+    ///
+    /// See [`layer::ContextLayer`] for an example.
+    #[must_use]
+    pub fn with_layer(mut self, layer: impl layer::ContextLayer) -> Self {
+        self.layers.push(Box::new(layer));
+        self
+    }
+
+    /// Keeps the last `capacity` records below the logger's level, down to `level`, per
+    /// thread and replays them before the next error record emitted on the same thread.
+    ///
+    /// Records are buffered together with their default and scope records, so an error
+    /// comes with the lead-up to it even if debug logging is off. Replayed records are
+    /// filtered, sampled, counted and processed like any other record when they are
+    /// emitted. Each logger keeps its own buffers. Records buffered on the calling thread
+    /// are also emitted by [`log::Log::flush`].
+    ///
+    /// Since records down to `level` have to reach the logger, the global maximum level,
+    /// as reported by [`max_level`] and [`log::log_enabled!`], is lowered to `level`, while
+    /// the level passed to [`Self::init`] is enforced by the logger itself. The inner
+    /// logger has to accept the replayed records.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use log::LevelFilter;
+    /// use context_logger::ContextLogger;
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().filter_level(LevelFilter::Trace).build())
+    ///     .with_debug_buffer(64, LevelFilter::Debug);
+    /// logger.init(LevelFilter::Info);
+    ///
+    /// log::trace!("Resolving the database host"); // Discarded
+    /// log::debug!("Connecting to the database"); // Buffered
+    /// log::error!("Query failed"); // Emitted after the buffered record
+    /// ```
+    #[must_use]
+    pub fn with_debug_buffer(mut self, capacity: usize, level: log::LevelFilter) -> Self {
+        self.debug_buffer = Some(DebugBuffer::new(capacity, level));
+        self
+    }
+
+    /// Sets a logger that additionally receives records whose context contains the given key.
+    ///
+    /// Records logged while a default or scope record with the marker key is active are
+    /// passed to both the inner logger and the audit sink, other records are passed only
+    /// to the inner logger. The value of the marker record is not taken into account.
+    /// Records passed to the audit sink are never [collapsed](Self::with_collapse_repeats).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+    ///
+    /// let logger = ContextLogger::new(env_logger::builder().build())
+    ///     .with_audit_sink("audit", env_logger::builder().build());
+    ///
+    /// LogContext::new().with_local_record("audit", true).in_scope(|| {
+    ///     log::info!("Permissions changed");
+    /// });
+    /// ```
+    #[must_use]
+    pub fn with_audit_sink<L>(mut self, key: impl Into<Cow<'static, str>>, logger: L) -> Self
+    where
+        L: log::Log + 'static,
+    {
+        self.audit_sink = Some(AuditSink {
+            key: key.into(),
+            logger: Box::new(logger),
+        });
+        self
+    }
+}
+
+impl std::fmt::Debug for ContextLogger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContextLogger").finish_non_exhaustive()
+    }
+}
+
+impl ContextLogger {
+    /// Enriches the record with the given records and passes it to the inner logger.
+    ///
+    /// Returns the observation of the forwarded record, which is passed to the observers
+    /// once the scope stack is released.
+    fn log_with_records<'a, I>(
+        &self,
+        inner: &dyn log::Log,
+        record: &'a log::Record,
+        records: I,
+    ) -> Option<Observation>
+    where
+        I: Iterator<Item = LogRecordRef<'a>> + Clone,
+    {
+        let null_repr = self.null_repr;
+        let records = records.filter_map(move |(key, value)| Some((key, null_repr.apply(value)?)));
+
+        if !self.is_enabled_in_context(record, records.clone()) {
+            if let Some(buffer) = &self.debug_buffer {
+                buffer.push(record, records);
+            }
+            return None;
+        }
+        if !self.forward(inner, record, records.clone()) {
+            return None;
+        }
+        self.observation(record, records, CapturedContext::try_with_frames)
+    }
+
+    /// Filters, processes and dispatches the enriched record, returning `false` if it
+    /// was dropped on the way.
+    ///
+    /// Records replayed from the [debug buffer](Self::with_debug_buffer) take the same
+    /// path, so they are processed exactly like the records emitted right away.
+    fn forward<'a, I>(&self, inner: &dyn log::Log, record: &'a log::Record, records: I) -> bool
+    where
+        I: Iterator<Item = LogRecordRef<'a>> + Clone,
+    {
+        if self.is_filtered_out(record, || ContextView::new(records.clone().collect())) {
+            return false;
+        }
+        if self
+            .keyed_sampler
+            .as_ref()
+            .is_some_and(|sampler| !sampler.sample(record.level(), records.clone()))
+        {
+            return false;
+        }
+        if let (Some(buffer), log::Level::Error) = (self.debug_buffer, record.level()) {
+            buffer.drain(|buffered| self.replay(inner, buffered));
+        }
+
+        let audit_sink = self.audit_sink.as_ref().filter(|audit_sink| {
+            audit_sink.logger.enabled(record.metadata())
+                && records.clone().any(|(key, _)| *key == audit_sink.key)
+        });
+
+        if !self.layers.is_empty() {
+            let Some(owned) = self.apply_layers(record, records.clone()) else {
+                return false;
+            };
+            self.count_error(record.level(), records);
+            owned.with_record(|record| self.dispatch(inner, audit_sink, record));
+            return true;
+        }
+
+        self.count_error(record.level(), records.clone());
+
+        if self.key_policy == KeyPolicy::Allow && self.max_serde_depth.is_none() {
+            let source = SourceWithRecords {
+                source: record.key_values(),
+                records,
+                order: self.kv_order,
+            };
+            self.dispatch(
+                inner,
+                audit_sink,
+                &record.to_builder().key_values(&source).build(),
+            );
+            return true;
+        }
+
+        let records = records
+            .filter_map(|(key, value)| Some((self.apply_key_policy(key)?, self.limit(value))))
+            .collect::<Vec<_>>();
+        let source = SourceWithRecords {
+            source: record.key_values(),
+            records: records.iter().map(|(key, value)| (key, &**value)),
+            order: self.kv_order,
+        };
+        self.dispatch(
+            inner,
+            audit_sink,
+            &record.to_builder().key_values(&source).build(),
+        );
+        true
+    }
+
+    /// Runs the [layers](Self::with_layer) over an owned copy of the enriched record,
+    /// returning `None` if one of them drops it.
+    fn apply_layers<'a, I>(&self, record: &log::Record, records: I) -> Option<layer::OwnedRecord>
+    where
+        I: Iterator<Item = LogRecordRef<'a>> + Clone,
+    {
+        let context = ContextView::new(records.clone().collect());
+        let processed = records
+            .filter_map(|(key, value)| Some((self.apply_key_policy(key)?, self.limit(value))))
+            .collect::<Vec<_>>();
+        let owned = layer::OwnedRecord::capture(
+            record,
+            processed.iter().map(|(key, value)| (key, &**value)),
+        );
+        self.layers
+            .iter()
+            .try_fold(owned, |owned, layer| layer.process(owned, &context))
+    }
+
+    /// Counts the record by the [error counter](Self::with_error_counter), if any.
+    fn count_error<'a>(&self, level: log::Level, records: impl Iterator<Item = LogRecordRef<'a>>) {
+        if let Some(counter) = &self.error_counter {
+            counter.count(level, records);
+        }
+    }
+
+    /// Forwards a record replayed from the debug buffer together with its context records.
+    fn replay(&self, inner: &dyn log::Log, buffered: &replay::BufferedRecord) {
+        buffered.with_record(|record, records| {
+            self.forward(
+                inner,
+                record,
+                records.iter().map(|(key, value)| (key, value)),
+            );
+        });
+    }
+
+    /// Applies the [key policy](Self::with_strict_keys) to the key, reporting dropped keys
+    /// to the [observer](Self::with_invalid_key_observer), if any.
+    #[allow(
+        clippy::ptr_arg,
+        reason = "cloning the `Cow` keeps static keys borrowed"
+    )]
+    fn apply_key_policy(&self, key: &Cow<'static, str>) -> Option<Cow<'static, str>> {
+        let applied = self.key_policy.apply(key);
+        if let (None, Some(observer)) = (&applied, &self.invalid_key_observer) {
+            observer(key);
+        }
+        applied
+    }
+
+    /// Applies the [maximum depth](Self::with_max_serde_depth) of `serde` values, if any.
+    fn limit<'a>(&self, value: &'a LogValue) -> Cow<'a, LogValue> {
+        self.max_serde_depth.map_or(Cow::Borrowed(value), |depth| {
+            value.with_max_serde_depth(depth)
+        })
+    }
+
+    /// Passes the enriched record to the inner logger and the audit sink, if any.
+    fn dispatch(&self, inner: &dyn log::Log, audit_sink: Option<&AuditSink>, record: &log::Record) {
+        self.emit(inner, record);
+        if let Some(audit_sink) = audit_sink {
+            let _forwarding = self.self_timing.is_some().then(Forwarding::start);
+            audit_sink.logger.log(record);
+        }
+    }
+
+    /// Returns `true` if the level and the context directives, if any, enable the record.
+    fn is_enabled_in_context<'a>(
+        &self,
+        record: &log::Record,
+        records: impl Iterator<Item = LogRecordRef<'a>> + Clone,
+    ) -> bool {
+        let base_level = self
+            .max_level
+            .get()
+            .copied()
+            .unwrap_or(log::LevelFilter::Trace);
+        self.context_directives.as_ref().map_or_else(
+            // The global level is lowered without directives only if records are buffered.
+            || self.debug_buffer.is_none() || record.level() <= base_level,
+            |directives| directives.enabled(record.level(), base_level, records),
+        )
+    }
+
+    /// Returns `true` if the target filter, if any, enables records with the given metadata.
+    fn is_target_enabled(&self, metadata: &log::Metadata) -> bool {
+        self.target_filter
+            .as_ref()
+            .is_none_or(|filter| filter.enabled(metadata))
+    }
+
+    /// Returns `true` if the record filter, if any, rejects the record.
+    fn is_filtered_out<'a>(
+        &self,
+        record: &log::Record,
+        view: impl FnOnce() -> ContextView<'a>,
+    ) -> bool {
+        self.record_filter
+            .as_ref()
+            .is_some_and(|filter| !filter(record, &view()))
+    }
+
+    /// Returns the observation of a forwarded record, if there are any observers.
+    ///
+    /// The records the forwarded record is enriched with are copied only if they are
+    /// [observed](Self::with_on_record), the snapshot of the context is taken only if it
+    /// is [captured](Self::with_error_context_capture).
+    fn observation<'a>(
+        &self,
+        record: &log::Record,
+        records: impl Iterator<Item = LogRecordRef<'a>>,
+        snapshot: impl FnOnce() -> CapturedContext,
+    ) -> Option<Observation> {
+        if self.record_observer.is_none() && self.error_context_capture.is_none() {
+            return None;
+        }
+        let records = if self.record_observer.is_some() {
+            records
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let captured = self
+            .error_context_capture
+            .as_ref()
+            .filter(|_| record.level() <= log::Level::Warn)
+            .map(|_| snapshot());
+        Some(Observation { records, captured })
+    }
+
+    /// Passes the observed record to the [observer](Self::with_on_record) and errors and
+    /// warnings to the [error context capture](Self::with_error_context_capture), if any.
+    ///
+    /// Must be called after the scope stack is released, since the observers may use it.
+    fn observe(&self, record: &log::Record, observation: Option<Observation>) {
+        let Some(observation) = observation else {
+            return;
+        };
+        if let Some(observer) = &self.record_observer {
+            let view = observation
+                .records
+                .iter()
+                .map(|(key, value)| (key, value))
+                .collect();
+            observer(record, &ContextView::new(view));
+        }
+        if let Some((capture, captured)) = self
+            .error_context_capture
+            .as_ref()
+            .zip(observation.captured)
+        {
+            capture(record, captured);
+        }
+    }
+
+    /// Returns `true` if a record with the given key is emitted from the given frames.
+    fn is_set_in_context(&self, frames: &[scope::stack::ScopeFrame], key: &str) -> bool {
+        let Some(top) = frames.last() else {
+            return false;
+        };
+        if top.context.inherited.contains_key(key) {
+            return true;
+        }
+        // Local records are emitted under prefixed keys.
+        if self.hierarchical_keys {
+            return false;
+        }
+        match self.inheritance {
+            Inheritance::Shadow => top.context.local.contains_key(key),
+            Inheritance::Merge => frames
+                .iter()
+                .any(|frame| frame.context.local.contains_key(key)),
+        }
+    }
+
+    /// Returns `true` if context processing is disabled for the given target.
+    fn is_excluded_target(&self, target: &str) -> bool {
+        self.excluded_targets
+            .iter()
+            .any(|prefix| target.starts_with(&**prefix))
+    }
+
+    /// Returns `true` if the record is passed to the inner logger without scope records.
+    fn is_context_skipped(
+        &self,
+        record: &log::Record,
+        config: Option<&ContextLoggerConfig>,
+    ) -> bool {
+        self.context_disabled
+            || config.is_some_and(|config| !config.is_context_enabled())
+            || self.is_excluded_target(record.target())
+    }
+
+    /// Returns `true` if every record is enriched with at least one default record.
+    fn has_default_records(&self, config: Option<&ContextLoggerConfig>) -> bool {
+        config.is_some_and(|config| !config.default_records().is_empty())
+            || !self.default_records.is_empty()
+            || !self.dynamic_default_records.is_empty()
+            || !self.level_default_records.is_empty()
+            || self.source_location
+            || self.message_field.is_some()
+    }
+
+    /// Returns the [breadcrumb](Self::with_breadcrumb_field) record of the given frames.
+    fn breadcrumb(
+        &self,
+        frames: &[scope::stack::ScopeFrame],
+    ) -> Option<(&Cow<'static, str>, LogValue)> {
+        let key = self.breadcrumb_field.as_ref()?;
+        let names = frames
+            .iter()
+            .filter_map(|frame| frame.context.name.as_deref())
+            .collect::<Vec<_>>();
+        (!names.is_empty()).then(|| (key, LogValue::from(names.join(">"))))
+    }
+
+    /// Computes the default records that depend on the record being logged.
+    fn dynamic_default_records(&self, record: &log::Record) -> Vec<(&Cow<'static, str>, LogValue)> {
+        let mut records = self
+            .dynamic_default_records
+            .iter()
+            .map(|(key, f)| (key, f(record)))
+            .collect::<Vec<_>>();
+        if self.source_location {
+            records.extend(source_location_records(record));
+        }
+        if let Some(key) = &self.message_field {
+            // An explicit key-value of the log statement takes precedence.
+            if record
+                .key_values()
+                .get(log::kv::Key::from_str(key))
+                .is_none()
+            {
+                records.push((key, LogValue::from_arguments(*record.args())));
+            }
+        }
+        records
+    }
+
+    /// Enriches the record and passes it to the inner logger.
+    fn log_record(&self, record: &log::Record) {
+        if !self.is_target_enabled(record.metadata()) || quiet::is_suppressed(record.level()) {
+            return;
+        }
+        let inner = self.inner.load();
+        if !inner.enabled(record.metadata()) {
+            self.observe_dropped(record);
+            return;
+        }
+        let _timer = self.self_timing.as_ref().map(SelfTiming::start);
+
+        let config_guard = config::load();
+        let config = config_guard.as_deref().map(Arc::as_ref);
+        if self.is_context_skipped(record, config) {
+            let observation = self.log_without_context(&**inner, record);
+            self.observe(record, observation);
+            return;
+        }
+
+        let observation = scope::stack::SCOPE_STACK.try_with(|stack| {
+            // The stack may be borrowed if a value being rendered re-enters it; the
+            // record is forwarded without the context instead of panicking.
+            let Some(frames) = stack.try_frames() else {
+                return self.log_without_context(&**inner, record);
+            };
+            // Fast path: records logged outside of any scope and without default records
+            // have nothing to be enriched with, so they are passed through as is.
+            if frames.is_empty()
+                && !self.has_default_records(config)
+                && self.record_filter.is_none()
+                && self.context_directives.is_none()
+                && self.debug_buffer.is_none()
+                && self.layers.is_empty()
+            {
+                self.emit(&**inner, record);
+                return self.observation(record, std::iter::empty(), CapturedContext::default);
+            }
+
+            let mut dynamic_default_records = self.dynamic_default_records(record);
+            dynamic_default_records.extend(self.breadcrumb(&frames));
+            let frames_ref: &[scope::stack::ScopeFrame] = &frames;
+            let default_records = self
+                .default_records
+                .iter()
+                .chain(
+                    self.level_default_records
+                        .get(&record.level())
+                        .into_iter()
+                        .flatten(),
+                )
+                .chain(
+                    config
+                        .into_iter()
+                        .flat_map(ContextLoggerConfig::default_records),
+                )
+                .chain(dynamic_default_records.iter().map(|(k, v)| (*k, v)))
+                .filter(|(key, _)| {
+                    self.default_override == DefaultOverridePolicy::KeepBoth
+                        || !self.is_set_in_context(frames_ref, key)
+                });
+
+            // Inherited records from outer scopes are copied into each newly entered
+            // frame on `enter()`, so the top frame always contains a complete, flat view
+            // of active inherited records. Only local records have to be collected from
+            // the outer frames when they are merged.
+            if let (Some(top), true) = (frames.last(), self.hierarchical_keys) {
+                let local = frames
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(depth, frame)| {
+                        frame.context.local.iter().map(move |(key, value)| {
+                            (Cow::Owned(format!("scope{depth}.{key}")), value)
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                return self.log_with_records(
+                    &**inner,
+                    record,
+                    default_records
+                        .chain(top.context.inherited.iter())
+                        .chain(local.iter().map(|(key, value)| (key, *value))),
+                );
+            }
+
+            match (frames.last(), self.inheritance) {
+                (None, _) => self.log_with_records(&**inner, record, default_records),
+                (Some(top), Inheritance::Shadow) => {
+                    self.log_with_records(&**inner, record, default_records.chain(top.records()))
+                }
+                (Some(top), Inheritance::Merge) => {
+                    // The records of the innermost scope come last, so that its inherited
+                    // records take precedence over the local records of outer scopes.
+                    let outer = &frames[..frames.len() - 1];
+                    let local = outer.iter().flat_map(|frame| frame.context.local.iter());
+                    self.log_with_records(
+                        &**inner,
+                        record,
+                        default_records.chain(local).chain(top.records()),
+                    )
+                }
+            }
+        });
+
+        match observation {
+            Ok(observation) => self.observe(record, observation),
+            Err(err) => {
+                // If the context stack is not available, log the original record.
+                inner.log(record);
+                // We can't use `log::error!` here because we are in the middle of logging and
+                // this invocation becomes recursive.
+                eprintln!("Error accessing context stack: {err}");
+            }
+        }
+    }
+
+    /// Passes the record to the inner logger without default and scope records.
+    ///
+    /// The record is still processed by the [layers](Self::with_layer), if any.
+    fn log_without_context(
+        &self,
+        inner: &dyn log::Log,
+        record: &log::Record,
+    ) -> Option<Observation> {
+        if !self.is_enabled_in_context(record, std::iter::empty())
+            || self.is_filtered_out(record, ContextView::default)
+        {
+            return None;
+        }
+        if self.layers.is_empty() {
+            self.emit(inner, record);
+        } else {
+            let owned = self.apply_layers(record, std::iter::empty())?;
+            owned.with_record(|record| self.emit(inner, record));
+        }
+        self.observation(record, std::iter::empty(), CapturedContext::default)
+    }
+
+    /// Passes a record rejected by the inner logger to the observer, if any.
+    fn observe_dropped(&self, record: &log::Record) {
+        if let Some(observer) = &self.dropped_record_observer {
+            // The observer is skipped if the thread-local stack is already destroyed.
+            let _ = scope::stack::SCOPE_STACK.try_with(|stack| {
+                let top = stack.try_top();
+                observer(record, top.as_deref().map(|frame| &frame.context));
+            });
+        }
+    }
+
+    /// Passes the enriched record to the inner logger.
+    fn emit(&self, inner: &dyn log::Log, record: &log::Record) {
+        let _forwarding = self.self_timing.is_some().then(Forwarding::start);
+        if let Some(collapser) = &self.collapser {
+            collapser.log(inner, record);
+        } else {
+            inner.log(record);
+        }
+    }
+}
+
+impl log::Log for ContextLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.is_target_enabled(metadata)
+            && !quiet::is_suppressed(metadata.level())
+            && self.inner.load().enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        // One-shot records are consumed by this record even if it is filtered out.
+        let next_records = once::take();
+        if next_records.is_empty() {
+            self.log_record(record);
+        } else {
+            let source = SourceWithRecords {
+                source: record.key_values(),
+                records: next_records.iter(),
+                order: kv::KvOrder::ContextFirst,
+            };
+            self.log_record(&record.to_builder().key_values(&source).build());
+        }
+    }
+
+    /// Emits the records held by the logger itself and flushes the inner loggers.
+    ///
+    /// The internal buffers are drained first, in this order: the records kept by the
+    /// [debug buffer](Self::with_debug_buffer) of the calling thread, which are replayed
+    /// exactly as before an error record, then the pending summary of
+    /// [collapsed repeats](Self::with_collapse_repeats). Only then the inner
+    /// logger and the audit sink, if any, are flushed, so everything logged so far is
+    /// delivered when this method returns.
+    fn flush(&self) {
+        let inner = self.inner.load();
+        if let Some(buffer) = self.debug_buffer {
+            buffer.drain(|buffered| self.replay(&**inner, buffered));
+        }
+        if let Some(collapser) = &self.collapser {
+            collapser.flush(&**inner);
+        }
+        inner.flush();
+        if let Some(audit_sink) = &self.audit_sink {
+            audit_sink.logger.flush();
+        }
+    }
+}
+
+/// Enters the given context, runs a closure, and exits the scope automatically.
+///
+/// This is a free function form of [`LogScope::in_scope`]. The scope is exited even if
+/// the closure panics.
+///
+/// # Example
+///
+/// ```
+/// use context_logger::{LogContext, with_context};
+///
+/// let context = LogContext::new().with_local_record("request_id", "req-123");
+/// let result = with_context(context, || {
+///     log::info!("Handling request"); // Will include request_id
+///     40 + 2
+/// });
+/// assert_eq!(result, 42);
+/// ```
+pub fn with_context<R>(context: LogContext, f: impl FnOnce() -> R) -> R {
+    LogScope::in_scope(context, f)
+}
+
+/// Returns the current maximum level of the global logger.
+///
+/// Libraries can use it to skip building expensive context when the
+/// corresponding records would be filtered out anyway. The level is more verbose than
+/// the one passed to [`ContextLogger::init`] if context directives or a
+/// [debug buffer](ContextLogger::with_debug_buffer) need the records below it.
+///
+/// # Example
+///
+/// ```
+/// use log::LevelFilter;
+/// use context_logger::{LogContext, LogContextExt as _};
+///
+/// if context_logger::max_level() >= LevelFilter::Debug {
+///     let context = LogContext::new().with_local_record("details", "expensive");
+///     context.in_scope(|| log::debug!("Detailed message"));
+/// }
+/// ```
+#[must_use]
+pub fn max_level() -> log::LevelFilter {
+    log::max_level()
+}
+
+/// Maps the level to the syslog severity defined by RFC 5424.
+///
+/// Errors map to `3` (error), warnings to `4` (warning), info to `6` (informational),
+/// and both debug and trace to `7` (debug). Intended for [`ContextLogger::with_level_map`].
+#[must_use]
+pub const fn syslog_severity(level: log::Level) -> i32 {
+    match level {
+        log::Level::Error => 3,
+        log::Level::Warn => 4,
+        log::Level::Info => 6,
+        log::Level::Debug | log::Level::Trace => 7,
+    }
+}
+
+/// A handle to the inner logger of a [`ContextLogger`].
+///
+/// Created by [`ContextLogger::handle`].
+#[derive(Clone)]
+pub struct ContextLoggerHandle {
+    inner: InnerLogger,
+    max_level: Arc<OnceLock<log::LevelFilter>>,
+    periodic_flush: Arc<PeriodicFlush>,
+}
+
+impl ContextLoggerHandle {
+    /// Atomically replaces the inner logger.
+    ///
+    /// Records logged concurrently with the swap are delivered either to the previous
+    /// or to the new logger. The previous logger is flushed and dropped once no
+    /// in-flight records refer to it.
+    pub fn set_inner<L>(&self, inner: L)
+    where
+        L: log::Log + 'static,
+    {
+        let previous = self.inner.swap(Arc::new(Box::new(inner)));
+        previous.flush();
+    }
+
+    /// Returns the maximum level the logger was initialized with.
+    ///
+    /// Returns `None` if the logger has not been installed by [`ContextLogger::init`]
+    /// or [`ContextLogger::try_init`]. Note that the effective level may be changed
+    /// later by [`log::set_max_level`], use [`max_level`] to get the current one.
+    #[must_use]
+    pub fn max_level(&self) -> Option<log::LevelFilter> {
+        self.max_level.get().copied()
+    }
+
+    /// Stops the background thread started by [`ContextLogger::with_flush_interval`].
+    ///
+    /// Signals the thread to stop, waits for it to finish and flushes the inner logger
+    /// one last time, so no buffered records are left behind at shutdown. Does nothing
+    /// if no flush thread is running.
+    pub fn stop_periodic_flush(&self) {
+        if self.periodic_flush.stop() {
+            self.inner.load().flush();
+        }
+    }
+}
+
+impl std::fmt::Debug for ContextLoggerHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContextLoggerHandle")
+            .finish_non_exhaustive()
+    }
+}
+
+fn source_location_records<'a>(
+    record: &log::Record<'_>,
+) -> impl Iterator<Item = (&'a Cow<'static, str>, LogValue)> {
+    let file = record
+        .file_static()
+        .map(|file| LogValue::from(Cow::Borrowed(file)))
+        .or_else(|| record.file().map(LogValue::from));
+    let module = record
+        .module_path_static()
+        .map(|module| LogValue::from(Cow::Borrowed(module)))
+        .or_else(|| record.module_path().map(LogValue::from));
+    let line = record.line().map(LogValue::from);
+
+    [
+        (&SOURCE_FILE_KEY, file),
+        (&SOURCE_LINE_KEY, line),
+        (&SOURCE_MODULE_KEY, module),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| Some((key, value?)))
+}
+
+/// A logger that discards all records.
+struct NopLogger;
+
+impl log::Log for NopLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        false
+    }
+
+    fn log(&self, _record: &log::Record) {}
+
+    fn flush(&self) {}
+}
+
+/// A logger that renders and discards all records.
+struct SinkLogger;
+
+impl log::Log for SinkLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        struct Render;
+
+        impl std::fmt::Write for Render {
+            fn write_str(&mut self, _s: &str) -> std::fmt::Result {
+                Ok(())
+            }
+        }
+
+        impl<'kvs> log::kv::VisitSource<'kvs> for Render {
+            fn visit_pair(
+                &mut self,
+                key: log::kv::Key<'kvs>,
+                value: log::kv::Value<'kvs>,
+            ) -> Result<(), log::kv::Error> {
+                std::fmt::Write::write_fmt(self, format_args!("{key}={value}"))?;
+                Ok(())
+            }
+        }
+
+        // Rendering can only fail inside user provided values, which is not our concern.
+        let _ = record.key_values().visit(&mut Render);
+    }
+
+    fn flush(&self) {}
+}
+
+struct SourceWithRecords<'a, I> {
+    source: &'a dyn log::kv::Source,
+    records: I,
+    order: kv::KvOrder,
+}
+
+impl<'a, I> log::kv::Source for SourceWithRecords<'a, I>
+where
+    I: Iterator<Item = LogRecordRef<'a>> + Clone,
+{
+    fn visit<'kvs>(
+        &'kvs self,
+        visitor: &mut dyn log::kv::VisitSource<'kvs>,
+    ) -> Result<(), log::kv::Error> {
+        if self.order == kv::KvOrder::SourceFirst {
+            self.source.visit(visitor)?;
+        }
+        for (key, value) in self.records.clone() {
+            visitor.visit_pair(log::kv::Key::from_str(key), value.as_log_value())?;
+        }
+        if self.order == kv::KvOrder::ContextFirst {
+            self.source.visit(visitor)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the value a backend with "last write wins" semantics would observe for the key.
+    fn get(&self, key: log::kv::Key<'_>) -> Option<log::kv::Value<'_>> {
+        let context_value = || {
+            self.records
+                .clone()
+                .filter(|(record_key, _)| **record_key == *key.as_str())
+                .last()
+                .map(|(_, value)| value.as_log_value())
+        };
+        match self.order {
+            kv::KvOrder::ContextFirst => self.source.get(key.clone()).or_else(context_value),
+            kv::KvOrder::SourceFirst => context_value().or_else(|| self.source.get(key)),
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.records.clone().count() + self.source.count()
     }
 }
 
 mod private {
     pub trait Sealed {}
 
-    impl<F: Future> Sealed for F {}
     impl Sealed for crate::LogContext {}
 }
@@ -0,0 +1,160 @@
+//! A mapped diagnostic context (MDC) API familiar to SLF4J and log4j users.
+//!
+//! The functions in this module operate on the records of the currently active scope,
+//! so they interoperate with [`LogScope`](crate::LogScope) guards: records put while a
+//! scope is active are removed together with the scope. Records are put into the
+//! inherited records of the scope and thus flow into nested scopes entered afterwards.
+//!
+//! If there is no active scope, [`put`] enters a new one that stays active on the
+//! current thread until [`LogScope::clear_current_thread`](crate::LogScope::clear_current_thread)
+//! is called, mirroring the thread-bound nature of the MDC.
+//!
+//! # Examples
+//!
+//! ```
+//! use context_logger::mdc;
+//!
+//! mdc::put("request_id", "req-123");
+//! log::info!("Handling request"); // Includes request_id="req-123"
+//!
+//! assert_eq!(mdc::get("request_id").unwrap().to_string(), "req-123");
+//! mdc::clear();
+//! assert!(mdc::get("request_id").is_none());
+//! ```
+
+use std::borrow::Cow;
+
+use crate::{LogContext, LogValue, scope::stack::SCOPE_STACK};
+
+/// Puts a record into the currently active scope, replacing the previous value of the key.
+///
+/// Enters a new scope if there is no active one. Does nothing if called while the scope
+/// stack is being read, e.g. from a value rendered by the logger.
+pub fn put(key: impl Into<Cow<'static, str>>, value: impl Into<LogValue>) {
+    SCOPE_STACK.with(|stack| {
+        if let Some(mut top) = stack.try_top_mut() {
+            top.context.inherited.insert(key, value);
+            return;
+        }
+        // The stack is either empty or borrowed, a new scope is entered only in the
+        // former case.
+        stack.try_push(LogContext::new().with_inherited_record(key, value));
+    });
+}
+
+/// Returns the value of the record with the given key in the currently active scope.
+///
+/// Local records of the scope take precedence over inherited ones. Returns `None` if
+/// called while the scope stack is being modified.
+#[must_use]
+pub fn get(key: &str) -> Option<LogValue> {
+    SCOPE_STACK.with(|stack| {
+        let top = stack.try_top()?;
+        top.context
+            .local
+            .get(key)
+            .or_else(|| top.context.inherited.get(key))
+            .cloned()
+    })
+}
+
+/// Removes the record with the given key from the currently active scope, returning its value.
+///
+/// Both local and inherited records with the key are removed. If both exist, the value
+/// of the local record is returned.
+///
+/// Returns `None` without removing anything if called while the scope stack is being
+/// read, e.g. from a value rendered by the logger.
+#[allow(
+    clippy::must_use_candidate,
+    reason = "records are often removed without using their values"
+)]
+pub fn remove(key: &str) -> Option<LogValue> {
+    SCOPE_STACK.with(|stack| {
+        let mut top = stack.try_top_mut()?;
+        let inherited = top.context.inherited.remove(key);
+        top.context.local.remove(key).or(inherited)
+    })
+}
+
+/// Removes all records from the currently active scope.
+///
+/// Outer scopes are not affected, so their records become visible again once
+/// the current scope is exited. Does nothing if called while the scope stack is being
+/// read, e.g. from a value rendered by the logger.
+pub fn clear() {
+    SCOPE_STACK.with(|stack| {
+        if let Some(mut top) = stack.try_top_mut() {
+            top.context = LogContext::new();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{LogContext, LogScope, mdc, scope::stack::SCOPE_STACK};
+
+    #[test]
+    fn test_mdc_without_active_scope() {
+        assert!(mdc::get("user_id").is_none());
+        assert!(mdc::remove("user_id").is_none());
+
+        mdc::put("user_id", 42);
+        mdc::put("request_id", "req-1");
+        assert_eq!(mdc::get("user_id").unwrap().to_string(), "42");
+
+        mdc::put("user_id", 7);
+        assert_eq!(mdc::get("user_id").unwrap().to_string(), "7");
+        assert_eq!(LogScope::current_context().inherited.iter().count(), 2);
+
+        assert_eq!(mdc::remove("user_id").unwrap().to_string(), "7");
+        assert!(mdc::get("user_id").is_none());
+
+        mdc::clear();
+        assert!(mdc::get("request_id").is_none());
+        assert!(LogScope::current_context().is_empty());
+
+        assert_eq!(LogScope::clear_current_thread(), 1);
+    }
+
+    #[test]
+    fn test_mdc_with_scope_guards() {
+        let _outer = LogScope::enter(LogContext::new().with_local_record("service", "api"));
+        mdc::put("request_id", "req-1");
+
+        {
+            let _inner = LogScope::enter(LogContext::new());
+            // Records put into the outer scope are inherited.
+            assert_eq!(mdc::get("request_id").unwrap().to_string(), "req-1");
+            mdc::put("user_id", 42);
+            mdc::clear();
+            assert!(mdc::get("request_id").is_none());
+        }
+
+        // The outer scope is not affected by the changes made in the inner scope.
+        assert_eq!(mdc::get("service").unwrap().to_string(), "api");
+        assert_eq!(mdc::get("request_id").unwrap().to_string(), "req-1");
+        assert!(mdc::get("user_id").is_none());
+    }
+
+    #[test]
+    fn test_mdc_while_stack_is_borrowed() {
+        let _scope = LogScope::enter(LogContext::new().with_local_record("service", "api"));
+
+        SCOPE_STACK.with(|stack| {
+            let _frames = stack.frames();
+            // The record is skipped instead of panicking.
+            mdc::put("request_id", "req-1");
+            assert_eq!(mdc::get("service").unwrap().to_string(), "api");
+        });
+        SCOPE_STACK.with(|stack| {
+            let _top = stack.top_mut();
+            assert!(mdc::get("service").is_none());
+        });
+
+        assert!(mdc::get("request_id").is_none());
+        assert_eq!(mdc::get("service").unwrap().to_string(), "api");
+    }
+}
@@ -0,0 +1,53 @@
+//! Records attached only to the next record logged on the current thread.
+
+use std::{borrow::Cow, cell::RefCell};
+
+use crate::{LogContext, LogRecords, LogValue};
+
+thread_local! {
+    /// Records waiting for the next record logged on the current thread.
+    static NEXT_RECORDS: RefCell<LogRecords> = RefCell::new(LogRecords::new());
+}
+
+impl LogContext {
+    /// Attaches a record only to the next record logged on the current thread.
+    ///
+    /// This annotates a single log line without entering a scope. The record is consumed
+    /// by the next record that reaches the [`ContextLogger`](crate::ContextLogger), even
+    /// if that record is then filtered out, so it never leaks to later records. Records
+    /// disabled by the global maximum level do not reach the logger and don't consume it.
+    /// If a one-shot record with the same key is already pending, its value is replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::LogContext;
+    ///
+    /// LogContext::record_once("retry_after_ms", 250);
+    /// log::warn!("Rate limited"); // Includes retry_after_ms
+    /// log::info!("Retrying"); // Does not
+    /// ```
+    pub fn record_once(key: impl Into<Cow<'static, str>>, value: impl Into<LogValue>) {
+        // The record is silently skipped if the thread-local storage is being destroyed.
+        let _ = NEXT_RECORDS.try_with(|records| {
+            if let Ok(mut records) = records.try_borrow_mut() {
+                records.insert(key, value);
+            }
+        });
+    }
+}
+
+/// Takes the records pending for the next record on the current thread.
+pub fn take() -> LogRecords {
+    NEXT_RECORDS
+        .try_with(|records| {
+            records
+                .try_borrow_mut()
+                .ok()
+                .filter(|records| !records.is_empty())
+                .map(|mut records| std::mem::take(&mut *records))
+        })
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+}
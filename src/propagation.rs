@@ -0,0 +1,173 @@
+//! Propagation of log contexts across service boundaries.
+
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+
+use crate::{LogContext, LogValue};
+
+/// An error returned when a propagation header cannot be decoded.
+#[derive(Debug)]
+pub struct PropagationHeaderError(PropagationHeaderErrorKind);
+
+#[derive(Debug)]
+enum PropagationHeaderErrorKind {
+    Base64(base64::DecodeError),
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for PropagationHeaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            PropagationHeaderErrorKind::Base64(_) => {
+                f.write_str("propagation header is not valid base64")
+            }
+            PropagationHeaderErrorKind::Json(_) => {
+                f.write_str("propagation header does not contain a JSON object")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PropagationHeaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.0 {
+            PropagationHeaderErrorKind::Base64(err) => Some(err),
+            PropagationHeaderErrorKind::Json(err) => Some(err),
+        }
+    }
+}
+
+impl LogContext {
+    /// Encodes this context into a string suitable for an HTTP or gRPC header.
+    ///
+    /// The context is flattened into a single JSON object, local records overwrite
+    /// inherited records with the same key, and encoded with the URL-safe base64
+    /// alphabet without padding. Primitive and `serde` values round-trip as is, while
+    /// debug, display and error values are transmitted as strings.
+    ///
+    /// Use [`LogScope::current_context`](crate::LogScope::current_context) to propagate
+    /// the context of the active scope.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use context_logger::LogContext;
+    ///
+    /// let context = LogContext::new()
+    ///     .with_inherited_record("request_id", "req-123")
+    ///     .with_local_record("attempt", 2);
+    /// let header = context.to_propagation_header();
+    ///
+    /// // On the receiving side.
+    /// let context = LogContext::from_propagation_header(&header).unwrap();
+    /// ```
+    #[must_use]
+    pub fn to_propagation_header(&self) -> String {
+        let object = self
+            .inherited
+            .iter()
+            .chain(self.local.iter())
+            .map(|(key, value)| {
+                // Serialization of the `log` values only fails for custom `serde`
+                // values, such values are transmitted as strings instead.
+                let json = serde_json::to_value(value.as_log_value())
+                    .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+                (key.to_string(), json)
+            })
+            .collect::<serde_json::Map<_, _>>();
+
+        URL_SAFE_NO_PAD.encode(serde_json::Value::Object(object).to_string())
+    }
+
+    /// Decodes a context from a header created by [`LogContext::to_propagation_header`].
+    ///
+    /// All records of the decoded context are inherited, so they are attached to
+    /// every record logged in the scope of the context and its nested scopes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header is not valid base64 or does not contain a JSON object.
+    pub fn from_propagation_header(header: &str) -> Result<Self, PropagationHeaderError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(header.trim())
+            .map_err(|err| PropagationHeaderError(PropagationHeaderErrorKind::Base64(err)))?;
+        let object =
+            serde_json::from_slice::<serde_json::Map<String, serde_json::Value>>(&bytes)
+                .map_err(|err| PropagationHeaderError(PropagationHeaderErrorKind::Json(err)))?;
+
+        Ok(object
+            .into_iter()
+            .fold(Self::new(), |context, (key, value)| {
+                context.with_inherited_record(key, json_to_log_value(value))
+            }))
+    }
+}
+
+fn json_to_log_value(value: serde_json::Value) -> LogValue {
+    match value {
+        serde_json::Value::Null => LogValue::null(),
+        serde_json::Value::Bool(b) => b.into(),
+        serde_json::Value::Number(n) => n.into(),
+        serde_json::Value::String(s) => s.into(),
+        value @ (serde_json::Value::Array(_) | serde_json::Value::Object(_)) => {
+            LogValue::serde(value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{LogContext, LogValue};
+
+    #[derive(Debug)]
+    struct Opaque;
+
+    #[test]
+    fn test_propagation_header_round_trip() {
+        let context = LogContext::new()
+            .with_inherited_record("request_id", "req-123")
+            .with_inherited_record("attempt", 1)
+            .with_local_record("attempt", 2)
+            .with_local_record("ratio", 0.5)
+            .with_local_record("sampled", true)
+            .with_local_record("parent", LogValue::null())
+            .with_local_record("tags", LogValue::serde(vec!["a", "b"]))
+            .with_local_record("opaque", LogValue::debug(Opaque));
+
+        let header = context.to_propagation_header();
+        assert!(
+            header
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        );
+
+        let decoded = LogContext::from_propagation_header(&header).unwrap();
+        assert!(decoded.local.is_empty());
+        let records = &decoded.inherited;
+        assert_eq!(records["request_id"].to_string(), "req-123");
+        assert_eq!(records["attempt"].to_string(), "2");
+        assert_eq!(records["ratio"].to_string(), "0.5");
+        assert_eq!(records["sampled"].to_string(), "true");
+        assert_eq!(records["parent"].to_string(), "None");
+        assert_eq!(
+            serde_json::to_value(records["tags"].as_log_value()).unwrap(),
+            serde_json::json!(["a", "b"])
+        );
+        assert_eq!(records["opaque"].to_string(), "Opaque");
+    }
+
+    #[test]
+    fn test_invalid_propagation_header() {
+        let err = LogContext::from_propagation_header("not base64!").unwrap_err();
+        assert_eq!(err.to_string(), "propagation header is not valid base64");
+
+        let header =
+            base64::Engine::encode(&base64::engine::general_purpose::URL_SAFE_NO_PAD, "[1]");
+        let err = LogContext::from_propagation_header(&header).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "propagation header does not contain a JSON object"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Suppression of records logged on the current thread.
+
+use std::{cell::Cell, marker::PhantomData};
+
+use crate::LogContext;
+
+thread_local! {
+    /// The most verbose level that is still emitted on the current thread.
+    static QUIET_LEVEL: Cell<log::LevelFilter> = const { Cell::new(log::LevelFilter::Trace) };
+}
+
+/// A guard that suppresses records logged on the current thread while it is alive.
+///
+/// Created by [`LogContext::quiet`] and [`LogContext::quiet_below`]. Dropping the
+/// guard restores the previous state. Nested guards can only make the thread quieter.
+#[derive(Debug)]
+#[must_use = "records are suppressed only while the guard is alive"]
+pub struct QuietGuard {
+    previous: log::LevelFilter,
+    // Make this guard non-Send: it manages thread-local state.
+    _marker: PhantomData<*mut ()>,
+}
+
+impl QuietGuard {
+    fn new(level: log::LevelFilter) -> Self {
+        let previous = QUIET_LEVEL.with(|quiet| quiet.replace(quiet.get().min(level)));
+        Self {
+            previous,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl Drop for QuietGuard {
+    fn drop(&mut self) {
+        // The state is gone anyway if the thread-local storage is being destroyed.
+        let _ = QUIET_LEVEL.try_with(|quiet| quiet.set(self.previous));
+    }
+}
+
+impl LogContext {
+    /// Suppresses all records logged on the current thread until the returned guard
+    /// is dropped.
+    ///
+    /// This is handy for silencing a chatty third-party call. Records are dropped by the
+    /// [`ContextLogger`](crate::ContextLogger) before they reach the inner logger.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::LogContext;
+    ///
+    /// # fn noisy_library_call() { log::info!("Noise"); }
+    /// {
+    ///     let _quiet = LogContext::quiet();
+    ///     noisy_library_call(); // Logs nothing
+    /// }
+    /// log::info!("Logged as usual");
+    /// ```
+    pub fn quiet() -> QuietGuard {
+        QuietGuard::new(log::LevelFilter::Off)
+    }
+
+    /// Suppresses records less severe than `level` logged on the current thread until
+    /// the returned guard is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::LogContext;
+    ///
+    /// let _quiet = LogContext::quiet_below(log::Level::Warn);
+    /// log::info!("Suppressed");
+    /// log::warn!("Still logged");
+    /// ```
+    pub fn quiet_below(level: log::Level) -> QuietGuard {
+        QuietGuard::new(level.to_level_filter())
+    }
+}
+
+/// Returns `true` if records of the given level are suppressed on the current thread.
+pub fn is_suppressed(level: log::Level) -> bool {
+    QUIET_LEVEL
+        .try_with(|quiet| level > quiet.get())
+        .unwrap_or(false)
+}
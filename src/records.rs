@@ -1,9 +1,7 @@
-use std::{borrow::Cow, collections::HashMap};
+use std::{borrow::Cow, iter::FusedIterator};
 
 use crate::LogValue;
 
-pub type LogRecordsIter<'a> = std::collections::hash_map::Iter<'a, Cow<'static, str>, LogValue>;
-pub type LogRecordsIntoIter = std::collections::hash_map::IntoIter<Cow<'static, str>, LogValue>;
 pub type LogRecord = (Cow<'static, str>, LogValue);
 pub type LogRecordRef<'a> = (&'a Cow<'static, str>, &'a LogValue);
 
@@ -14,10 +12,27 @@ pub type LogRecordRef<'a> = (&'a Cow<'static, str>, &'a LogValue);
 ///
 /// # Ordering
 ///
-/// The order in which records appear is **not guaranteed**. Do not rely on any specific
-/// ordering of keys.
+/// Records are kept in insertion order. Replacing the value of an existing key keeps
+/// the record in its original position, while a removed and re-inserted key moves
+/// to the end.
+///
+/// # Keys
+///
+/// Keys are stored as [`Cow<'static, str>`](Cow). String literals are stored by reference
+/// without any allocation, while an owned [`String`] key is moved into [`Cow::Owned`]
+/// as is, without copying its buffer. Dynamically built keys can therefore be passed
+/// by value without an extra allocation:
+///
+/// ```
+/// use context_logger::LogRecords;
+///
+/// let shard = 3;
+/// let records = LogRecords::new()
+///     .with_record("static_key", 1)
+///     .with_record(format!("shard_{shard}"), true);
+/// ```
 #[derive(Debug, Clone, Default)]
-pub struct LogRecords(pub(crate) HashMap<Cow<'static, str>, LogValue>);
+pub struct LogRecords(pub(crate) Vec<LogRecord>);
 
 impl LogRecords {
     /// Creates a new, empty set of records.
@@ -26,6 +41,18 @@ impl LogRecords {
         Self::default()
     }
 
+    /// Creates a new, empty set of records with space for at least `capacity` records.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(Vec::with_capacity(capacity))
+    }
+
+    /// Returns the number of records this collection can hold without reallocating.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+
     /// Inserts a key-value record into this collection, returning the collection for chained calls.
     ///
     /// This method takes ownership of `self`, so it can be used as part of a
@@ -56,6 +83,9 @@ impl LogRecords {
     /// returns a mutable reference, allowing it to be used when chaining with other methods
     /// that require borrowing.
     ///
+    /// Setting a key to a value identical to the stored one is a no-op, so reasserting
+    /// the same record in a loop does not replace the stored value.
+    ///
     /// # Examples
     ///
     /// ```
@@ -71,7 +101,13 @@ impl LogRecords {
         key: impl Into<Cow<'static, str>>,
         value: impl Into<LogValue>,
     ) -> &mut Self {
-        self.0.insert(key.into(), value.into());
+        let key = key.into();
+        let value = value.into();
+        match self.get_mut(&key) {
+            Some(stored) if stored.is_same(&value) => {}
+            Some(stored) => *stored = value,
+            None => self.0.push((key, value)),
+        }
         self
     }
 
@@ -83,7 +119,8 @@ impl LogRecords {
     /// # Merging policy
     ///
     /// Keys in this collection with duplicate names will be overwritten by keys from the
-    /// provided collection. The order of keys in the resulting collection is undefined.
+    /// provided collection, keeping their position. New keys are appended in the order
+    /// of the provided collection.
     ///
     /// # Examples
     ///
@@ -98,14 +135,34 @@ impl LogRecords {
     ///     .insert("request_id", 42);
     /// ```
     pub fn merge_with(&mut self, other: impl IntoIterator<Item = LogRecord>) -> &mut Self {
-        self.0.extend(other);
+        for (key, value) in other {
+            self.replace(key, value);
+        }
+        self
+    }
+
+    /// Retains only the records for which the predicate returns `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::LogRecords;
+    ///
+    /// let mut records = LogRecords::new()
+    ///     .with_record("user_id", "user-123")
+    ///     .with_record("internal.trace", true);
+    /// records.retain(|key, _| !key.starts_with("internal."));
+    /// assert_eq!(records.iter().count(), 1);
+    /// ```
+    pub fn retain(&mut self, mut f: impl FnMut(&str, &LogValue) -> bool) -> &mut Self {
+        self.0.retain(|(key, value)| f(key, value));
         self
     }
 
     /// Returns an iterator over the records in this collection.
     #[must_use]
     pub fn iter(&self) -> LogRecordsIter<'_> {
-        self.0.iter()
+        LogRecordsIter(self.0.iter())
     }
 
     /// Returns `true` if this collection contains no records.
@@ -129,19 +186,64 @@ impl IntoIterator for LogRecords {
     type IntoIter = LogRecordsIntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        LogRecordsIntoIter(self.0.into_iter())
     }
 }
 
 impl Extend<LogRecord> for LogRecords {
     fn extend<I: IntoIterator<Item = LogRecord>>(&mut self, iter: I) {
-        self.0.extend(iter);
+        self.merge_with(iter);
     }
 }
 
 impl FromIterator<LogRecord> for LogRecords {
     fn from_iter<T: IntoIterator<Item = LogRecord>>(iter: T) -> Self {
-        Self(HashMap::from_iter(iter))
+        let mut records = Self::new();
+        records.merge_with(iter);
+        records
+    }
+}
+
+impl LogRecords {
+    /// Returns the value of the record with the given key, if any.
+    pub(crate) fn get(&self, key: &str) -> Option<&LogValue> {
+        self.0
+            .iter()
+            .find_map(|(record_key, value)| (record_key == key).then_some(value))
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut LogValue> {
+        self.0
+            .iter_mut()
+            .find_map(|(record_key, value)| (record_key == key).then_some(value))
+    }
+
+    /// Returns `true` if there is a record with the given key.
+    pub(crate) fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns the number of records in this collection.
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Sets the value of the record with the given key, returning the previous value.
+    pub(crate) fn replace(&mut self, key: Cow<'static, str>, value: LogValue) -> Option<LogValue> {
+        if let Some(stored) = self.get_mut(&key) {
+            return Some(std::mem::replace(stored, value));
+        }
+        self.0.push((key, value));
+        None
+    }
+
+    /// Removes the record with the given key, returning its value.
+    pub(crate) fn remove(&mut self, key: &str) -> Option<LogValue> {
+        let index = self
+            .0
+            .iter()
+            .position(|(record_key, _)| record_key == key)?;
+        Some(self.0.remove(index).1)
     }
 }
 
@@ -149,17 +251,149 @@ impl FromIterator<LogRecord> for LogRecords {
 impl LogRecords {
     /// Returns a reference to the value associated with the given key, if it exists.
     pub(crate) fn find(&self, key: impl AsRef<str>) -> Option<&LogValue> {
-        self.0.get(&Cow::Owned(key.as_ref().to_owned()))
+        self.get(key.as_ref())
+    }
+}
+
+/// An iterator over the records of [`LogRecords`] in insertion order.
+#[derive(Debug, Clone)]
+pub struct LogRecordsIter<'a>(std::slice::Iter<'a, LogRecord>);
+
+impl<'a> Iterator for LogRecordsIter<'a> {
+    type Item = LogRecordRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, value)| (key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for LogRecordsIter<'_> {}
+
+impl FusedIterator for LogRecordsIter<'_> {}
+
+/// An owning iterator over the records of [`LogRecords`] in insertion order.
+#[derive(Debug)]
+pub struct LogRecordsIntoIter(std::vec::IntoIter<LogRecord>);
+
+impl Iterator for LogRecordsIntoIter {
+    type Item = LogRecord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
     }
 }
 
+impl ExactSizeIterator for LogRecordsIntoIter {}
+
+impl FusedIterator for LogRecordsIntoIter {}
+
 #[cfg(test)]
 impl std::ops::Index<&str> for LogRecords {
     type Output = LogValue;
 
     fn index(&self, index: &str) -> &Self::Output {
-        self.0
-            .get(&Cow::Owned(index.to_owned()))
-            .expect("No record found for the given key")
+        self.get(index).expect("No record found for the given key")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use pretty_assertions::assert_eq;
+
+    use super::LogRecords;
+
+    #[test]
+    fn test_owned_key_is_moved() {
+        let key = format!("dynamic_{}", 42);
+        let ptr = key.as_ptr();
+
+        let records = LogRecords::new().with_record(key, true);
+        let (stored, _) = records.iter().next().unwrap();
+        let Cow::Owned(stored) = stored else {
+            panic!("expected an owned key");
+        };
+        assert_eq!(stored.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_duplicate_key_is_replaced() {
+        let records = LogRecords::new()
+            .with_record("id", 1)
+            .with_record("id", 2)
+            .with_record(String::from("id"), 3);
+
+        assert_eq!(records.iter().count(), 1);
+        assert_eq!(records["id"].to_string(), "3");
+    }
+
+    #[test]
+    fn test_static_key_is_borrowed() {
+        static KEY: &str = "static_key";
+
+        let records = LogRecords::new().with_record(KEY, true);
+        let (stored, _) = records.iter().next().unwrap();
+        let Cow::Borrowed(stored) = stored else {
+            panic!("expected a borrowed key");
+        };
+        assert_eq!(stored.as_ptr(), KEY.as_ptr());
+    }
+
+    #[test]
+    fn test_insert_same_value_is_noop() {
+        let mut records = LogRecords::with_capacity(1);
+        records.insert("user_id", String::from("user-123"));
+        let capacity = records.capacity();
+        let stored = records.find("user_id").unwrap().as_str().unwrap().as_ptr();
+
+        for _ in 0..100 {
+            records.insert("user_id", String::from("user-123"));
+        }
+
+        assert_eq!(records.iter().count(), 1);
+        assert_eq!(records.capacity(), capacity);
+        // The originally stored string is kept.
+        assert_eq!(
+            records.find("user_id").unwrap().as_str().unwrap().as_ptr(),
+            stored
+        );
+
+        records.insert("user_id", "user-456");
+        assert_eq!(records.iter().count(), 1);
+        assert_eq!(records["user_id"].to_string(), "user-456");
+    }
+
+    #[test]
+    fn test_insertion_order_is_kept() {
+        let mut records = LogRecords::new()
+            .with_record("c", 1)
+            .with_record("a", 2)
+            .with_record("b", 3);
+        // Replaced values keep their position, removed keys are appended when re-inserted.
+        records.insert("a", 4);
+        records.remove("c");
+        records.merge_with([("c".into(), 5.into()), ("b".into(), 6.into())]);
+
+        let records = records
+            .iter()
+            .map(|(key, value)| (key.as_ref(), value.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            records,
+            [
+                ("a", "4".to_owned()),
+                ("b", "6".to_owned()),
+                ("c", "5".to_owned())
+            ]
+        );
     }
 }
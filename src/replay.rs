@@ -0,0 +1,107 @@
+//! A per-thread buffer of suppressed records replayed on errors.
+
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{LogValue, layer::OwnedRecord, records::LogRecordRef};
+
+thread_local! {
+    /// Records suppressed on the current thread per buffer, oldest first.
+    static BUFFERS: RefCell<HashMap<u64, VecDeque<BufferedRecord>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Source of the identifiers that keep the buffers of different loggers apart.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A suppressed record kept apart from its context records, so that it can be
+/// forwarded as if it was just logged.
+#[derive(Debug)]
+pub struct BufferedRecord {
+    record: OwnedRecord,
+    records: Vec<(Cow<'static, str>, LogValue)>,
+}
+
+impl BufferedRecord {
+    /// Builds the record and passes it to the closure together with its context records.
+    pub fn with_record<R>(
+        &self,
+        f: impl FnOnce(&log::Record, &[(Cow<'static, str>, LogValue)]) -> R,
+    ) -> R {
+        self.record.with_record(|record| f(record, &self.records))
+    }
+}
+
+/// A bounded buffer of records that were below the logger's level.
+///
+/// See [`ContextLogger::with_debug_buffer`](crate::ContextLogger::with_debug_buffer).
+#[derive(Debug, Clone, Copy)]
+pub struct DebugBuffer {
+    id: u64,
+    capacity: usize,
+    level: log::LevelFilter,
+}
+
+impl DebugBuffer {
+    pub fn new(capacity: usize, level: log::LevelFilter) -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            capacity,
+            level,
+        }
+    }
+
+    /// Returns the most verbose level of buffered records.
+    pub const fn level(self) -> log::LevelFilter {
+        self.level
+    }
+
+    /// Stores an owned copy of the record and its context records if the record is
+    /// within the buffer's level, evicting the oldest record if the buffer of the current
+    /// thread is full.
+    pub fn push<'a>(self, record: &log::Record, records: impl Iterator<Item = LogRecordRef<'a>>) {
+        if self.capacity == 0 || record.level() > self.level {
+            return;
+        }
+
+        let buffered = BufferedRecord {
+            record: OwnedRecord::capture(record, std::iter::empty()),
+            records: records
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        };
+        // The buffer is silently skipped if the thread-local storage is being destroyed.
+        let _ = BUFFERS.try_with(|buffers| {
+            let Ok(mut buffers) = buffers.try_borrow_mut() else {
+                return;
+            };
+            let buffer = buffers.entry(self.id).or_default();
+            if buffer.len() == self.capacity {
+                buffer.pop_front();
+            }
+            buffer.push_back(buffered);
+        });
+    }
+
+    /// Passes every record buffered on the current thread to the given closure and
+    /// empties the buffer.
+    pub fn drain(self, mut replay: impl FnMut(&BufferedRecord)) {
+        let records = BUFFERS
+            .try_with(|buffers| {
+                buffers
+                    .try_borrow_mut()
+                    .map(|mut buffers| buffers.remove(&self.id))
+            })
+            .ok()
+            .and_then(Result::ok)
+            .flatten()
+            .unwrap_or_default();
+        for buffered in &records {
+            replay(buffered);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Sampling of records per value of a context key.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt::Write as _,
+    hash::{BuildHasher, Hasher, RandomState},
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use crate::records::LogRecordRef;
+
+/// The maximum number of distinct values that are sampled separately.
+pub const MAX_SAMPLED_VALUES: usize = 1024;
+
+/// Tolerance for the rounding errors accumulated by the sampling credit.
+const CREDIT_EPSILON: f64 = 1e-9;
+
+/// The number of independently locked parts of the sampling state.
+const SHARDS: usize = 16;
+
+/// Decides which records to emit, keeping a separate budget per value of a context key.
+#[derive(Debug)]
+pub struct KeyedSampler {
+    key: Cow<'static, str>,
+    rate: f64,
+    hasher: RandomState,
+    // Credits per hash of the value, split by the hash to reduce contention.
+    shards: [Mutex<HashMap<u64, f64>>; SHARDS],
+    tracked_values: AtomicUsize,
+    // Shared by the values that are not tracked because of the cardinality limit.
+    overflow: Mutex<f64>,
+}
+
+impl KeyedSampler {
+    pub fn new(key: Cow<'static, str>, rate: f64) -> Self {
+        Self {
+            key,
+            rate: rate.clamp(0.0, 1.0),
+            hasher: RandomState::new(),
+            shards: std::array::from_fn(|_| Mutex::default()),
+            tracked_values: AtomicUsize::new(0),
+            overflow: Mutex::new(1.0),
+        }
+    }
+
+    /// Returns `true` if the record with the given level and context records should be
+    /// emitted.
+    ///
+    /// Each value starts with a full credit, so the first record of every value is
+    /// emitted, and gains `rate` credits with every record. Warnings, errors and records
+    /// without the key are always emitted.
+    pub fn sample<'a>(
+        &self,
+        level: log::Level,
+        records: impl Iterator<Item = LogRecordRef<'a>>,
+    ) -> bool {
+        if level <= log::Level::Warn {
+            return true;
+        }
+        // The last occurrence of the key is the one "last write wins" consumers observe.
+        let Some((_, value)) = records.filter(|(key, _)| **key == self.key).last() else {
+            return true;
+        };
+
+        // Values are told apart by the hash of their text, which avoids an allocation.
+        let mut hasher = HashWriter(self.hasher.build_hasher());
+        let _ = write!(hasher, "{value}");
+        let hash = hasher.0.finish();
+
+        let shard = &self.shards[usize::try_from(hash % SHARDS as u64).unwrap_or_default()];
+        let mut credits = shard.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(credit) = credits.get_mut(&hash) {
+            return self.spend(credit);
+        }
+        if self.tracked_values.fetch_add(1, Ordering::Relaxed) < MAX_SAMPLED_VALUES {
+            return self.spend(credits.entry(hash).or_insert(1.0));
+        }
+        self.tracked_values.fetch_sub(1, Ordering::Relaxed);
+        drop(credits);
+
+        let mut overflow = self.overflow.lock().unwrap_or_else(PoisonError::into_inner);
+        self.spend(&mut overflow)
+    }
+
+    /// Returns `true` if the credit allows to emit a record and updates it.
+    fn spend(&self, credit: &mut f64) -> bool {
+        let emit = *credit + CREDIT_EPSILON >= 1.0;
+        if emit {
+            *credit -= 1.0;
+        }
+        *credit += self.rate;
+        emit
+    }
+}
+
+/// Feeds the formatted text into a hasher.
+struct HashWriter<H>(H);
+
+impl<H: Hasher> std::fmt::Write for HashWriter<H> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.0.write(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{KeyedSampler, MAX_SAMPLED_VALUES};
+    use crate::LogValue;
+
+    #[test]
+    fn test_cardinality_is_capped() {
+        let sampler = KeyedSampler::new(Cow::Borrowed("user"), 0.5);
+        let key = Cow::Borrowed("user");
+        let sample = |user: usize| {
+            sampler.sample(
+                log::Level::Info,
+                std::iter::once((&key, &LogValue::from(user))),
+            )
+        };
+
+        for user in 0..MAX_SAMPLED_VALUES {
+            assert!(sample(user));
+        }
+        // Untracked values share a single budget.
+        let emitted = (0..10)
+            .map(|i| sample(MAX_SAMPLED_VALUES + i))
+            .filter(|emitted| *emitted)
+            .count();
+        assert_eq!(emitted, 5);
+        let tracked = sampler
+            .shards
+            .iter()
+            .map(|shard| shard.lock().unwrap().len())
+            .sum::<usize>();
+        assert_eq!(tracked, MAX_SAMPLED_VALUES);
+    }
+    #[test]
+    fn test_warnings_and_errors_are_not_sampled() {
+        let sampler = KeyedSampler::new(Cow::Borrowed("endpoint"), 0.0);
+        let key = Cow::Borrowed("endpoint");
+        let value = LogValue::from("/hot");
+        let sample = |level| sampler.sample(level, std::iter::once((&key, &value)));
+
+        assert!(sample(log::Level::Info));
+        assert!(!sample(log::Level::Info));
+        assert!(!sample(log::Level::Debug));
+        assert!(sample(log::Level::Warn));
+        assert!(sample(log::Level::Error));
+    }
+}
@@ -1,12 +1,86 @@
 //! A current logging context guard.
 
-use std::{borrow::Cow, marker::PhantomData};
+use std::{
+    borrow::Cow,
+    marker::PhantomData,
+    sync::{
+        Arc, PoisonError, RwLock,
+        atomic::{AtomicBool, AtomicU8, Ordering},
+    },
+    time::Instant,
+};
 
 use self::stack::{SCOPE_STACK, ScopeStack};
 use crate::{LogContext, LogValue};
 
 pub mod stack;
 
+type OrphanRecordHook = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// The process-wide [`OrphanRecordPolicy`], stored as its discriminant.
+static ORPHAN_RECORD_POLICY: AtomicU8 = AtomicU8::new(OrphanRecordPolicy::Ignore as u8);
+/// The process-wide hook reporting orphan records, see [`LogScope::set_orphan_record_hook`].
+static ORPHAN_RECORD_HOOK: RwLock<Option<OrphanRecordHook>> = RwLock::new(None);
+/// The process-wide [`LeakedScopePolicy`], stored as its discriminant.
+static LEAKED_SCOPE_POLICY: AtomicU8 = AtomicU8::new(LeakedScopePolicy::Keep as u8);
+
+/// A policy applied when [`LogScope::add_record`] is called without an active scope.
+///
+/// See [`LogScope::set_orphan_record_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OrphanRecordPolicy {
+    /// The record is silently discarded.
+    #[default]
+    Ignore,
+    /// A new scope containing the record is entered. The scope stays active on the
+    /// current thread until [`LogScope::clear_current_thread`] is called.
+    AutoCreateFrame,
+    /// The record is discarded. The first time this happens, its key is reported to the
+    /// hook set by [`LogScope::set_orphan_record_hook`], if any.
+    Warn,
+}
+
+impl OrphanRecordPolicy {
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::AutoCreateFrame,
+            2 => Self::Warn,
+            _ => Self::Ignore,
+        }
+    }
+}
+
+/// A policy applied when a [`ScopeGroup`] is dropped while scopes entered after it are
+/// still active.
+///
+/// A scope group marks a task boundary: a worker enters the context of a task with
+/// [`CapturedContext::enter_all`] or [`ContextEnvelope::enter`](crate::ContextEnvelope::enter)
+/// and drops the group once the task is done. Scopes still active above the group's
+/// own scopes at that point were leaked by the task, e.g. with [`std::mem::forget`].
+///
+/// See [`LogScope::set_leaked_scope_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LeakedScopePolicy {
+    /// The leaked scopes stay active.
+    #[default]
+    Keep,
+    /// The leaked scopes are removed together with the scopes of the group.
+    Clear,
+    /// The leaked scopes are removed and the drop of the group panics, unless the thread
+    /// is already panicking. Intended for tests and debug builds.
+    Panic,
+}
+
+impl LeakedScopePolicy {
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Clear,
+            2 => Self::Panic,
+            _ => Self::Keep,
+        }
+    }
+}
+
 /// A guard that represents an active logging context on the current thread's scope stack.
 ///
 /// When the guard is dropped, the context is automatically removed from the stack.
@@ -28,9 +102,34 @@ pub mod stack;
 ///
 /// // When `guard` goes out of scope, the context is automatically removed
 /// ```
+///
+/// # Moving a scope to another thread
+///
+/// The guard is not [`Send`]: it refers to the scope stack of the thread that entered
+/// it, and dropping it on another thread would remove a frame of that thread's stack
+/// instead. To continue a scope on another thread, hand over its context rather than
+/// the guard: [`Self::exit`] returns the context including the records added while it
+/// was active, and the context can be entered again on the target thread. To carry
+/// the whole stack, use [`CapturedContext`].
+///
+/// ```
+/// use context_logger::{LogContext, LogScope};
+///
+/// let guard = LogScope::enter(LogContext::new().with_local_record("request_id", "req-123"));
+/// LogScope::add_record("stage", "accepted");
+///
+/// let context = guard.exit().unwrap();
+/// std::thread::spawn(move || {
+///     let _guard = LogScope::enter(context);
+///     log::info!("Processing"); // Includes request_id and stage
+/// })
+/// .join()
+/// .unwrap();
+/// ```
 #[non_exhaustive]
 #[derive(Debug)]
 pub struct LogScope {
+    id: u64,
     // Make this guard non-Send: LogScope manages thread-local state
     // and must not be transferred to another thread.
     _marker: PhantomData<*mut ()>,
@@ -66,12 +165,41 @@ impl LogScope {
     /// Please use the [`crate::FutureExt::in_log_context`] instead.
     #[must_use]
     pub fn enter(context: LogContext) -> Self {
-        SCOPE_STACK.with(|stack| stack.push(context));
+        let id = SCOPE_STACK.with(|stack| stack.push(context));
         Self {
+            id,
             _marker: PhantomData,
         }
     }
 
+    /// Pushes the given context like [`Self::enter`] and returns the guard together with
+    /// the resulting nesting depth.
+    ///
+    /// The returned depth equals [`Self::depth`] right after the push, which helps to
+    /// assert or log the nesting level of deeply nested instrumentation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::{LogContext, LogScope};
+    ///
+    /// let (_outer, depth) = LogScope::enter_with_depth(LogContext::new());
+    /// assert_eq!(depth, 1);
+    /// let (_inner, depth) = LogScope::enter_with_depth(LogContext::new());
+    /// assert_eq!(depth, 2);
+    /// ```
+    #[must_use]
+    pub fn enter_with_depth(context: LogContext) -> (Self, usize) {
+        let guard = Self::enter(context);
+        (guard, Self::depth())
+    }
+
+    /// Returns the number of scopes active on the current thread.
+    #[must_use]
+    pub fn depth() -> usize {
+        SCOPE_STACK.with(ScopeStack::len)
+    }
+
     /// Enters the given context, runs a closure, and exits the scope automatically.
     ///
     /// This is a convenience method for short synchronous sections where context
@@ -90,6 +218,14 @@ impl LogScope {
     ///
     /// assert_eq!(result, 42);
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the closure panics, the scope is exited while the panic unwinds, so no frame
+    /// is leaked even if the panic is caught with [`std::panic::catch_unwind`]. The
+    /// panic hook runs before unwinding starts, so a custom hook installed with
+    /// [`std::panic::set_hook`] still observes the context of the panicking scope, e.g.
+    /// via [`Self::current_context`].
     pub fn in_scope<R>(context: LogContext, f: impl FnOnce() -> R) -> R {
         let _guard = Self::enter(context);
         f()
@@ -102,12 +238,13 @@ impl LogScope {
     ///
     /// # Note
     ///
-    /// If there is no active context, this operation will have no effect.
+    /// If there is no active context, this operation has no effect by default.
+    /// See [`Self::set_orphan_record_policy`] to change this behavior.
     ///
     /// # Ordering
     ///
-    /// The order in which records appear in log output is **not guaranteed**.
-    /// Do not rely on any specific ordering of keys.
+    /// The record is appended to the local records of the scope, unless the scope already
+    /// has a local record with the same key, whose value is replaced in place.
     ///
     /// # Examples
     ///
@@ -129,11 +266,141 @@ impl LogScope {
     pub fn add_record(key: impl Into<Cow<'static, str>>, value: impl Into<LogValue>) {
         SCOPE_STACK.with(|stack| {
             if let Some(mut top) = stack.top_mut() {
-                top.0.local.insert(key, value);
+                top.context.local.insert(key, value);
+                return;
+            }
+
+            match Self::orphan_record_policy() {
+                OrphanRecordPolicy::Ignore => {}
+                OrphanRecordPolicy::AutoCreateFrame => {
+                    stack.push(LogContext::new().with_local_record(key, value));
+                }
+                OrphanRecordPolicy::Warn => {
+                    static WARNED: AtomicBool = AtomicBool::new(false);
+                    let hook = ORPHAN_RECORD_HOOK
+                        .read()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .clone();
+                    if let Some(hook) = hook.filter(|_| !WARNED.swap(true, Ordering::Relaxed)) {
+                        hook(&key.into());
+                    }
+                }
+            }
+        });
+    }
+
+    /// Adds a record with the time elapsed since the currently active scope was entered
+    /// or since its previous checkpoint.
+    ///
+    /// The elapsed time is recorded in milliseconds as a local record with the given name,
+    /// so several checkpoints of a scope accumulate as separate fields. This allows
+    /// lightweight timing of the stages of a request without spans. Does nothing if
+    /// there is no active scope.
+    ///
+    /// # Note
+    ///
+    /// Futures instrumented with [`crate::FutureExt::in_log_context`] enter their scope
+    /// on every poll, so their checkpoints measure the time since the current poll.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::{LogContext, LogContextExt as _, LogScope};
+    ///
+    /// LogContext::new().in_scope(|| {
+    ///     // parse the request...
+    ///     LogScope::checkpoint("parse_ms");
+    ///     // query the database...
+    ///     LogScope::checkpoint("query_ms");
+    ///     log::info!("Request processed"); // Will include parse_ms and query_ms
+    /// });
+    /// ```
+    pub fn checkpoint(name: impl Into<Cow<'static, str>>) {
+        SCOPE_STACK.with(|stack| {
+            if let Some(mut top) = stack.top_mut() {
+                let now = Instant::now();
+                let elapsed = now.duration_since(std::mem::replace(&mut top.checkpoint, now));
+                top.context
+                    .local
+                    .insert(name, elapsed.as_secs_f64() * 1000.0);
             }
         });
     }
 
+    /// Sets the process-wide policy applied when [`Self::add_record`] is called without
+    /// an active scope.
+    ///
+    /// By default, such records are silently ignored, which may hide bugs where the
+    /// enrichment is expected to land in a scope, e.g. because of a wrong middleware order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::{LogScope, OrphanRecordPolicy};
+    ///
+    /// LogScope::set_orphan_record_hook(|key| eprintln!("record {key:?} added without a scope"));
+    /// LogScope::set_orphan_record_policy(OrphanRecordPolicy::Warn);
+    /// LogScope::add_record("user_id", 42); // Prints a warning to stderr
+    /// ```
+    pub fn set_orphan_record_policy(policy: OrphanRecordPolicy) {
+        ORPHAN_RECORD_POLICY.store(policy as u8, Ordering::Relaxed);
+    }
+
+    /// Sets the process-wide hook that receives the key of the first record added
+    /// without an active scope under [`OrphanRecordPolicy::Warn`].
+    ///
+    /// The library itself never prints the warning, so the hook decides where it goes,
+    /// e.g. to stderr or to a metrics counter. The hook must not log through the
+    /// context logger, since it may be called while a record is being logged.
+    pub fn set_orphan_record_hook(hook: impl Fn(&str) + Send + Sync + 'static) {
+        *ORPHAN_RECORD_HOOK
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(hook));
+    }
+
+    /// Returns the policy applied when [`Self::add_record`] is called without an active scope.
+    #[must_use]
+    pub fn orphan_record_policy() -> OrphanRecordPolicy {
+        OrphanRecordPolicy::from_u8(ORPHAN_RECORD_POLICY.load(Ordering::Relaxed))
+    }
+
+    /// Sets the process-wide policy applied when a [`ScopeGroup`] is dropped while scopes
+    /// entered after it are still active.
+    ///
+    /// By default, such scopes are kept, so a guard leaked by one task pollutes the logs
+    /// of the next tasks running on the same worker thread. Clearing them at the task
+    /// boundary prevents this, while panicking additionally reveals the leak.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::{
+    ///     CapturedContext, LeakedScopePolicy, LogContext, LogContextExt as _, LogScope,
+    /// };
+    ///
+    /// LogScope::set_leaked_scope_policy(LeakedScopePolicy::Clear);
+    ///
+    /// let task = LogContext::new()
+    ///     .with_local_record("task_id", 1)
+    ///     .in_scope(CapturedContext::with_frames);
+    /// let guard = task.enter_all();
+    /// // The task leaks a guard.
+    /// std::mem::forget(LogScope::enter(LogContext::new().with_local_record("stale", true)));
+    /// drop(guard);
+    ///
+    /// assert_eq!(LogScope::depth(), 0);
+    /// ```
+    pub fn set_leaked_scope_policy(policy: LeakedScopePolicy) {
+        LEAKED_SCOPE_POLICY.store(policy as u8, Ordering::Relaxed);
+    }
+
+    /// Returns the policy applied when a [`ScopeGroup`] is dropped while scopes entered
+    /// after it are still active.
+    #[must_use]
+    pub fn leaked_scope_policy() -> LeakedScopePolicy {
+        LeakedScopePolicy::from_u8(LEAKED_SCOPE_POLICY.load(Ordering::Relaxed))
+    }
+
     /// Extracts the currently active logging context.
     ///
     /// This is useful for propagating context when spawning new threads or async tasks,
@@ -156,21 +423,426 @@ impl LogScope {
             .unwrap_or_default()
     }
 
-    pub(crate) fn exit(self) -> LogContext {
+    /// Replaces the records of the currently active scope with the given context.
+    ///
+    /// Returns the previously active context, or `None` if there is no active scope,
+    /// in which case the given context is discarded. This is cheaper than exiting
+    /// and re-entering a scope when the whole set of records is recomputed.
+    ///
+    /// Inherited records of the parent scopes are preserved, just like on [`Self::enter`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::{LogContext, LogContextExt as _, LogScope};
+    ///
+    /// LogContext::new().with_local_record("stage", "parse").in_scope(|| {
+    ///     let previous = LogScope::replace_current(
+    ///         LogContext::new().with_local_record("stage", "validate"),
+    ///     );
+    ///     assert_eq!(previous.unwrap().local.iter().count(), 1);
+    /// });
+    ///
+    /// assert!(LogScope::replace_current(LogContext::new()).is_none());
+    /// ```
+    pub fn replace_current(context: LogContext) -> Option<LogContext> {
+        SCOPE_STACK
+            .with(|stack| stack.replace_top(context))
+            .map(Into::into)
+    }
+
+    /// Removes all scopes from the current thread's scope stack.
+    ///
+    /// Returns the number of removed scopes. This is a defensive measure for executors
+    /// that reuse worker threads: a guard leaked by one task (for example, with
+    /// [`std::mem::forget`] or by holding it across an `.await` point) would otherwise
+    /// pollute the logs of all subsequent tasks running on the same thread. Calling
+    /// this method at a task or thread boundary, where no scope is expected to be
+    /// active, removes such stale records, and a non-zero result reveals the leak. To do
+    /// this automatically when the context of a task is entered with a [`ScopeGroup`],
+    /// see [`Self::set_leaked_scope_policy`].
+    ///
+    /// Guards of the removed scopes become no-ops: dropping them does not affect the
+    /// scopes entered after the stack was cleared.
+    ///
+    /// # Warning
+    ///
+    /// This method must not be called while a scope is expected to be active, for
+    /// example, inside a future instrumented with [`crate::FutureExt::in_log_context`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::{LogContext, LogScope};
+    ///
+    /// // A leaked guard keeps its scope active forever.
+    /// std::mem::forget(LogScope::enter(LogContext::new().with_local_record("stale", true)));
+    ///
+    /// let leaked = LogScope::clear_current_thread();
+    /// assert_eq!(leaked, 1);
+    /// assert!(LogScope::current_context().is_empty());
+    /// ```
+    pub fn clear_current_thread() -> usize {
+        SCOPE_STACK.with(ScopeStack::clear)
+    }
+
+    /// Returns the maximum scope nesting depth reached on the current thread.
+    ///
+    /// The value is a high-water mark updated every time a scope is entered, so it
+    /// cheaply surfaces pathological nesting. Use [`Self::reset_max_depth_seen`] to
+    /// start a new measurement.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::{LogContext, LogContextExt as _, LogScope};
+    ///
+    /// LogContext::new().in_scope(|| {
+    ///     LogContext::new().in_scope(|| {});
+    /// });
+    ///
+    /// assert_eq!(LogScope::max_depth_seen(), 2);
+    /// ```
+    #[must_use]
+    pub fn max_depth_seen() -> usize {
+        SCOPE_STACK.with(ScopeStack::max_depth)
+    }
+
+    /// Resets the maximum scope nesting depth on the current thread to the current depth.
+    pub fn reset_max_depth_seen() {
+        SCOPE_STACK.with(ScopeStack::reset_max_depth);
+    }
+
+    /// Exits the scope and returns its context.
+    ///
+    /// Unlike dropping the guard, which discards the context, this method hands the
+    /// context back, so it can be modified and entered again. The returned context
+    /// contains the records added by [`Self::add_record`] and the inherited records of
+    /// the outer scopes that were merged into it on enter.
+    ///
+    /// Exactly the frame of this scope is removed, even if scopes entered after it are
+    /// still active. Returns `None` if the frame no longer exists because the scope stack
+    /// was cleared by [`Self::clear_current_thread`] while this scope was active.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::{LogContext, LogScope};
+    ///
+    /// let guard = LogScope::enter(LogContext::new().with_local_record("attempt", 1));
+    /// // ...
+    /// let context = guard.exit().unwrap().with_local_record("attempt", 2);
+    /// let _guard = LogScope::enter(context);
+    /// ```
+    #[must_use = "the context is discarded, drop the guard instead"]
+    pub fn exit(self) -> Option<LogContext> {
         // We need to prevent the destructor from being called
         // because we're manually managing the context stack here.
+        let id = self.id;
         std::mem::forget(self);
 
-        let frame = SCOPE_STACK
-            .with(ScopeStack::pop)
-            .expect("bug in LogScope::exit: expected a scope frame to exist when popping on exit");
-        frame.into()
+        SCOPE_STACK.with(|stack| stack.remove(id)).map(Into::into)
+    }
+
+    /// Runs the closure in the given context and returns the context back together with
+    /// the result, so it can be entered again later, e.g. on the next poll of a future.
+    ///
+    /// Unlike [`Self::exit`], the returned context does not contain the inherited records
+    /// of the outer scopes, so re-entering it in a different outer scope does not resurrect
+    /// stale values. Records added while the closure runs are kept.
+    pub(crate) fn reenterable<R>(context: LogContext, f: impl FnOnce() -> R) -> (LogContext, R) {
+        // The parent's inherited records are merged into the frame on enter. Only then
+        // the own inherited records have to be saved to be restored on exit.
+        let has_parent_inherited = SCOPE_STACK.with(|stack| {
+            stack
+                .top()
+                .is_some_and(|parent| !parent.context.inherited.is_empty())
+        });
+        let own_inherited = has_parent_inherited.then(|| context.inherited.clone());
+
+        let guard = Self::enter(context);
+        let result = f();
+        // The frame is gone only if the closure cleared the stack, which is documented
+        // as a misuse; the context is lost in this case.
+        let mut context = guard.exit().unwrap_or_default();
+        if let Some(own_inherited) = own_inherited {
+            context.inherited = own_inherited;
+        }
+        (context, result)
     }
 }
 
 impl Drop for LogScope {
     fn drop(&mut self) {
-        SCOPE_STACK.with(ScopeStack::pop);
+        SCOPE_STACK.with(|stack| stack.remove(self.id));
+    }
+}
+
+/// A guard for records merged into an already active scope.
+///
+/// When the guard is dropped, the merged records are removed from the scope and
+/// the values they have overwritten are restored. If there was no active scope at the
+/// time of merging, a new scope was entered instead, and the guard exits it on drop.
+/// The guard is tied to its scope: if the scope has been exited in the meantime, the
+/// drop does not affect other scopes.
+/// Created by [`LogScope::merge_into_current`].
+#[derive(Debug)]
+pub struct MergeGuard {
+    // The frame the records were merged into, or the pushed frame.
+    id: u64,
+    // `None` if a new frame has been pushed.
+    overwritten: Option<OverwrittenRecords>,
+    // Make this guard non-Send: it manages thread-local state.
+    _marker: PhantomData<*mut ()>,
+}
+
+type OverwrittenRecords = (Vec<OverwrittenRecord>, Vec<OverwrittenRecord>);
+type OverwrittenRecord = (Cow<'static, str>, Option<LogValue>);
+
+impl LogScope {
+    /// Merges the records of the given context into the currently active scope.
+    ///
+    /// This is a middle ground between [`Self::add_record`], which never removes the
+    /// added records, and [`Self::enter`], which pushes a new scope. The records
+    /// stay in the active scope until the returned guard is dropped, after which the
+    /// scope is restored to its previous state. Local records are merged into the local
+    /// records of the scope, inherited ones into the inherited records.
+    ///
+    /// If there is no active scope, this behaves like [`Self::enter`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::{LogContext, LogContextExt as _, LogScope};
+    ///
+    /// LogContext::new().with_local_record("request_id", "req-123").in_scope(|| {
+    ///     {
+    ///         let _guard = LogScope::merge_into_current(
+    ///             LogContext::new().with_local_record("stage", "auth"),
+    ///         );
+    ///         assert_eq!(LogScope::current_context().local.iter().count(), 2);
+    ///     }
+    ///     assert_eq!(LogScope::current_context().local.iter().count(), 1);
+    /// });
+    /// ```
+    #[must_use]
+    pub fn merge_into_current(context: LogContext) -> MergeGuard {
+        SCOPE_STACK.with(|stack| {
+            let Some(mut top) = stack.top_mut() else {
+                return MergeGuard {
+                    id: stack.push(context),
+                    overwritten: None,
+                    _marker: PhantomData,
+                };
+            };
+            let merge = |target: &mut crate::LogRecords, records: crate::LogRecords| {
+                records
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let previous = target.replace(key.clone(), value);
+                        (key, previous)
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let local = merge(&mut top.context.local, context.local);
+            let inherited = merge(&mut top.context.inherited, context.inherited);
+
+            MergeGuard {
+                id: top.id,
+                overwritten: Some((local, inherited)),
+                _marker: PhantomData,
+            }
+        })
+    }
+}
+
+impl Drop for MergeGuard {
+    fn drop(&mut self) {
+        let Some((local, inherited)) = self.overwritten.take() else {
+            SCOPE_STACK.with(|stack| stack.remove(self.id));
+            return;
+        };
+
+        SCOPE_STACK.with(|stack| {
+            // Nothing is restored if the frame has been removed in the meantime.
+            let Some(mut frame) = stack.get_mut(self.id) else {
+                return;
+            };
+            let restore = |target: &mut crate::LogRecords, records: Vec<OverwrittenRecord>| {
+                for (key, previous) in records {
+                    if let Some(previous) = previous {
+                        target.replace(key, previous);
+                    } else {
+                        target.remove(&key);
+                    }
+                }
+            };
+            restore(&mut frame.context.local, local);
+            restore(&mut frame.context.inherited, inherited);
+        });
+    }
+}
+
+/// A guard for a group of scopes entered at once.
+///
+/// When the guard is dropped, the scopes entered by it are removed from the stack,
+/// innermost first. Scopes entered after the group and still active at that point are
+/// handled according to [`LogScope::leaked_scope_policy`]. Created by
+/// [`LogScope::enter_all`].
+#[derive(Debug)]
+pub struct ScopeGroup {
+    ids: Vec<u64>,
+    // Make this guard non-Send: it manages thread-local state.
+    _marker: PhantomData<*mut ()>,
+}
+
+impl LogScope {
+    /// Enters all the given contexts in order and returns a single guard for them.
+    ///
+    /// Each context is entered as a nested scope of the previous one, so inherited
+    /// records flow from the first context to the last. This is convenient for composing
+    /// context layers built separately without juggling several guards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::{LogContext, LogScope};
+    ///
+    /// let service = LogContext::new().with_inherited_record("service", "api");
+    /// let request = LogContext::new().with_local_record("request_id", "req-123");
+    ///
+    /// let guard = LogScope::enter_all([service, request]);
+    /// assert_eq!(guard.len(), 2);
+    /// // Both contexts are active until the guard is dropped.
+    /// drop(guard);
+    /// ```
+    #[must_use]
+    pub fn enter_all(contexts: impl IntoIterator<Item = LogContext>) -> ScopeGroup {
+        let ids = SCOPE_STACK.with(|stack| {
+            contexts
+                .into_iter()
+                .map(|context| stack.push(context))
+                .collect()
+        });
+        ScopeGroup {
+            ids,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl ScopeGroup {
+    /// Returns the number of scopes entered by this guard.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns `true` if no scopes were entered by this guard.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+impl Drop for ScopeGroup {
+    fn drop(&mut self) {
+        let Some(&last) = self.ids.last() else {
+            return;
+        };
+        let policy = LogScope::leaked_scope_policy();
+        let leaked = SCOPE_STACK.with(|stack| {
+            let leaked = match policy {
+                LeakedScopePolicy::Keep => 0,
+                LeakedScopePolicy::Clear | LeakedScopePolicy::Panic => stack.truncate_above(last),
+            };
+            for &id in self.ids.iter().rev() {
+                stack.remove(id);
+            }
+            leaked
+        });
+        assert!(
+            policy != LeakedScopePolicy::Panic || leaked == 0 || std::thread::panicking(),
+            "{leaked} scopes entered within a scope group were still active on its drop"
+        );
+    }
+}
+
+/// A snapshot of the scope stack that preserves the boundaries between frames.
+///
+/// Unlike [`LogScope::current_context`], which returns a single flattened context,
+/// the snapshot keeps every active frame separately. Re-entering it on another thread
+/// with [`Self::enter_all`] recreates the same stack, so code running there can itself
+/// enter and exit nested scopes and observe the same restoration as on the original
+/// thread.
+///
+/// # Examples
+///
+/// ```
+/// use context_logger::{CapturedContext, LogContext, LogContextExt as _};
+///
+/// let captured = LogContext::new()
+///     .with_inherited_record("service", "api")
+///     .in_scope(|| {
+///         LogContext::new()
+///             .with_local_record("request_id", "req-123")
+///             .in_scope(CapturedContext::with_frames)
+///     });
+///
+/// std::thread::spawn(move || {
+///     let guard = captured.enter_all();
+///     assert_eq!(guard.len(), 2);
+///     log::info!("Running with the captured frames");
+/// })
+/// .join()
+/// .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CapturedContext {
+    frames: Vec<LogContext>,
+}
+
+impl CapturedContext {
+    /// Captures every frame of the current thread's scope stack, outermost first.
+    #[must_use]
+    pub fn with_frames() -> Self {
+        let frames = SCOPE_STACK.with(|stack| {
+            stack
+                .frames()
+                .iter()
+                .map(|frame| frame.context.clone())
+                .collect()
+        });
+        Self { frames }
+    }
+
+    /// Captures every frame of the current thread's scope stack, or returns an empty
+    /// context if the stack is mutably borrowed or already destroyed.
+    pub(crate) fn try_with_frames() -> Self {
+        SCOPE_STACK
+            .try_with(|stack| {
+                let frames = stack.try_frames()?;
+                Some(Self {
+                    frames: frames.iter().map(|frame| frame.context.clone()).collect(),
+                })
+            })
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    /// Returns the captured frames, outermost first.
+    #[must_use]
+    pub fn frames(&self) -> &[LogContext] {
+        &self.frames
+    }
+
+    /// Enters all captured frames in order and returns a single guard for them.
+    ///
+    /// See [`LogScope::enter_all`].
+    #[must_use]
+    pub fn enter_all(self) -> ScopeGroup {
+        LogScope::enter_all(self.frames)
     }
 }
 
@@ -204,13 +876,19 @@ impl LogContextExt for LogContext {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use pretty_assertions::assert_eq;
-    use static_assertions::assert_not_impl_any;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
 
     use super::*;
 
     // LogScope manages thread-local state and must never be Send.
     assert_not_impl_any!(LogScope: Send);
+    assert_not_impl_any!(MergeGuard: Send);
+    assert_not_impl_any!(ScopeGroup: Send);
+    // Captured contexts are handed over to other threads.
+    assert_impl_all!(CapturedContext: Send, Sync);
 
     #[test]
     fn test_log_context_guard_enter() {
@@ -242,9 +920,9 @@ mod tests {
         );
 
         SCOPE_STACK.with(|stack| {
-            let context = &stack.top().unwrap().0;
+            let context = &stack.top().unwrap().context;
             assert_eq!(
-                context.local.0.get("simple_record").unwrap().to_string(),
+                context.local.get("simple_record").unwrap().to_string(),
                 "outer_value"
             );
         });
@@ -257,7 +935,12 @@ mod tests {
             SCOPE_STACK.with(|stack| {
                 let frame = stack.top().unwrap();
                 assert_eq!(
-                    frame.0.local.find("simple_record").unwrap().to_string(),
+                    frame
+                        .context
+                        .local
+                        .find("simple_record")
+                        .unwrap()
+                        .to_string(),
                     "inner_value"
                 );
             });
@@ -272,7 +955,12 @@ mod tests {
         SCOPE_STACK.with(|stack| {
             let frame = stack.top().unwrap();
             assert_eq!(
-                frame.0.local.find("simple_record").unwrap().to_string(),
+                frame
+                    .context
+                    .local
+                    .find("simple_record")
+                    .unwrap()
+                    .to_string(),
                 "outer_value"
             );
         });
@@ -296,7 +984,12 @@ mod tests {
             SCOPE_STACK.with(|stack| {
                 let frame = stack.top().unwrap();
                 assert_eq!(
-                    frame.0.local.find("simple_record").unwrap().to_string(),
+                    frame
+                        .context
+                        .local
+                        .find("simple_record")
+                        .unwrap()
+                        .to_string(),
                     "first_thread"
                 );
             });
@@ -313,7 +1006,12 @@ mod tests {
             SCOPE_STACK.with(|stack| {
                 let frame = stack.top().unwrap();
                 assert_eq!(
-                    frame.0.local.find("simple_record").unwrap().to_string(),
+                    frame
+                        .context
+                        .local
+                        .find("simple_record")
+                        .unwrap()
+                        .to_string(),
                     "second_thread"
                 );
             });
@@ -326,7 +1024,7 @@ mod tests {
 
         SCOPE_STACK.with(|stack| {
             let frame = stack.top().unwrap();
-            assert_eq!(frame.0.local["simple_record"].to_string(), "main");
+            assert_eq!(frame.context.local["simple_record"].to_string(), "main");
         });
         drop(local_guard);
     }
@@ -475,4 +1173,335 @@ mod tests {
         // After parent scope: stack is empty
         assert_eq!(SCOPE_STACK.with(ScopeStack::len), 0);
     }
+
+    #[test]
+    fn test_enter_with_depth() {
+        assert_eq!(LogScope::depth(), 0);
+        let (outer, depth) = LogScope::enter_with_depth(LogContext::new());
+        assert_eq!(depth, 1);
+        {
+            let (_inner, depth) = LogScope::enter_with_depth(LogContext::new());
+            assert_eq!(depth, 2);
+            assert_eq!(LogScope::depth(), 2);
+        }
+        assert_eq!(LogScope::depth(), 1);
+        drop(outer);
+        assert_eq!(LogScope::depth(), 0);
+    }
+
+    #[test]
+    fn test_max_depth_seen() {
+        LogScope::reset_max_depth_seen();
+        assert_eq!(LogScope::max_depth_seen(), 0);
+
+        LogContext::new().in_scope(|| {
+            LogContext::new().in_scope(|| {
+                LogContext::new().in_scope(|| {});
+            });
+            LogContext::new().in_scope(|| {});
+
+            assert_eq!(LogScope::max_depth_seen(), 3);
+            // Resetting keeps the currently active frames.
+            LogScope::reset_max_depth_seen();
+            assert_eq!(LogScope::max_depth_seen(), 1);
+        });
+
+        assert_eq!(LogScope::max_depth_seen(), 1);
+        LogScope::reset_max_depth_seen();
+        assert_eq!(LogScope::max_depth_seen(), 0);
+    }
+
+    #[test]
+    fn test_checkpoints() {
+        let elapsed = |key: &str| {
+            LogScope::current_context().local[key]
+                .to_string()
+                .parse::<f64>()
+                .unwrap()
+        };
+
+        LogContext::new().in_scope(|| {
+            std::thread::sleep(Duration::from_millis(5));
+            LogScope::checkpoint("first");
+            std::thread::sleep(Duration::from_millis(5));
+            LogScope::checkpoint("second");
+
+            // The first checkpoint measures the time since the scope was entered, a sleep
+            // only guarantees the lower bound of the elapsed time.
+            let first = elapsed("first");
+            assert!(first >= 5.0, "{first}");
+            let second = elapsed("second");
+            assert!(second >= 5.0, "{second}");
+        });
+
+        // Without an active scope the checkpoint is ignored.
+        LogScope::checkpoint("orphan");
+        assert!(SCOPE_STACK.with(ScopeStack::is_empty));
+    }
+
+    #[test]
+    fn test_with_context() {
+        let result = crate::with_context(LogContext::new().with_local_record("answer", 42), || {
+            LogScope::current_context().local["answer"].to_string()
+        });
+        assert_eq!(result, "42");
+        assert!(SCOPE_STACK.with(ScopeStack::is_empty));
+
+        std::panic::catch_unwind(|| {
+            crate::with_context(LogContext::new(), || panic!("Goodbye cruel world"));
+        })
+        .unwrap_err();
+        assert!(SCOPE_STACK.with(ScopeStack::is_empty));
+    }
+
+    #[test]
+    fn test_exit_and_reenter() {
+        let guard = LogScope::enter(LogContext::new().with_local_record("attempt", 1));
+        LogScope::add_record("stage", "parse");
+
+        let context = guard.exit().unwrap();
+        assert!(SCOPE_STACK.with(ScopeStack::is_empty));
+        assert_eq!(context.local["attempt"].to_string(), "1");
+        assert_eq!(context.local["stage"].to_string(), "parse");
+
+        let guard = LogScope::enter(context.with_local_record("attempt", 2));
+        let current = LogScope::current_context();
+        assert_eq!(current.local["attempt"].to_string(), "2");
+        assert_eq!(current.local["stage"].to_string(), "parse");
+        assert_eq!(SCOPE_STACK.with(ScopeStack::len), 1);
+
+        // Exiting does not pop the frame a second time on drop.
+        LogContext::new().in_scope(|| {
+            let inner = LogScope::enter(LogContext::new());
+            let _ = inner.exit();
+            assert_eq!(SCOPE_STACK.with(ScopeStack::len), 2);
+        });
+        assert_eq!(SCOPE_STACK.with(ScopeStack::len), 1);
+
+        drop(guard);
+        assert!(SCOPE_STACK.with(ScopeStack::is_empty));
+    }
+
+    #[test]
+    fn test_exit_removes_own_frame() {
+        // Guards exited out of order return their own contexts.
+        let outer = LogScope::enter(LogContext::new().with_local_record("scope", "outer"));
+        let inner = LogScope::enter(LogContext::new().with_local_record("scope", "inner"));
+        let context = outer.exit().unwrap();
+        assert_eq!(context.local["scope"].to_string(), "outer");
+        assert_eq!(
+            LogScope::current_context().local["scope"].to_string(),
+            "inner"
+        );
+        assert_eq!(inner.exit().unwrap().local["scope"].to_string(), "inner");
+
+        // The frame of a cleared scope no longer exists.
+        let guard = LogScope::enter(LogContext::new());
+        LogScope::clear_current_thread();
+        let next = LogScope::enter(LogContext::new().with_local_record("scope", "next"));
+        assert!(guard.exit().is_none());
+        assert_eq!(SCOPE_STACK.with(ScopeStack::len), 1);
+        drop(next);
+        assert!(SCOPE_STACK.with(ScopeStack::is_empty));
+    }
+
+    #[test]
+    fn test_enter_all() {
+        LogContext::new()
+            .with_local_record("outer", 1)
+            .in_scope(|| {
+                let guard = LogScope::enter_all([
+                    LogContext::new().with_inherited_record("service", "api"),
+                    LogContext::new().with_inherited_record("request_id", "req-1"),
+                    LogContext::new().with_local_record("stage", "parse"),
+                ]);
+                assert_eq!(guard.len(), 3);
+                assert_eq!(SCOPE_STACK.with(ScopeStack::len), 4);
+
+                let current = LogScope::current_context();
+                assert_eq!(current.inherited["service"].to_string(), "api");
+                assert_eq!(current.inherited["request_id"].to_string(), "req-1");
+                assert_eq!(current.local["stage"].to_string(), "parse");
+
+                // The stack is mutated in between, which does not affect the pop count.
+                LogScope::add_record("user_id", 42);
+                drop(guard);
+
+                assert_eq!(SCOPE_STACK.with(ScopeStack::len), 1);
+                assert_eq!(LogScope::current_context().local["outer"].to_string(), "1");
+            });
+        assert!(SCOPE_STACK.with(ScopeStack::is_empty));
+
+        let guard = LogScope::enter_all([]);
+        assert!(guard.is_empty());
+        assert!(SCOPE_STACK.with(ScopeStack::is_empty));
+    }
+
+    #[test]
+    fn test_captured_frames_reentered_on_another_thread() {
+        let captured = LogContext::new()
+            .with_inherited_record("service", "api")
+            .with_local_record("outer", 1)
+            .in_scope(|| {
+                LogContext::new()
+                    .with_local_record("request_id", "req-1")
+                    .in_scope(CapturedContext::with_frames)
+            });
+        assert_eq!(captured.frames().len(), 2);
+
+        std::thread::spawn(move || {
+            let guard = captured.enter_all();
+            assert_eq!(SCOPE_STACK.with(ScopeStack::len), 2);
+
+            let inner = LogScope::current_context();
+            assert_eq!(inner.inherited["service"].to_string(), "api");
+            assert_eq!(inner.local["request_id"].to_string(), "req-1");
+            assert!(inner.local.find("outer").is_none());
+
+            // Exiting a nested scope restores the captured inner frame.
+            LogContext::new()
+                .with_local_record("stage", "parse")
+                .in_scope(|| assert_eq!(SCOPE_STACK.with(ScopeStack::len), 3));
+            assert_eq!(
+                LogScope::current_context().local["request_id"].to_string(),
+                "req-1"
+            );
+
+            // The outer frame keeps its own local records.
+            let outer = SCOPE_STACK.with(|stack| stack.frames()[0].context.clone());
+            assert_eq!(outer.local["outer"].to_string(), "1");
+            assert!(outer.local.find("request_id").is_none());
+
+            drop(guard);
+            assert!(SCOPE_STACK.with(ScopeStack::is_empty));
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_replace_current() {
+        assert!(LogScope::replace_current(LogContext::new().with_local_record("a", 1)).is_none());
+        assert!(SCOPE_STACK.with(ScopeStack::is_empty));
+
+        LogContext::new()
+            .with_inherited_record("request_id", "req-1")
+            .in_scope(|| {
+                LogContext::new()
+                    .with_local_record("stage", "parse")
+                    .with_inherited_record("attempt", 1)
+                    .in_scope(|| {
+                        let previous = LogScope::replace_current(
+                            LogContext::new().with_local_record("stage", "validate"),
+                        )
+                        .unwrap();
+                        assert_eq!(previous.local["stage"].to_string(), "parse");
+                        assert_eq!(previous.inherited["attempt"].to_string(), "1");
+
+                        let current = LogScope::current_context();
+                        assert_eq!(current.local["stage"].to_string(), "validate");
+                        assert_eq!(current.inherited["request_id"].to_string(), "req-1");
+                        assert!(current.inherited.find("attempt").is_none());
+                        assert_eq!(SCOPE_STACK.with(ScopeStack::len), 2);
+                    });
+
+                let current = LogScope::current_context();
+                assert!(current.local.is_empty());
+                assert_eq!(current.inherited["request_id"].to_string(), "req-1");
+            });
+
+        assert!(SCOPE_STACK.with(ScopeStack::is_empty));
+    }
+
+    #[test]
+    fn test_merge_into_current() {
+        LogContext::new()
+            .with_local_record("request_id", "req-1")
+            .with_local_record("stage", "parse")
+            .in_scope(|| {
+                {
+                    let _guard = LogScope::merge_into_current(
+                        LogContext::new()
+                            .with_local_record("stage", "auth")
+                            .with_inherited_record("user_id", 42),
+                    );
+                    assert_eq!(SCOPE_STACK.with(ScopeStack::len), 1);
+
+                    let current = LogScope::current_context();
+                    assert_eq!(current.local["request_id"].to_string(), "req-1");
+                    assert_eq!(current.local["stage"].to_string(), "auth");
+                    assert_eq!(current.inherited["user_id"].to_string(), "42");
+
+                    // Merged inherited records are visible in child scopes.
+                    LogContext::new().in_scope(|| {
+                        let current = LogScope::current_context();
+                        assert_eq!(current.inherited["user_id"].to_string(), "42");
+                    });
+                }
+
+                let current = LogScope::current_context();
+                assert_eq!(current.local["request_id"].to_string(), "req-1");
+                assert_eq!(current.local["stage"].to_string(), "parse");
+                assert!(current.inherited.is_empty());
+            });
+    }
+
+    #[test]
+    fn test_merge_into_current_without_active_scope() {
+        {
+            let _guard =
+                LogScope::merge_into_current(LogContext::new().with_local_record("stage", "auth"));
+            assert_eq!(SCOPE_STACK.with(ScopeStack::len), 1);
+            assert_eq!(
+                LogScope::current_context().local["stage"].to_string(),
+                "auth"
+            );
+        }
+
+        assert!(SCOPE_STACK.with(ScopeStack::is_empty));
+    }
+
+    #[test]
+    fn test_merge_guard_restores_its_own_frame() {
+        LogContext::new().in_scope(|| {
+            let guard =
+                LogScope::merge_into_current(LogContext::new().with_local_record("stage", "auth"));
+            let child = LogScope::enter(LogContext::new().with_local_record("child", true));
+
+            drop(guard);
+            assert_eq!(
+                LogScope::current_context().local["child"].to_string(),
+                "true"
+            );
+            drop(child);
+
+            assert!(LogScope::current_context().is_empty());
+        });
+    }
+
+    #[test]
+    fn test_merge_guard_outliving_its_frame() {
+        // The frame is replaced by an unrelated one at the same depth.
+        let scope = LogScope::enter(LogContext::new().with_local_record("stage", "parse"));
+        let guard =
+            LogScope::merge_into_current(LogContext::new().with_local_record("stage", "auth"));
+        drop(scope);
+        let next = LogScope::enter(LogContext::new().with_local_record("stage", "query"));
+        drop(guard);
+        assert_eq!(
+            LogScope::current_context().local["stage"].to_string(),
+            "query"
+        );
+        drop(next);
+
+        // The pushed frame is removed even if it is not the top one.
+        let guard =
+            LogScope::merge_into_current(LogContext::new().with_local_record("stage", "auth"));
+        let child = LogScope::enter(LogContext::new().with_local_record("child", true));
+        drop(guard);
+        assert_eq!(SCOPE_STACK.with(ScopeStack::len), 1);
+        assert!(LogScope::current_context().local.find("stage").is_none());
+        drop(child);
+        assert!(SCOPE_STACK.with(ScopeStack::is_empty));
+    }
 }
@@ -3,7 +3,10 @@
 //! The stack is used by both the synchronous and asynchronous log
 //! context propagation mechanisms.
 
-use std::cell::{Ref, RefCell, RefMut};
+use std::{
+    cell::{Cell, Ref, RefCell, RefMut},
+    time::Instant,
+};
 
 use crate::{LogContext, records::LogRecordRef};
 
@@ -18,18 +21,30 @@ thread_local! {
 /// A single frame in the thread-local [`ScopeStack`].
 ///
 /// Pushed when a scope is entered and popped when its guard is dropped.
-#[derive(Debug, Clone, Default)]
-pub struct ScopeFrame(pub LogContext);
+#[derive(Debug, Clone)]
+pub struct ScopeFrame {
+    /// The context of the scope.
+    pub context: LogContext,
+    /// The identifier of the frame, unique within its stack.
+    ///
+    /// Guards remove their frame by this identifier, so a guard whose frame has been
+    /// removed by other means does not remove an unrelated frame.
+    pub id: u64,
+    /// The time the scope was entered or of its last [checkpoint](crate::LogScope::checkpoint).
+    pub checkpoint: Instant,
+}
 
 /// A stack of scope frames, one per active [`crate::LogScope`].
 #[derive(Debug)]
 pub struct ScopeStack {
     inner: RefCell<Vec<ScopeFrame>>,
+    max_depth: Cell<usize>,
+    next_id: Cell<u64>,
 }
 
 impl ScopeFrame {
     pub fn new() -> Self {
-        Self(LogContext::new())
+        Self::from(LogContext::new())
     }
 
     /// Returns an iterator over all records in this scope frame.
@@ -38,19 +53,26 @@ impl ScopeFrame {
     /// records to shadow inherited ones when a consumer resolves duplicate keys
     /// using "last write wins" semantics.
     pub fn records(&self) -> impl Iterator<Item = LogRecordRef<'_>> + Clone {
-        self.0.inherited.iter().chain(self.0.local.iter())
+        self.context
+            .inherited
+            .iter()
+            .chain(self.context.local.iter())
     }
 }
 
 impl From<LogContext> for ScopeFrame {
     fn from(context: LogContext) -> Self {
-        Self(context)
+        Self {
+            context,
+            id: 0,
+            checkpoint: Instant::now(),
+        }
     }
 }
 
 impl From<ScopeFrame> for LogContext {
     fn from(frame: ScopeFrame) -> Self {
-        frame.0
+        frame.context
     }
 }
 
@@ -59,36 +81,124 @@ impl ScopeStack {
     pub const fn new() -> Self {
         Self {
             inner: RefCell::new(Vec::new()),
+            max_depth: Cell::new(0),
+            next_id: Cell::new(0),
         }
     }
 
     /// Pushes a new scope frame onto the stack, merging inherited records from
     /// the current top frame into the new context's inherited records.
     ///
+    /// Returns the identifier of the pushed frame.
+    ///
     /// # Panics
     ///
     /// If the stack is already borrowed.
-    pub fn push(&self, mut context: LogContext) {
+    pub fn push(&self, context: LogContext) -> u64 {
+        self.push_into(&mut self.inner.borrow_mut(), context)
+    }
+
+    /// Pushes a new scope frame like [`Self::push`], or returns `None` without pushing
+    /// it if the stack is already borrowed.
+    pub fn try_push(&self, context: LogContext) -> Option<u64> {
+        let mut inner = self.inner.try_borrow_mut().ok()?;
+        Some(self.push_into(&mut inner, context))
+    }
+
+    fn push_into(&self, inner: &mut Vec<ScopeFrame>, context: LogContext) -> u64 {
+        let id = self.next_id.get();
+        self.next_id.set(id.wrapping_add(1));
+        let mut frame = Self::inherit(inner.last(), context);
+        frame.id = id;
+        inner.push(frame);
+        self.max_depth.set(self.max_depth.get().max(inner.len()));
+        id
+    }
+
+    /// Replaces the top scope frame with the given context, returning the previous frame.
+    ///
+    /// Inherited records of the parent frame are merged into the new context the same
+    /// way as in [`Self::push`]. Returns `None` and leaves the stack untouched if it is empty.
+    ///
+    /// # Panics
+    ///
+    /// If the stack is already borrowed.
+    pub fn replace_top(&self, context: LogContext) -> Option<ScopeFrame> {
+        let mut inner = self.inner.borrow_mut();
+        let (top, rest) = inner.split_last_mut()?;
+        // The frame still belongs to the same scope, so its guard stays valid.
+        let mut frame = Self::inherit(rest.last(), context);
+        frame.id = top.id;
+        Some(std::mem::replace(top, frame))
+    }
+
+    fn inherit(parent: Option<&ScopeFrame>, mut context: LogContext) -> ScopeFrame {
         // Merge inherited records from the parent frame into the child context.
         // Parent inherited records are applied first, then child inherited records
         // so child scopes can shadow inherited keys from their parent.
-        let mut inherited = self
-            .top()
-            .map(|top| top.0.inherited.clone())
+        let mut inherited = parent
+            .map(|parent| parent.context.inherited.clone())
             .unwrap_or_default();
         inherited.merge_with(context.inherited);
         context.inherited = inherited;
+        ScopeFrame::from(context)
+    }
+
+    /// Removes the scope frame with the given identifier, returning it.
+    ///
+    /// The frame is usually the top one, but guards dropped out of order remove their
+    /// frame from the middle of the stack. Returns `None` if there is no such frame,
+    /// e.g. because the stack has been [cleared](Self::clear).
+    ///
+    /// # Panics
+    ///
+    /// If the stack is already borrowed.
+    pub fn remove(&self, id: u64) -> Option<ScopeFrame> {
+        let mut inner = self.inner.borrow_mut();
+        if inner.last().is_some_and(|frame| frame.id == id) {
+            return inner.pop();
+        }
+        let index = inner.iter().rposition(|frame| frame.id == id)?;
+        Some(inner.remove(index))
+    }
 
-        self.inner.borrow_mut().push(ScopeFrame::from(context));
+    /// Removes the scope frames above the frame with the given identifier, returning
+    /// the number of removed frames.
+    ///
+    /// # Panics
+    ///
+    /// If the stack is already borrowed.
+    pub fn truncate_above(&self, id: u64) -> usize {
+        let mut inner = self.inner.borrow_mut();
+        let Some(index) = inner.iter().rposition(|frame| frame.id == id) else {
+            return 0;
+        };
+        inner.drain(index + 1..).count()
     }
 
-    /// Pops the top scope frame from the stack.
+    /// Removes all scope frames from the stack, returning the number of removed frames.
     ///
     /// # Panics
     ///
     /// If the stack is already borrowed.
-    pub fn pop(&self) -> Option<ScopeFrame> {
-        self.inner.borrow_mut().pop()
+    pub fn clear(&self) -> usize {
+        let frames = std::mem::take(&mut *self.inner.borrow_mut());
+        frames.len()
+    }
+
+    /// Returns the maximum number of frames this stack has held since creation
+    /// or the last [`Self::reset_max_depth`] call.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth.get()
+    }
+
+    /// Resets the maximum depth to the current number of frames.
+    ///
+    /// # Panics
+    ///
+    /// If the stack is already mutably borrowed.
+    pub fn reset_max_depth(&self) {
+        self.max_depth.set(self.inner.borrow().len());
     }
 
     /// Returns a reference to the top scope frame on the stack.
@@ -105,6 +215,50 @@ impl ScopeStack {
         }
     }
 
+    /// Returns a reference to the top scope frame, or `None` if the stack is empty
+    /// or already mutably borrowed.
+    pub fn try_top(&self) -> Option<Ref<'_, ScopeFrame>> {
+        let inner = self.inner.try_borrow().ok()?;
+        Ref::filter_map(inner, |inner| inner.last()).ok()
+    }
+
+    /// Returns all scope frames on the stack, or `None` if the stack is already
+    /// mutably borrowed.
+    pub fn try_frames(&self) -> Option<Ref<'_, [ScopeFrame]>> {
+        let inner = self.inner.try_borrow().ok()?;
+        Some(Ref::map(inner, Vec::as_slice))
+    }
+
+    /// Returns all scope frames on the stack, from the outermost to the innermost.
+    ///
+    /// # Panics
+    ///
+    /// If the stack is already mutably borrowed.
+    pub fn frames(&self) -> Ref<'_, [ScopeFrame]> {
+        Ref::map(self.inner.borrow(), Vec::as_slice)
+    }
+
+    /// Returns a mutable reference to the scope frame with the given identifier.
+    ///
+    /// # Panics
+    ///
+    /// If the stack is already borrowed.
+    pub fn get_mut(&self, id: u64) -> Option<RefMut<'_, ScopeFrame>> {
+        RefMut::filter_map(self.inner.borrow_mut(), |inner| {
+            inner.iter_mut().rfind(|frame| frame.id == id)
+        })
+        .ok()
+    }
+
+    /// Returns the number of scope frames on the stack.
+    ///
+    /// # Panics
+    ///
+    /// If the stack is already mutably borrowed.
+    pub fn len(&self) -> usize {
+        self.inner.borrow().len()
+    }
+
     /// Returns a mutable reference to the top scope frame on the stack.
     ///
     /// # Panics
@@ -118,6 +272,13 @@ impl ScopeStack {
             Some(RefMut::map(inner, |inner| inner.last_mut().unwrap()))
         }
     }
+
+    /// Returns a mutable reference to the top scope frame, or `None` if the stack
+    /// is empty or already borrowed.
+    pub fn try_top_mut(&self) -> Option<RefMut<'_, ScopeFrame>> {
+        let inner = self.inner.try_borrow_mut().ok()?;
+        RefMut::filter_map(inner, |inner| inner.last_mut()).ok()
+    }
 }
 
 impl Default for ScopeStack {
@@ -128,15 +289,19 @@ impl Default for ScopeStack {
 
 #[cfg(test)]
 impl ScopeStack {
-    /// Returns the number of scope frames on the stack.
-    pub fn len(&self) -> usize {
-        self.inner.borrow().len()
-    }
-
     /// Returns `true` if the stack is empty.
     pub fn is_empty(&self) -> bool {
         self.inner.borrow().is_empty()
     }
+
+    /// Pops the top scope frame from the stack.
+    ///
+    /// # Panics
+    ///
+    /// If the stack is already borrowed.
+    pub fn pop(&self) -> Option<ScopeFrame> {
+        self.inner.borrow_mut().pop()
+    }
 }
 
 #[cfg(test)]
@@ -152,9 +317,10 @@ mod tests {
 
     #[test]
     fn test_scope_frame_records_with_inherited() {
-        let frame = ScopeFrame(LogContext {
+        let frame = ScopeFrame::from(LogContext {
             local: LogRecords::new().with_record("name", "bob"),
             inherited: LogRecords::new().with_record("tag", 42),
+            name: None,
         });
 
         let records: HashMap<_, _> = frame.records().map(record_to_string).collect();
@@ -162,4 +328,29 @@ mod tests {
         assert_eq!(records.len(), 2);
         assert_eq!(records["tag"], "42");
     }
+
+    #[test]
+    fn test_try_access_on_borrow_conflict() {
+        let stack = ScopeStack::new();
+        stack.push(LogContext::new().with_local_record("name", "bob"));
+
+        {
+            let _top = stack.top_mut().unwrap();
+            assert!(stack.try_top().is_none());
+            assert!(stack.try_frames().is_none());
+            assert!(stack.try_top_mut().is_none());
+        }
+
+        {
+            let _frames = stack.frames();
+            assert!(stack.try_top().is_some());
+            assert!(stack.try_top_mut().is_none());
+        }
+
+        assert_eq!(stack.try_frames().unwrap().len(), 1);
+        assert!(stack.try_top_mut().is_some());
+        stack.pop();
+        assert!(stack.try_top().is_none());
+        assert!(stack.try_top_mut().is_none());
+    }
 }
@@ -0,0 +1,383 @@
+//! Serialization of `serde` values with a limited nesting depth.
+
+use serde::ser::{
+    Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant, Serializer,
+};
+
+/// A marker that replaces structures nested deeper than the limit.
+const TRUNCATED: &str = "...";
+
+/// A value serialized with at most `depth` levels of nested sequences, maps and structs.
+///
+/// Deeper structures are replaced with a `"..."` string. Options, newtypes and other
+/// wrappers that serialize their contents transparently do not count as a level.
+///
+/// See [`ContextLogger::with_max_serde_depth`](crate::ContextLogger::with_max_serde_depth).
+pub struct MaxDepth<T> {
+    pub value: T,
+    pub depth: usize,
+}
+
+impl<T: Serialize> Serialize for MaxDepth<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(Limited {
+            inner: serializer,
+            remaining: self.depth,
+        })
+    }
+}
+
+/// A borrowed nested value serialized with the remaining depth.
+struct Nested<'a, T: ?Sized> {
+    value: &'a T,
+    remaining: usize,
+}
+
+impl<T: Serialize + ?Sized> Serialize for Nested<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(Limited {
+            inner: serializer,
+            remaining: self.remaining,
+        })
+    }
+}
+
+/// A serializer that forwards to the inner one until the depth limit is reached.
+struct Limited<S> {
+    inner: S,
+    remaining: usize,
+}
+
+impl<S: Serializer> Limited<S> {
+    /// Starts a nested structure, unless the depth limit is reached.
+    fn nest<C>(
+        self,
+        start: impl FnOnce(S) -> Result<C, S::Error>,
+    ) -> Result<Compound<S, C>, S::Error> {
+        match self.remaining.checked_sub(1) {
+            Some(remaining) => Ok(Compound::Forward {
+                inner: start(self.inner)?,
+                remaining,
+            }),
+            None => Ok(Compound::Truncated(self.inner)),
+        }
+    }
+}
+
+macro_rules! forward_primitives {
+    ($($method:ident($ty:ty)),*) => {
+        $(
+            fn $method(self, value: $ty) -> Result<S::Ok, S::Error> {
+                self.inner.$method(value)
+            }
+        )*
+    };
+}
+
+impl<S: Serializer> Serializer for Limited<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Compound<S, S::SerializeSeq>;
+    type SerializeTuple = Compound<S, S::SerializeTuple>;
+    type SerializeTupleStruct = Compound<S, S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Compound<S, S::SerializeTupleVariant>;
+    type SerializeMap = Compound<S, S::SerializeMap>;
+    type SerializeStruct = Compound<S, S::SerializeStruct>;
+    type SerializeStructVariant = Compound<S, S::SerializeStructVariant>;
+
+    forward_primitives!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8])
+    );
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_none()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_some(&Nested {
+            value,
+            remaining: self.remaining,
+        })
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit_struct(name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner
+            .serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_newtype_struct(
+            name,
+            &Nested {
+                value,
+                remaining: self.remaining,
+            },
+        )
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_newtype_variant(
+            name,
+            variant_index,
+            variant,
+            &Nested {
+                value,
+                remaining: self.remaining,
+            },
+        )
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        self.nest(|inner| inner.serialize_seq(len))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        self.nest(|inner| inner.serialize_tuple(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        self.nest(|inner| inner.serialize_tuple_struct(name, len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        self.nest(|inner| inner.serialize_tuple_variant(name, variant_index, variant, len))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        self.nest(|inner| inner.serialize_map(len))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        self.nest(|inner| inner.serialize_struct(name, len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        self.nest(|inner| inner.serialize_struct_variant(name, variant_index, variant, len))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+/// A nested structure that is either forwarded or replaced with the truncation marker.
+enum Compound<S, C> {
+    Forward { inner: C, remaining: usize },
+    Truncated(S),
+}
+
+impl<S: Serializer, C> Compound<S, C> {
+    /// Passes a nested value to the forwarded structure, skipping it if truncated.
+    fn nested<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+        serialize: impl FnOnce(&mut C, &Nested<'_, T>) -> Result<(), S::Error>,
+    ) -> Result<(), S::Error> {
+        match self {
+            Self::Forward { inner, remaining } => serialize(
+                inner,
+                &Nested {
+                    value,
+                    remaining: *remaining,
+                },
+            ),
+            Self::Truncated(_) => Ok(()),
+        }
+    }
+
+    fn finish(self, end: impl FnOnce(C) -> Result<S::Ok, S::Error>) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Forward { inner, .. } => end(inner),
+            Self::Truncated(serializer) => serializer.serialize_str(TRUNCATED),
+        }
+    }
+}
+
+impl<S: Serializer> SerializeSeq for Compound<S, S::SerializeSeq> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), S::Error> {
+        self.nested(value, |inner, value| inner.serialize_element(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.finish(SerializeSeq::end)
+    }
+}
+
+impl<S: Serializer> SerializeTuple for Compound<S, S::SerializeTuple> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), S::Error> {
+        self.nested(value, |inner, value| inner.serialize_element(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.finish(SerializeTuple::end)
+    }
+}
+
+impl<S: Serializer> SerializeTupleStruct for Compound<S, S::SerializeTupleStruct> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), S::Error> {
+        self.nested(value, |inner, value| inner.serialize_field(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.finish(SerializeTupleStruct::end)
+    }
+}
+
+impl<S: Serializer> SerializeTupleVariant for Compound<S, S::SerializeTupleVariant> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), S::Error> {
+        self.nested(value, |inner, value| inner.serialize_field(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.finish(SerializeTupleVariant::end)
+    }
+}
+
+impl<S: Serializer> SerializeMap for Compound<S, S::SerializeMap> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), S::Error> {
+        self.nested(key, |inner, key| inner.serialize_key(key))
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), S::Error> {
+        self.nested(value, |inner, value| inner.serialize_value(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.finish(SerializeMap::end)
+    }
+}
+
+impl<S: Serializer> SerializeStruct for Compound<S, S::SerializeStruct> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), S::Error> {
+        self.nested(value, |inner, value| inner.serialize_field(key, value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.finish(SerializeStruct::end)
+    }
+}
+
+impl<S: Serializer> SerializeStructVariant for Compound<S, S::SerializeStructVariant> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), S::Error> {
+        self.nested(value, |inner, value| inner.serialize_field(key, value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.finish(SerializeStructVariant::end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::MaxDepth;
+
+    fn to_json(value: impl serde::Serialize, depth: usize) -> serde_json::Value {
+        serde_json::to_value(MaxDepth { value, depth }).unwrap()
+    }
+
+    #[test]
+    fn test_nested_collections_are_truncated() {
+        let value = vec![vec![vec![1, 2]], vec![]];
+        assert_eq!(to_json(&value, 0), json!("..."));
+        assert_eq!(to_json(&value, 1), json!(["...", "..."]));
+        assert_eq!(to_json(&value, 2), json!([["..."], []]));
+        assert_eq!(to_json(&value, 3), json!([[[1, 2]], []]));
+
+        let map = BTreeMap::from([("a", (1, Some([2])))]);
+        assert_eq!(to_json(&map, 2), json!({"a": [1, "..."]}));
+        assert_eq!(to_json(&map, 3), json!({"a": [1, [2]]}));
+    }
+}
@@ -0,0 +1,449 @@
+//! Owned copies of borrowed `serde` values.
+
+use serde::ser::{
+    Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant, Serializer,
+};
+
+/// An owned copy of a serialized value.
+///
+/// The copy keeps the data model of self-describing formats such as JSON: tuples are
+/// kept as sequences, newtypes and options serialize their contents and enum variants
+/// other than unit ones are externally tagged. Structs keep their names, since
+/// serializers may treat some of them specially, e.g. the numbers of `serde_json` with
+/// the `arbitrary_precision` feature.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OwnedValue {
+    Unit,
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    I128(i128),
+    U128(u128),
+    F64(f64),
+    Char(char),
+    Str(String),
+    Bytes(Vec<u8>),
+    Seq(Vec<Self>),
+    Map(Vec<(Self, Self)>),
+    Struct(&'static str, Vec<(&'static str, Self)>),
+}
+
+impl OwnedValue {
+    /// Copies the given value by serializing it.
+    pub fn capture<T: Serialize + ?Sized>(value: &T) -> Result<Self, Error> {
+        value.serialize(Capture)
+    }
+
+    /// Wraps the value into a single entry map keyed by the variant name.
+    fn tagged(variant: &'static str, value: Self) -> Self {
+        Self::Map(vec![(Self::Str(variant.to_owned()), value)])
+    }
+}
+
+impl Serialize for OwnedValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Unit => serializer.serialize_unit(),
+            Self::Bool(value) => serializer.serialize_bool(*value),
+            Self::I64(value) => serializer.serialize_i64(*value),
+            Self::U64(value) => serializer.serialize_u64(*value),
+            Self::I128(value) => serializer.serialize_i128(*value),
+            Self::U128(value) => serializer.serialize_u128(*value),
+            Self::F64(value) => serializer.serialize_f64(*value),
+            Self::Char(value) => serializer.serialize_char(*value),
+            Self::Str(value) => serializer.serialize_str(value),
+            Self::Bytes(value) => serializer.serialize_bytes(value),
+            Self::Seq(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            Self::Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            Self::Struct(name, fields) => {
+                let mut map = serializer.serialize_struct(name, fields.len())?;
+                for (key, value) in fields {
+                    map.serialize_field(key, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+/// An error raised by a value while it is being copied.
+#[derive(Debug)]
+pub struct Error(String);
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl serde::ser::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// A serializer that builds an [`OwnedValue`].
+struct Capture;
+
+macro_rules! capture_primitives {
+    ($($method:ident($ty:ty) => $arm:ident),*) => {
+        $(
+            fn $method(self, value: $ty) -> Result<OwnedValue, Error> {
+                Ok(OwnedValue::$arm(value.into()))
+            }
+        )*
+    };
+}
+
+impl Serializer for Capture {
+    type Ok = OwnedValue;
+    type Error = Error;
+    type SerializeSeq = Sequence;
+    type SerializeTuple = Sequence;
+    type SerializeTupleStruct = Sequence;
+    type SerializeTupleVariant = Sequence;
+    type SerializeMap = Map;
+    type SerializeStruct = Struct;
+    type SerializeStructVariant = Struct;
+
+    capture_primitives!(
+        serialize_bool(bool) => Bool,
+        serialize_i8(i8) => I64,
+        serialize_i16(i16) => I64,
+        serialize_i32(i32) => I64,
+        serialize_i64(i64) => I64,
+        serialize_i128(i128) => I128,
+        serialize_u8(u8) => U64,
+        serialize_u16(u16) => U64,
+        serialize_u32(u32) => U64,
+        serialize_u64(u64) => U64,
+        serialize_u128(u128) => U128,
+        serialize_f32(f32) => F64,
+        serialize_f64(f64) => F64,
+        serialize_char(char) => Char,
+        serialize_str(&str) => Str,
+        serialize_bytes(&[u8]) => Bytes
+    );
+
+    fn serialize_none(self) -> Result<OwnedValue, Error> {
+        Ok(OwnedValue::Unit)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<OwnedValue, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<OwnedValue, Error> {
+        Ok(OwnedValue::Unit)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<OwnedValue, Error> {
+        Ok(OwnedValue::Unit)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<OwnedValue, Error> {
+        Ok(OwnedValue::Str(variant.to_owned()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<OwnedValue, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<OwnedValue, Error> {
+        Ok(OwnedValue::tagged(variant, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Sequence, Error> {
+        Ok(Sequence::new(None, len.unwrap_or_default()))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Sequence, Error> {
+        Ok(Sequence::new(None, len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Sequence, Error> {
+        Ok(Sequence::new(None, len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Sequence, Error> {
+        Ok(Sequence::new(Some(variant), len))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Map, Error> {
+        Ok(Map {
+            entries: Vec::with_capacity(len.unwrap_or_default()),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Struct, Error> {
+        Ok(Struct::new(name, None, len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Struct, Error> {
+        Ok(Struct::new(name, Some(variant), len))
+    }
+}
+
+/// A sequence being copied, tagged with its enum variant, if any.
+struct Sequence {
+    variant: Option<&'static str>,
+    items: Vec<OwnedValue>,
+}
+
+impl Sequence {
+    fn new(variant: Option<&'static str>, len: usize) -> Self {
+        Self {
+            variant,
+            items: Vec::with_capacity(len),
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.items.push(OwnedValue::capture(value)?);
+        Ok(())
+    }
+
+    fn finish(self) -> OwnedValue {
+        let value = OwnedValue::Seq(self.items);
+        match self.variant {
+            Some(variant) => OwnedValue::tagged(variant, value),
+            None => value,
+        }
+    }
+}
+
+impl SerializeSeq for Sequence {
+    type Ok = OwnedValue;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<OwnedValue, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeTuple for Sequence {
+    type Ok = OwnedValue;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<OwnedValue, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeTupleStruct for Sequence {
+    type Ok = OwnedValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<OwnedValue, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeTupleVariant for Sequence {
+    type Ok = OwnedValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<OwnedValue, Error> {
+        Ok(self.finish())
+    }
+}
+
+/// A map being copied.
+struct Map {
+    entries: Vec<(OwnedValue, OwnedValue)>,
+    key: Option<OwnedValue>,
+}
+
+impl SerializeMap for Map {
+    type Ok = OwnedValue;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(OwnedValue::capture(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| serde::ser::Error::custom("map value without a key"))?;
+        self.entries.push((key, OwnedValue::capture(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<OwnedValue, Error> {
+        Ok(OwnedValue::Map(self.entries))
+    }
+}
+
+/// A struct being copied, tagged with its enum variant, if any.
+struct Struct {
+    name: &'static str,
+    variant: Option<&'static str>,
+    fields: Vec<(&'static str, OwnedValue)>,
+}
+
+impl Struct {
+    fn new(name: &'static str, variant: Option<&'static str>, len: usize) -> Self {
+        Self {
+            name,
+            variant,
+            fields: Vec::with_capacity(len),
+        }
+    }
+
+    fn field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+        self.fields.push((key, OwnedValue::capture(value)?));
+        Ok(())
+    }
+
+    fn finish(self) -> OwnedValue {
+        let value = OwnedValue::Struct(self.name, self.fields);
+        match self.variant {
+            Some(variant) => OwnedValue::tagged(variant, value),
+            None => value,
+        }
+    }
+}
+
+impl SerializeStruct for Struct {
+    type Ok = OwnedValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<OwnedValue, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeStructVariant for Struct {
+    type Ok = OwnedValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<OwnedValue, Error> {
+        Ok(self.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::OwnedValue;
+
+    fn round_trip(value: impl serde::Serialize) -> serde_json::Value {
+        serde_json::to_value(OwnedValue::capture(&value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_copies_keep_the_structure() {
+        #[derive(serde::Serialize)]
+        enum Shape {
+            Point,
+            Circle(f64),
+            Rect { w: u32, h: u32 },
+        }
+
+        let value = json!({"user": {"id": 42, "roles": ["admin", null]}, "ok": true});
+        assert_eq!(round_trip(&value), value);
+        assert_eq!(
+            round_trip(BTreeMap::from([("a", (1, Some(-2)))])),
+            json!({"a": [1, -2]})
+        );
+        assert_eq!(
+            round_trip([Shape::Point, Shape::Circle(0.5), Shape::Rect { w: 2, h: 3 }]),
+            json!(["Point", {"Circle": 0.5}, {"Rect": {"w": 2, "h": 3}}])
+        );
+    }
+
+    #[test]
+    fn test_copies_keep_struct_names() {
+        #[derive(serde::Serialize)]
+        struct Point {
+            x: i32,
+        }
+
+        assert_eq!(
+            OwnedValue::capture(&Point { x: -1 }).unwrap(),
+            OwnedValue::Struct("Point", vec![("x", OwnedValue::I64(-1))])
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! Helpers for testing log context propagation.
+
+use crate::LogScope;
+
+/// Asserts that the active log context contains the key with the expected rendered value.
+///
+/// This is the function behind the [`assert_context_has!`](crate::assert_context_has)
+/// macro. Local records shadow inherited ones, just like in the emitted records.
+///
+/// # Panics
+///
+/// Panics if the key is missing or its value renders differently. The message lists
+/// the records of the active context.
+#[track_caller]
+pub fn assert_context_has(key: &str, expected: &dyn std::fmt::Display) {
+    let context = LogScope::current_context();
+    let expected = expected.to_string();
+    let actual = context
+        .local
+        .get(key)
+        .or_else(|| context.inherited.get(key))
+        .map(ToString::to_string);
+    if actual.as_deref() == Some(&*expected) {
+        return;
+    }
+
+    let mut records = context
+        .inherited
+        .iter()
+        .filter(|(key, _)| !context.local.contains_key(key))
+        .chain(context.local.iter())
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>();
+    records.sort_unstable();
+    match actual {
+        Some(actual) => panic!(
+            "expected `{key}` in the active log context to be `{expected}`, \
+             but it is `{actual}`\n  context: [{}]",
+            records.join(", ")
+        ),
+        None => panic!(
+            "expected the active log context to contain `{key}`\n  context: [{}]",
+            records.join(", ")
+        ),
+    }
+}
+
+/// Asserts that the active log context contains the key with the expected value.
+///
+/// The value is compared by its [`Display`](std::fmt::Display) rendering. On failure,
+/// the panic message lists the records of the active context.
+///
+/// Requires the `testing` feature.
+///
+/// # Examples
+///
+/// ```
+/// use context_logger::{LogContext, LogContextExt as _, assert_context_has};
+///
+/// LogContext::new()
+///     .with_inherited_record("request_id", "req-123")
+///     .with_local_record("attempt", 2)
+///     .in_scope(|| {
+///         assert_context_has!("request_id" => "req-123");
+///         assert_context_has!("attempt" => 2);
+///     });
+/// ```
+#[macro_export]
+macro_rules! assert_context_has {
+    ($key:expr => $value:expr $(,)?) => {
+        $crate::testing::assert_context_has($key, &$value)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LogContext, LogContextExt as _};
+
+    fn in_context(f: impl FnOnce()) {
+        LogContext::new()
+            .with_inherited_record("service", "api")
+            .with_inherited_record("stage", "outer")
+            .with_local_record("stage", "inner")
+            .in_scope(f);
+    }
+
+    #[test]
+    fn test_assert_context_has_passes() {
+        in_context(|| {
+            assert_context_has!("service" => "api");
+            assert_context_has!("stage" => "inner");
+        });
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "expected `stage` in the active log context to be `outer`, but it is `inner`\n  \
+                    context: [service=api, stage=inner]"
+    )]
+    fn test_assert_context_has_fails_on_value() {
+        in_context(|| assert_context_has!("stage" => "outer"));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected the active log context to contain `user_id`")]
+    fn test_assert_context_has_fails_on_missing_key() {
+        in_context(|| assert_context_has!("user_id" => 42));
+    }
+}
@@ -0,0 +1,161 @@
+//! Measurement of the time spent by the logger itself.
+
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+thread_local! {
+    /// The time spent in the inner loggers on the current thread so far.
+    static FORWARDED: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Aggregate statistics of the time spent in [`ContextLogger`](crate::ContextLogger).
+///
+/// See [`ContextLogger::with_self_timing`](crate::ContextLogger::with_self_timing).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SelfTimingStats {
+    records: u64,
+    total: Duration,
+    max: Duration,
+}
+
+impl SelfTimingStats {
+    /// Returns the number of measured records.
+    #[must_use]
+    pub const fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// Returns the total time spent logging the measured records.
+    #[must_use]
+    pub const fn total(&self) -> Duration {
+        self.total
+    }
+
+    /// Returns the longest time spent logging a single record.
+    #[must_use]
+    pub const fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Returns the average time spent logging a record.
+    #[must_use]
+    pub fn mean(&self) -> Duration {
+        let nanos = self
+            .total
+            .as_nanos()
+            .checked_div(u128::from(self.records))
+            .unwrap_or_default();
+        Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+    }
+}
+
+type SelfTimingSink = Box<dyn Fn(&SelfTimingStats) + Send + Sync>;
+
+/// Collects the time spent in the logger and periodically reports it to the sink.
+pub struct SelfTiming {
+    interval: Duration,
+    sink: SelfTimingSink,
+    created_at: Instant,
+    // The time of the last report relative to `created_at` and the statistics collected
+    // since then, all in nanoseconds.
+    last_report: AtomicU64,
+    records: AtomicU64,
+    total: AtomicU64,
+    max: AtomicU64,
+}
+
+impl SelfTiming {
+    pub fn new(interval: Duration, sink: SelfTimingSink) -> Self {
+        Self {
+            interval,
+            sink,
+            created_at: Instant::now(),
+            last_report: AtomicU64::new(0),
+            records: AtomicU64::new(0),
+            total: AtomicU64::new(0),
+            max: AtomicU64::new(0),
+        }
+    }
+
+    /// Starts measuring a record, the measurement ends when the returned timer is dropped.
+    pub fn start(&self) -> Timer<'_> {
+        Timer {
+            timing: self,
+            started_at: Instant::now(),
+            forwarded_before: forwarded(),
+        }
+    }
+
+    fn add(&self, elapsed: Duration) {
+        let elapsed = nanos(elapsed);
+        self.records.fetch_add(1, Ordering::Relaxed);
+        self.total.fetch_add(elapsed, Ordering::Relaxed);
+        self.max.fetch_max(elapsed, Ordering::Relaxed);
+
+        let now = nanos(self.created_at.elapsed());
+        let last_report = self.last_report.load(Ordering::Relaxed);
+        // Only the thread that moves the time of the last report calls the sink.
+        if now.saturating_sub(last_report) < nanos(self.interval)
+            || self
+                .last_report
+                .compare_exchange(last_report, now, Ordering::Relaxed, Ordering::Relaxed)
+                .is_err()
+        {
+            return;
+        }
+        let stats = SelfTimingStats {
+            records: self.records.swap(0, Ordering::Relaxed),
+            total: Duration::from_nanos(self.total.swap(0, Ordering::Relaxed)),
+            max: Duration::from_nanos(self.max.swap(0, Ordering::Relaxed)),
+        };
+        (self.sink)(&stats);
+    }
+}
+
+/// Measures the time until it is dropped, excluding the time spent in the inner loggers.
+pub struct Timer<'a> {
+    timing: &'a SelfTiming,
+    started_at: Instant,
+    forwarded_before: Duration,
+}
+
+impl Drop for Timer<'_> {
+    fn drop(&mut self) {
+        let forwarded = forwarded().saturating_sub(self.forwarded_before);
+        self.timing
+            .add(self.started_at.elapsed().saturating_sub(forwarded));
+    }
+}
+
+/// Measures the time spent forwarding a record to the inner loggers until it is dropped,
+/// so that it is excluded from the running [`Timer`] of the current thread.
+pub struct Forwarding {
+    started_at: Instant,
+}
+
+impl Forwarding {
+    pub fn start() -> Self {
+        Self {
+            started_at: Instant::now(),
+        }
+    }
+}
+
+impl Drop for Forwarding {
+    fn drop(&mut self) {
+        let elapsed = self.started_at.elapsed();
+        let _ = FORWARDED.try_with(|forwarded| forwarded.set(forwarded.get() + elapsed));
+    }
+}
+
+/// Returns the time spent in the inner loggers on the current thread so far.
+fn forwarded() -> Duration {
+    FORWARDED.try_with(Cell::get).unwrap_or_default()
+}
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
@@ -1,6 +1,19 @@
 //! Value types for the context logger.
 
-use std::sync::Arc;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, HashSet},
+    hash::BuildHasher,
+    sync::{Arc, LazyLock, PoisonError, RwLock},
+};
+
+use crate::serde_depth::MaxDepth;
+
+/// The maximum number of distinct strings interned by [`LogValue::interned_str`].
+const MAX_INTERNED_STRINGS: usize = 1024;
+
+/// Strings interned by [`LogValue::interned_str`]. They are leaked, so the table is bounded.
+static INTERNED_STRINGS: LazyLock<RwLock<HashSet<&'static str>>> = LazyLock::new(RwLock::default);
 
 /// A sized, cloneable wrapper around `Arc<dyn erased_serde::Serialize>` that implements
 /// `serde::Serialize`. This is needed because `log::kv::Value::from_serde` requires `T: Sized`,
@@ -23,6 +36,201 @@ impl serde::Serialize for SerdeArc {
     }
 }
 
+/// A value computed by a closure every time it is serialized.
+struct LazyValue<F>(F);
+
+impl<F> serde::Serialize for LazyValue<F>
+where
+    F: Fn() -> LogValue,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.0)().as_log_value().serialize(serializer)
+    }
+}
+
+/// A value rendered with the alternate, multi-line debug format.
+struct PrettyDebug<T>(T);
+
+impl<T: std::fmt::Debug> std::fmt::Debug for PrettyDebug<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#?}", self.0)
+    }
+}
+
+/// An integer that keeps its original width, created by the sized constructors such
+/// as [`LogValue::i32`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SizedInt {
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+}
+
+impl SizedInt {
+    const fn bits(self) -> u32 {
+        match self {
+            Self::I8(_) | Self::U8(_) => 8,
+            Self::I16(_) | Self::U16(_) => 16,
+            Self::I32(_) | Self::U32(_) => 32,
+            Self::I64(_) | Self::U64(_) => 64,
+        }
+    }
+}
+
+impl serde::Serialize for SizedInt {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Self::I8(value) => serializer.serialize_i8(value),
+            Self::I16(value) => serializer.serialize_i16(value),
+            Self::I32(value) => serializer.serialize_i32(value),
+            Self::I64(value) => serializer.serialize_i64(value),
+            Self::U8(value) => serializer.serialize_u8(value),
+            Self::U16(value) => serializer.serialize_u16(value),
+            Self::U32(value) => serializer.serialize_u32(value),
+            Self::U64(value) => serializer.serialize_u64(value),
+        }
+    }
+}
+
+/// A key of the type tag added by [`LogValue::serde_tagged`].
+const TYPE_TAG_KEY: &str = "__type";
+
+/// A serde value wrapped together with a type tag.
+struct Tagged<T> {
+    tag: &'static str,
+    value: T,
+}
+
+impl<T: serde::Serialize> serde::Serialize for Tagged<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap as _;
+
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry(TYPE_TAG_KEY, self.tag)?;
+        map.serialize_entry("value", &self.value)?;
+        map.end()
+    }
+}
+
+/// A value rendered by a user provided serialization function.
+struct SerdeWith<T, F> {
+    value: T,
+    serialize: F,
+}
+
+impl<T, F, E> std::fmt::Display for SerdeWith<T, F>
+where
+    F: Fn(&T) -> Result<String, E>,
+    E: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.serialize)(&self.value) {
+            Ok(s) => f.write_str(&s),
+            // Formatting errors are reserved for the formatter itself,
+            // so the serialization error is rendered instead.
+            Err(err) => write!(f, "<serialization error: {err}>"),
+        }
+    }
+}
+
+/// An encoding used to render [bytes](LogValue::bytes) values.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BytesEncoding {
+    /// Lowercase hexadecimal, e.g. `deadbeef`.
+    #[default]
+    Hex,
+    /// Uppercase hexadecimal, e.g. `DEADBEEF`.
+    UpperHex,
+}
+
+/// The kind of a [`LogValue`], as returned by [`LogValue::kind`].
+///
+/// Makes it possible to branch on the type of a value without rendering it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LogValueKind {
+    /// A [null](LogValue::null) value.
+    Null,
+    /// A string value.
+    String,
+    /// A boolean value.
+    Bool,
+    /// A character value.
+    Char,
+    /// A signed or unsigned integer value.
+    Int,
+    /// A floating point value.
+    Float,
+    /// A value created by [`LogValue::debug`].
+    Debug,
+    /// A value created by [`LogValue::display`] or [`LogValue::serde_with`].
+    Display,
+    /// A value created by [`LogValue::error`].
+    Error,
+    /// A value created by [`LogValue::serde`] or [`LogValue::lazy`].
+    Serde,
+    /// A value created by [`LogValue::bytes`].
+    Bytes,
+    /// A value created by [`LogValue::to_value`].
+    ToValue,
+}
+
+/// A representation of [null](LogValue::null) values of default and scope records.
+///
+/// See [`ContextLogger::with_null_repr`](crate::ContextLogger::with_null_repr).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NullRepr {
+    /// Records with null values are skipped entirely.
+    Omit,
+    /// Null values are passed to the backend as is.
+    #[default]
+    Null,
+    /// Null values are replaced with an empty string.
+    EmptyString,
+}
+
+impl NullRepr {
+    /// Applies the representation to the given value, returning `None` if the record
+    /// should be skipped.
+    pub(crate) fn apply(self, value: &LogValue) -> Option<&LogValue> {
+        static EMPTY_STRING: LogValue = LogValue::static_str("");
+
+        if value.kind() != LogValueKind::Null {
+            return Some(value);
+        }
+        match self {
+            Self::Omit => None,
+            Self::Null => Some(value),
+            Self::EmptyString => Some(&EMPTY_STRING),
+        }
+    }
+}
+
+/// Raw bytes that are encoded only when rendered.
+#[derive(Clone)]
+struct Bytes {
+    bytes: Cow<'static, [u8]>,
+    encoding: BytesEncoding,
+}
+
+impl std::fmt::Display for Bytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for byte in &*self.bytes {
+            match self.encoding {
+                BytesEncoding::Hex => write!(f, "{byte:02x}")?,
+                BytesEncoding::UpperHex => write!(f, "{byte:02X}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Represents a value that can be stored in a log record.
 ///
 /// The `LogValue` type is a flexible container designed to hold various kinds of data
@@ -33,6 +241,13 @@ impl serde::Serialize for SerdeArc {
 /// This allows for rich and structured logging, enabling developers to attach meaningful
 /// context to log messages.
 ///
+/// # Cloning
+///
+/// Cloning a value never requires [`Clone`] on the wrapped user type and is lossless:
+/// debug, display, error and `serde` values are stored behind an [`Arc`], so a clone
+/// shares the original object and renders identically. As a consequence, a clone of a
+/// [lazy](Self::lazy) value still computes its value at log time.
+///
 /// # Examples
 ///
 /// ```
@@ -49,6 +264,7 @@ pub struct LogValue(LogValueInner);
 enum LogValueInner {
     Null,
     String(String),
+    StaticStr(&'static str),
     Bool(bool),
     Char(char),
     I64(i64),
@@ -56,10 +272,29 @@ enum LogValueInner {
     F64(f64),
     I128(i128),
     U128(u128),
+    SizedInt(SizedInt),
     Debug(Arc<dyn std::fmt::Debug + Send + Sync + 'static>),
     Display(Arc<dyn std::fmt::Display + Send + Sync + 'static>),
     Error(Arc<dyn std::error::Error + Send + Sync + 'static>),
     Serde(SerdeArc),
+    Bytes(Bytes),
+    ToValue(Arc<dyn log::kv::ToValue + Send + Sync + 'static>),
+}
+
+macro_rules! sized_int_constructors {
+    ($($ty:ident => $arm:ident),*) => {
+        $(
+            #[doc = concat!("Creates an integer value that keeps its `", stringify!($ty), "` width.")]
+            ///
+            /// Converting an integer with [`From`] widens it to 64 bits, while values created
+            /// by this constructor are serialized with their original width and report it
+            /// with [`Self::int_bits`]. This matters for schema-strict consumers.
+            #[must_use]
+            pub const fn $ty(value: $ty) -> Self {
+                Self(LogValueInner::SizedInt(SizedInt::$arm(value)))
+            }
+        )*
+    };
 }
 
 impl From<LogValueInner> for LogValue {
@@ -75,6 +310,65 @@ impl LogValue {
         LogValueInner::Null.into()
     }
 
+    /// Creates a log value from a string literal, stored by reference without allocation.
+    ///
+    /// Converting a `&str` with [`From`] copies it into an owned string, since the
+    /// conversion has to accept borrowed strings of any lifetime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::{LogContext, LogValue};
+    ///
+    /// let context = LogContext::new().with_inherited_record("env", LogValue::static_str("prod"));
+    /// ```
+    #[must_use]
+    pub const fn static_str(value: &'static str) -> Self {
+        Self(LogValueInner::StaticStr(value))
+    }
+
+    /// Creates a string log value that shares one allocation with all equal interned values.
+    ///
+    /// This reduces memory usage for high-volume records with low-cardinality string
+    /// fields, such as `status=active`. Interned strings are kept for the lifetime of the
+    /// process, so the table is bounded: once 1024 distinct strings are interned, new
+    /// strings are copied into owned values as with [`From<&str>`](From).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::LogValue;
+    ///
+    /// let status = LogValue::interned_str("active");
+    /// assert_eq!(status.as_str(), Some("active"));
+    /// ```
+    #[must_use]
+    pub fn interned_str(value: &str) -> Self {
+        let interned = INTERNED_STRINGS
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(value)
+            .copied();
+        if let Some(interned) = interned {
+            return Self::static_str(interned);
+        }
+
+        let mut strings = INTERNED_STRINGS
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        let interned = match strings.get(value) {
+            Some(interned) => Some(*interned),
+            None if strings.len() < MAX_INTERNED_STRINGS => {
+                let interned: &'static str = Box::leak(value.into());
+                strings.insert(interned);
+                Some(interned)
+            }
+            None => None,
+        };
+        drop(strings);
+        interned.map_or_else(|| value.into(), Self::static_str)
+    }
+
     /// Creates a log value from a [`serde::Serialize`].
     pub fn serde<S>(value: S) -> Self
     where
@@ -83,6 +377,135 @@ impl LogValue {
         LogValueInner::Serde(SerdeArc::new(value)).into()
     }
 
+    /// Creates a log value with the number of elements of the collection.
+    ///
+    /// This is an alternative to recording the contents of high-cardinality collections,
+    /// which are expensive to serialize and make records large.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::LogValue;
+    ///
+    /// let ids = vec![1, 2, 3];
+    /// assert_eq!(LogValue::len_of(&ids).to_string(), "3");
+    /// ```
+    pub fn len_of<I>(collection: I) -> Self
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+    {
+        collection.into_iter().len().into()
+    }
+
+    /// Creates a log value from a [`serde::Serialize`] wrapped together with a type tag.
+    ///
+    /// The value is serialized as a `{"__type": tag, "value": value}` object, so consumers
+    /// can deserialize heterogeneous context objects by their tag. Since [`log::kv`] values
+    /// have a single representation, backends that render values as text display the
+    /// wrapper as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::LogValue;
+    ///
+    /// let value = LogValue::serde_tagged("Point", [1, 2]);
+    /// assert_eq!(
+    ///     serde_json::to_string(&value.as_log_value()).unwrap(),
+    ///     r#"{"__type":"Point","value":[1,2]}"#
+    /// );
+    /// ```
+    pub fn serde_tagged<S>(tag: &'static str, value: S) -> Self
+    where
+        S: serde::Serialize + Send + Sync + 'static,
+    {
+        Self::serde(Tagged { tag, value })
+    }
+
+    /// Creates a log value from a [`serde::Serialize`] rendered by a custom serializer.
+    ///
+    /// Unlike [`Self::serde`], which passes the value to the serializer of the logging
+    /// backend, the value is serialized into a string by the given function, e.g. to use
+    /// a domain specific encoding. The function is called only when the value is rendered.
+    /// If it fails, the error message is rendered instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::LogValue;
+    ///
+    /// let value = LogValue::serde_with(vec![1, 2], |value| serde_json::to_string_pretty(value));
+    /// assert_eq!(value.to_string(), "[\n  1,\n  2\n]");
+    /// ```
+    pub fn serde_with<T, F, E>(value: T, serialize: F) -> Self
+    where
+        T: serde::Serialize + Send + Sync + 'static,
+        F: Fn(&T) -> Result<String, E> + Send + Sync + 'static,
+        E: std::fmt::Display + 'static,
+    {
+        Self::display(SerdeWith { value, serialize })
+    }
+
+    /// Creates a log value computed by the given closure at log time.
+    ///
+    /// This is useful for fields that should reflect the moment of logging rather than
+    /// the moment of the context creation, such as a current queue depth. The closure
+    /// is called every time the value is rendered, which is typically once per emitted
+    /// record, so it should be cheap. The computed value is passed to the logging backend
+    /// as a [`serde`](Self::serde) value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+    ///
+    /// use context_logger::{LogContext, LogValue};
+    ///
+    /// let queue_depth = Arc::new(AtomicUsize::new(0));
+    /// let context = LogContext::new().with_local_record("queue_depth", {
+    ///     let queue_depth = queue_depth.clone();
+    ///     LogValue::lazy(move || queue_depth.load(Ordering::Relaxed))
+    /// });
+    /// ```
+    pub fn lazy<F, V>(f: F) -> Self
+    where
+        F: Fn() -> V + Send + Sync + 'static,
+        V: Into<Self>,
+    {
+        Self::serde(LazyValue(move || f().into()))
+    }
+
+    /// Creates a log value from a [`log::kv::ToValue`].
+    ///
+    /// The value is rendered by its own [`ToValue`](log::kv::ToValue) implementation, so
+    /// types that already support the [`log`] key-values are recorded without converting
+    /// them through `Display` or serde.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::LogValue;
+    /// use log::kv::{ToValue, Value};
+    ///
+    /// struct Port(u16);
+    ///
+    /// impl ToValue for Port {
+    ///     fn to_value(&self) -> Value<'_> {
+    ///         Value::from(self.0)
+    ///     }
+    /// }
+    ///
+    /// let value = LogValue::to_value(Port(8080));
+    /// assert_eq!(value.as_log_value().to_u64(), Some(8080));
+    /// ```
+    pub fn to_value<T>(value: T) -> Self
+    where
+        T: log::kv::ToValue + Send + Sync + 'static,
+    {
+        LogValueInner::ToValue(Arc::new(value)).into()
+    }
+
     /// Creates a log value from a [`std::fmt::Display`].
     pub fn display<T>(value: T) -> Self
     where
@@ -91,6 +514,56 @@ impl LogValue {
         LogValueInner::Display(Arc::new(value)).into()
     }
 
+    /// Creates a log value by eagerly formatting a borrowed [`std::fmt::Display`].
+    ///
+    /// Other constructors require `'static` values, since records may outlive the
+    /// code that creates them. This is the way to record a borrowed local: the value
+    /// is rendered into an owned string right away, so later changes are not reflected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::{LogContext, LogContextExt as _, LogValue};
+    ///
+    /// fn handle(path: &str) {
+    ///     LogContext::new()
+    ///         .with_local_record("path", LogValue::display_ref(path))
+    ///         .in_scope(|| log::info!("Handling request"));
+    /// }
+    /// ```
+    pub fn display_ref<T>(value: &T) -> Self
+    where
+        T: std::fmt::Display + ?Sized,
+    {
+        LogValueInner::String(value.to_string()).into()
+    }
+
+    /// Creates a log value from pre-formatted [`std::fmt::Arguments`].
+    ///
+    /// The arguments are rendered into an owned string right away. Arguments without
+    /// any placeholders, e.g. `format_args!("literal")`, are stored by reference
+    /// without allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::LogValue;
+    ///
+    /// let (shard, replica) = (3, 1);
+    /// let value = LogValue::from_arguments(format_args!("{shard}-{replica}"));
+    /// assert_eq!(value.to_string(), "3-1");
+    /// ```
+    #[must_use]
+    pub fn from_arguments(args: std::fmt::Arguments<'_>) -> Self {
+        args.as_str().map_or_else(
+            || LogValueInner::String(args.to_string()).into(),
+            Self::static_str,
+        )
+    }
+
+    sized_int_constructors!(i8 => I8, i16 => I16, i32 => I32, i64 => I64);
+    sized_int_constructors!(u8 => U8, u16 => U16, u32 => U32, u64 => U64);
+
     /// Creates a log value from a [`std::fmt::Debug`].
     pub fn debug<T>(value: T) -> Self
     where
@@ -99,6 +572,32 @@ impl LogValue {
         LogValueInner::Debug(Arc::new(value)).into()
     }
 
+    /// Creates a log value rendered with the alternate `{:#?}` debug format.
+    ///
+    /// The pretty form spreads nested structures over multiple indented lines, which
+    /// is easier to read in human-oriented output. The rendered text is noticeably
+    /// larger than the compact [`Self::debug`] form and contains newlines, so it is
+    /// better suited for development logs than for line-oriented or size-limited sinks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::LogValue;
+    ///
+    /// #[derive(Debug)]
+    /// struct Point {
+    ///     x: i32,
+    /// }
+    ///
+    /// assert_eq!(LogValue::debug_pretty(Point { x: 1 }).to_string(), "Point {\n    x: 1,\n}");
+    /// ```
+    pub fn debug_pretty<T>(value: T) -> Self
+    where
+        T: std::fmt::Debug + Send + Sync + 'static,
+    {
+        Self::debug(PrettyDebug(value))
+    }
+
     /// Creates a log value from a [`std::error::Error`].
     pub fn error<T>(value: T) -> Self
     where
@@ -107,12 +606,167 @@ impl LogValue {
         LogValueInner::Error(Arc::new(value)).into()
     }
 
+    /// Creates a log value from raw bytes rendered as lowercase hex.
+    ///
+    /// The bytes are stored as is and encoded only when the value is rendered,
+    /// so filtered out records do not pay for the encoding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::LogValue;
+    ///
+    /// let value = LogValue::bytes(&b"\xde\xad\xbe\xef"[..]);
+    /// assert_eq!(value.to_string(), "deadbeef");
+    /// ```
+    pub fn bytes(value: impl Into<Cow<'static, [u8]>>) -> Self {
+        Self::bytes_with_encoding(value, BytesEncoding::default())
+    }
+
+    /// Creates a log value from raw bytes rendered with the given encoding.
+    pub fn bytes_with_encoding(
+        value: impl Into<Cow<'static, [u8]>>,
+        encoding: BytesEncoding,
+    ) -> Self {
+        LogValueInner::Bytes(Bytes {
+            bytes: value.into(),
+            encoding,
+        })
+        .into()
+    }
+
+    /// Returns the kind of this value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::{LogValue, LogValueKind};
+    ///
+    /// assert_eq!(LogValue::from(42).kind(), LogValueKind::Int);
+    /// assert_eq!(LogValue::display("text").kind(), LogValueKind::Display);
+    /// ```
+    #[must_use]
+    pub const fn kind(&self) -> LogValueKind {
+        match &self.0 {
+            LogValueInner::Null => LogValueKind::Null,
+            LogValueInner::String(_) | LogValueInner::StaticStr(_) => LogValueKind::String,
+            LogValueInner::Bool(_) => LogValueKind::Bool,
+            LogValueInner::Char(_) => LogValueKind::Char,
+            LogValueInner::I64(_)
+            | LogValueInner::U64(_)
+            | LogValueInner::I128(_)
+            | LogValueInner::U128(_)
+            | LogValueInner::SizedInt(_) => LogValueKind::Int,
+            LogValueInner::F64(_) => LogValueKind::Float,
+            LogValueInner::Debug(_) => LogValueKind::Debug,
+            LogValueInner::Display(_) => LogValueKind::Display,
+            LogValueInner::Error(_) => LogValueKind::Error,
+            LogValueInner::Serde(_) => LogValueKind::Serde,
+            LogValueInner::Bytes(_) => LogValueKind::Bytes,
+            LogValueInner::ToValue(_) => LogValueKind::ToValue,
+        }
+    }
+
+    /// Returns the width in bits of an integer value, if it is known.
+    ///
+    /// The width is known for values created by the sized constructors such as
+    /// [`Self::i32`] and for 128-bit integers. Other integers are widened to 64 bits
+    /// when converted and have no width hint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::LogValue;
+    ///
+    /// assert_eq!(LogValue::u16(8080).int_bits(), Some(16));
+    /// assert_eq!(LogValue::from(8080_u16).int_bits(), None);
+    /// ```
+    #[must_use]
+    pub const fn int_bits(&self) -> Option<u32> {
+        match &self.0 {
+            LogValueInner::SizedInt(value) => Some(value.bits()),
+            LogValueInner::I128(_) | LogValueInner::U128(_) => Some(128),
+            _ => None,
+        }
+    }
+
+    /// Returns the underlying string slice if this value is a string.
+    ///
+    /// Values built from `Display`, `Debug` or serde sources return `None`,
+    /// even if they render as text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use context_logger::LogValue;
+    ///
+    /// assert_eq!(LogValue::from("request-42").as_str(), Some("request-42"));
+    /// assert_eq!(LogValue::from(42).as_str(), None);
+    /// ```
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.0 {
+            LogValueInner::String(s) => Some(s),
+            LogValueInner::StaticStr(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if both values are known to render identically.
+    ///
+    /// Primitive and string values are compared by value, while debug, display, error
+    /// and `serde` values are only considered equal if they share the same object, so
+    /// the check never renders a value.
+    pub(crate) fn is_same(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (LogValueInner::Null, LogValueInner::Null) => true,
+            (
+                LogValueInner::String(_) | LogValueInner::StaticStr(_),
+                LogValueInner::String(_) | LogValueInner::StaticStr(_),
+            ) => self.as_str() == other.as_str(),
+            (LogValueInner::Bool(a), LogValueInner::Bool(b)) => a == b,
+            (LogValueInner::Char(a), LogValueInner::Char(b)) => a == b,
+            (LogValueInner::I64(a), LogValueInner::I64(b)) => a == b,
+            (LogValueInner::U64(a), LogValueInner::U64(b)) => a == b,
+            (LogValueInner::F64(a), LogValueInner::F64(b)) => a.to_bits() == b.to_bits(),
+            (LogValueInner::I128(a), LogValueInner::I128(b)) => a == b,
+            (LogValueInner::U128(a), LogValueInner::U128(b)) => a == b,
+            (LogValueInner::SizedInt(a), LogValueInner::SizedInt(b)) => a == b,
+            (LogValueInner::Debug(a), LogValueInner::Debug(b)) => Arc::ptr_eq(a, b),
+            (LogValueInner::Display(a), LogValueInner::Display(b)) => Arc::ptr_eq(a, b),
+            (LogValueInner::Error(a), LogValueInner::Error(b)) => Arc::ptr_eq(a, b),
+            (LogValueInner::Serde(a), LogValueInner::Serde(b)) => Arc::ptr_eq(&a.0, &b.0),
+            (LogValueInner::Bytes(a), LogValueInner::Bytes(b)) => {
+                a.encoding == b.encoding && a.bytes == b.bytes
+            }
+            (LogValueInner::ToValue(a), LogValueInner::ToValue(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+
+    /// Returns the value with `serde` structures nested deeper than `depth` truncated.
+    ///
+    /// Values of other kinds are returned as is.
+    pub(crate) fn with_max_serde_depth(&self, depth: usize) -> Cow<'_, Self> {
+        match &self.0 {
+            LogValueInner::Serde(value) => Cow::Owned(
+                LogValueInner::Serde(SerdeArc::new(MaxDepth {
+                    value: value.clone(),
+                    depth,
+                }))
+                .into(),
+            ),
+            _ => Cow::Borrowed(self),
+        }
+    }
+
     /// Converts the log value to a value compatible with the [`log`] crate.
     #[must_use]
     pub fn as_log_value(&self) -> log::kv::Value<'_> {
         match &self.0 {
             LogValueInner::Null => log::kv::Value::null(),
             LogValueInner::String(s) => log::kv::Value::from(&**s),
+            LogValueInner::StaticStr(s) => log::kv::Value::from(*s),
             LogValueInner::Bool(b) => log::kv::Value::from(*b),
             LogValueInner::Char(c) => log::kv::Value::from(*c),
             LogValueInner::I64(i) => log::kv::Value::from(*i),
@@ -120,10 +774,13 @@ impl LogValue {
             LogValueInner::F64(f) => log::kv::Value::from(*f),
             LogValueInner::I128(i) => log::kv::Value::from(*i),
             LogValueInner::U128(u) => log::kv::Value::from(*u),
+            LogValueInner::SizedInt(value) => log::kv::Value::from_serde(value),
             LogValueInner::Display(value) => log::kv::Value::from_dyn_display(&**value),
             LogValueInner::Debug(value) => log::kv::Value::from_dyn_debug(&**value),
             LogValueInner::Error(value) => log::kv::Value::from_dyn_error(&**value),
             LogValueInner::Serde(value) => log::kv::Value::from_serde(value),
+            LogValueInner::Bytes(value) => log::kv::Value::from_display(value),
+            LogValueInner::ToValue(value) => value.to_value(),
         }
     }
 }
@@ -144,7 +801,6 @@ impl_log_value_from_primitive!(
     bool => Bool,
     char => Char,
     &str => String,
-    std::borrow::Cow<'_, str> => String,
     String => String,
     i8 => I64,
     i16 => I64,
@@ -160,6 +816,190 @@ impl_log_value_from_primitive!(
     u128 => U128
 );
 
+impl From<usize> for LogValue {
+    /// Stored as a `u64`, `usize` is at most 64 bits wide on all supported platforms.
+    fn from(value: usize) -> Self {
+        LogValueInner::U64(value as u64).into()
+    }
+}
+
+impl From<isize> for LogValue {
+    /// Stored as an `i64`, `isize` is at most 64 bits wide on all supported platforms.
+    fn from(value: isize) -> Self {
+        LogValueInner::I64(value as i64).into()
+    }
+}
+
+macro_rules! impl_log_value_from_ref {
+    ($($ty:ty),*) => {
+        $(
+            impl From<&$ty> for LogValue {
+                fn from(value: &$ty) -> Self {
+                    value.clone().into()
+                }
+            }
+        )*
+    };
+}
+
+impl_log_value_from_ref!(
+    bool, char, String, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64
+);
+
+macro_rules! impl_log_value_from_to_string {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for LogValue {
+                fn from(value: $ty) -> Self {
+                    LogValue(LogValueInner::String(value.to_string()))
+                }
+            }
+        )*
+    };
+}
+
+// Network addresses are rendered in their canonical form, including IPv6 scope ids.
+impl_log_value_from_to_string!(
+    std::net::IpAddr,
+    std::net::Ipv4Addr,
+    std::net::Ipv6Addr,
+    std::net::SocketAddr,
+    std::net::SocketAddrV4,
+    std::net::SocketAddrV6
+);
+
+#[cfg(feature = "serde_json")]
+impl From<serde_json::Number> for LogValue {
+    /// Numbers are mapped to the matching numeric representation. Integers that do
+    /// not fit into `u64` or `i64` and floats that do not fit into `f64` (possible with
+    /// the `arbitrary_precision` feature of `serde_json`) are stored as strings, so
+    /// they keep all their digits.
+    fn from(value: serde_json::Number) -> Self {
+        value
+            .as_u64()
+            .map(LogValueInner::U64)
+            .or_else(|| value.as_i64().map(LogValueInner::I64))
+            .or_else(|| {
+                value
+                    .is_f64()
+                    .then(|| value.as_f64())
+                    .flatten()
+                    .map(LogValueInner::F64)
+            })
+            .unwrap_or_else(|| LogValueInner::String(value.to_string()))
+            .into()
+    }
+}
+
+impl From<Cow<'static, str>> for LogValue {
+    /// Borrowed static strings are stored by reference without allocation,
+    /// owned strings are moved into the value.
+    fn from(value: Cow<'static, str>) -> Self {
+        match value {
+            Cow::Borrowed(s) => LogValueInner::StaticStr(s).into(),
+            Cow::Owned(s) => LogValueInner::String(s).into(),
+        }
+    }
+}
+
+impl From<&std::path::Path> for LogValue {
+    /// Paths are stored as strings. Non-UTF-8 sequences are replaced with `U+FFFD`,
+    /// like in [`std::path::Path::display`].
+    fn from(value: &std::path::Path) -> Self {
+        LogValueInner::String(value.to_string_lossy().into_owned()).into()
+    }
+}
+
+impl From<std::path::PathBuf> for LogValue {
+    /// Valid UTF-8 paths are moved into the value without copying. Non-UTF-8 sequences
+    /// are replaced with `U+FFFD`, like in [`std::path::Path::display`].
+    fn from(value: std::path::PathBuf) -> Self {
+        match value.into_os_string().into_string() {
+            Ok(s) => LogValueInner::String(s).into(),
+            Err(s) => LogValueInner::String(s.to_string_lossy().into_owned()).into(),
+        }
+    }
+}
+
+impl From<Cow<'static, [u8]>> for LogValue {
+    /// Bytes are stored without copying and rendered as lowercase hex,
+    /// see [`LogValue::bytes`].
+    fn from(value: Cow<'static, [u8]>) -> Self {
+        Self::bytes(value)
+    }
+}
+
+impl<T> From<Option<T>> for LogValue
+where
+    T: Into<Self>,
+{
+    /// `Some` values are converted as usual, `None` becomes a [null](Self::null) value.
+    fn from(value: Option<T>) -> Self {
+        value.map_or_else(Self::null, Into::into)
+    }
+}
+
+impl<T, E> From<Result<T, E>> for LogValue
+where
+    T: Into<Self>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// `Ok` values are converted as usual, `Err` values are stored as
+    /// [errors](Self::error) and rendered via their [`std::fmt::Display`] implementation.
+    fn from(value: Result<T, E>) -> Self {
+        value.map_or_else(Self::error, Into::into)
+    }
+}
+
+impl<V, S> From<HashMap<String, V, S>> for LogValue
+where
+    V: serde::Serialize + Send + Sync + 'static,
+    S: BuildHasher + Send + Sync + 'static,
+{
+    /// Maps are stored as [`serde`](Self::serde) values and rendered as nested objects.
+    ///
+    /// The order of entries follows the iteration order of the map and is not guaranteed;
+    /// use a [`BTreeMap`] if a deterministic order is required.
+    fn from(value: HashMap<String, V, S>) -> Self {
+        Self::serde(value)
+    }
+}
+
+impl<V> From<BTreeMap<String, V>> for LogValue
+where
+    V: serde::Serialize + Send + Sync + 'static,
+{
+    /// Maps are stored as [`serde`](Self::serde) values and rendered as nested objects
+    /// with entries sorted by key.
+    fn from(value: BTreeMap<String, V>) -> Self {
+        Self::serde(value)
+    }
+}
+
+impl<T> From<Vec<T>> for LogValue
+where
+    T: serde::Serialize + Send + Sync + 'static,
+{
+    /// Vectors are stored as [`serde`](Self::serde) values and rendered as arrays.
+    ///
+    /// Every element is serialized each time the record is rendered, so large collections
+    /// make every record larger and slower to emit. Use [`Self::len_of`] to record only
+    /// the number of elements.
+    fn from(value: Vec<T>) -> Self {
+        Self::serde(value)
+    }
+}
+
+impl<T> From<&[T]> for LogValue
+where
+    T: serde::Serialize + Clone + Send + Sync + 'static,
+{
+    /// Slices are copied into a vector, see the conversion from [`Vec`].
+    fn from(value: &[T]) -> Self {
+        Self::serde(value.to_vec())
+    }
+}
+
 impl std::fmt::Display for LogValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.as_log_value().fmt(f)
@@ -171,3 +1011,428 @@ impl std::fmt::Debug for LogValue {
         self.as_log_value().fmt(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Cow,
+        collections::{BTreeMap, HashMap},
+        net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6},
+    };
+
+    use pretty_assertions::assert_eq;
+
+    use super::{BytesEncoding, LogValue, LogValueInner, LogValueKind};
+
+    #[test]
+    fn test_borrowed_static_cow_is_not_copied() {
+        static VALUE: &str = "production";
+
+        let value = LogValue::from(Cow::Borrowed(VALUE));
+        let LogValueInner::StaticStr(stored) = value.0 else {
+            panic!("expected a static string variant");
+        };
+        assert_eq!(stored.as_ptr(), VALUE.as_ptr());
+        assert_eq!(value.to_string(), "production");
+    }
+
+    #[test]
+    fn test_static_str_is_not_copied() {
+        let literal = "prod";
+        let value = LogValue::static_str(literal);
+        let LogValueInner::StaticStr(stored) = value.0 else {
+            panic!("expected a static string");
+        };
+        assert_eq!(stored.as_ptr(), literal.as_ptr());
+        assert_eq!(value.to_string(), "prod");
+    }
+
+    #[test]
+    fn test_owned_cow_is_moved() {
+        let owned = String::from("staging");
+        let ptr = owned.as_ptr();
+
+        let value = LogValue::from(Cow::<'static, str>::Owned(owned));
+        let LogValueInner::String(stored) = &value.0 else {
+            panic!("expected an owned string variant");
+        };
+        assert_eq!(stored.as_ptr(), ptr);
+        assert_eq!(value.to_string(), "staging");
+    }
+
+    #[test]
+    fn test_bytes_are_stored_raw() {
+        static BYTES: &[u8] = &[0x00, 0x0f, 0xab, 0xff];
+
+        let value = LogValue::from(Cow::Borrowed(BYTES));
+        let LogValueInner::Bytes(stored) = &value.0 else {
+            panic!("expected a bytes variant");
+        };
+        assert_eq!(stored.bytes.as_ptr(), BYTES.as_ptr());
+        assert_eq!(value.to_string(), "000fabff");
+    }
+
+    #[test]
+    fn test_bytes_encoding() {
+        let value =
+            LogValue::bytes_with_encoding(vec![0xde, 0xad, 0xbe, 0xef], BytesEncoding::UpperHex);
+        assert_eq!(value.to_string(), "DEADBEEF");
+        assert_eq!(LogValue::bytes(Vec::new()).to_string(), "");
+    }
+
+    #[test]
+    fn test_numeric_values() {
+        let value = LogValue::from(usize::MAX);
+        assert!(matches!(value.0, LogValueInner::U64(n) if n == usize::MAX as u64));
+        assert_eq!(LogValue::from(3_usize).to_string(), "3");
+
+        let value = LogValue::from(isize::MIN);
+        assert!(matches!(value.0, LogValueInner::I64(n) if n == isize::MIN as i64));
+        assert_eq!(LogValue::from(-3_isize).to_string(), "-3");
+
+        let value = LogValue::from(1.5_f32);
+        assert!(matches!(value.0, LogValueInner::F64(1.5)));
+        assert_eq!(LogValue::from(0.25_f32).to_string(), "0.25");
+    }
+
+    #[test]
+    fn test_serde_with_custom_serializer() {
+        #[derive(serde::Serialize)]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        let value = LogValue::serde_with(Point { x: 1, y: -2 }, |point| {
+            let json = serde_json::to_value(point)?;
+            Ok::<_, serde_json::Error>(format!("Point<{}, {}>", json["x"], json["y"]))
+        });
+        assert_eq!(value.to_string(), "Point<1, -2>");
+
+        let value = LogValue::serde_with(1, |_| Err("unsupported"));
+        assert_eq!(value.to_string(), "<serialization error: unsupported>");
+    }
+
+    #[test]
+    fn test_paths() {
+        let path = std::path::Path::new("/var/log/app.log");
+        assert_eq!(LogValue::from(path).to_string(), "/var/log/app.log");
+        assert_eq!(
+            LogValue::from(path.to_path_buf()).to_string(),
+            "/var/log/app.log"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_paths() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt as _, path::Path};
+
+        let path = Path::new(OsStr::from_bytes(b"/tmp/\xffname"));
+        assert_eq!(LogValue::from(path).to_string(), "/tmp/\u{fffd}name");
+        assert_eq!(
+            LogValue::from(path.to_path_buf()).to_string(),
+            "/tmp/\u{fffd}name"
+        );
+    }
+
+    #[test]
+    fn test_lazy_value_is_computed_on_render() {
+        let counter = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let value = LogValue::lazy({
+            let counter = counter.clone();
+            move || counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        });
+
+        assert_eq!(value.to_string(), "0");
+        assert_eq!(value.to_string(), "1");
+        assert_eq!(
+            serde_json::to_value(value.as_log_value()).unwrap(),
+            serde_json::json!(2)
+        );
+        assert_eq!(counter.load(std::sync::atomic::Ordering::Relaxed), 3);
+
+        let value = LogValue::lazy(|| "text");
+        assert_eq!(
+            serde_json::to_value(value.as_log_value()).unwrap(),
+            serde_json::json!("text")
+        );
+    }
+
+    #[test]
+    fn test_debug_pretty() {
+        let request = || (1, Some(["alice"]));
+
+        assert_eq!(
+            LogValue::debug(request()).to_string(),
+            r#"(1, Some(["alice"]))"#
+        );
+        let pretty = LogValue::debug_pretty(request());
+        assert_eq!(pretty.kind(), LogValueKind::Debug);
+        assert_eq!(
+            pretty.to_string(),
+            "(\n    1,\n    Some(\n        [\n            \"alice\",\n        ],\n    ),\n)"
+        );
+        assert_eq!(
+            serde_json::to_value(pretty.as_log_value()).unwrap(),
+            serde_json::json!(pretty.to_string())
+        );
+    }
+
+    #[test]
+    fn test_display_ref_of_borrowed_local() {
+        let mut name = String::from("alice");
+        let context =
+            crate::LogContext::new().with_local_record("user", LogValue::display_ref(&name));
+        name.push_str("-changed");
+
+        assert!(matches!(context.local["user"].0, LogValueInner::String(_)));
+        assert_eq!(context.local["user"].to_string(), "alice");
+    }
+
+    #[test]
+    fn test_value_kinds() {
+        let kinds = [
+            (LogValue::null(), LogValueKind::Null),
+            (LogValue::from("text"), LogValueKind::String),
+            (LogValue::static_str("text"), LogValueKind::String),
+            (LogValue::from(true), LogValueKind::Bool),
+            (LogValue::from('c'), LogValueKind::Char),
+            (LogValue::from(-1), LogValueKind::Int),
+            (LogValue::from(1_u64), LogValueKind::Int),
+            (LogValue::from(u128::MAX), LogValueKind::Int),
+            (LogValue::from(1.5), LogValueKind::Float),
+            (LogValue::debug([1, 2]), LogValueKind::Debug),
+            (LogValue::display("text"), LogValueKind::Display),
+            (LogValue::display_ref("text"), LogValueKind::String),
+            (LogValue::error(std::fmt::Error), LogValueKind::Error),
+            (LogValue::serde([1, 2]), LogValueKind::Serde),
+            (LogValue::lazy(|| 1), LogValueKind::Serde),
+            (LogValue::bytes(&b"\x01"[..]), LogValueKind::Bytes),
+        ];
+        for (value, kind) in kinds {
+            assert_eq!(value.kind(), kind, "{value:?}");
+        }
+    }
+
+    #[test]
+    fn test_sized_ints() {
+        let values = [
+            (LogValue::i8(-8), 8, serde_json::json!(-8)),
+            (LogValue::i16(-16), 16, serde_json::json!(-16)),
+            (LogValue::i32(-32), 32, serde_json::json!(-32)),
+            (LogValue::i64(-64), 64, serde_json::json!(-64)),
+            (LogValue::u8(8), 8, serde_json::json!(8)),
+            (LogValue::u16(16), 16, serde_json::json!(16)),
+            (LogValue::u32(u32::MAX), 32, serde_json::json!(u32::MAX)),
+            (LogValue::u64(u64::MAX), 64, serde_json::json!(u64::MAX)),
+        ];
+        for (value, bits, json) in values {
+            assert_eq!(value.kind(), LogValueKind::Int);
+            assert_eq!(value.int_bits(), Some(bits));
+            assert_eq!(value.to_string(), json.to_string());
+            assert_eq!(serde_json::to_value(value.as_log_value()).unwrap(), json);
+        }
+
+        assert_eq!(LogValue::i32(-1).as_log_value().to_i64(), Some(-1));
+        assert_eq!(LogValue::u8(1).as_log_value().to_u64(), Some(1));
+        assert_eq!(LogValue::from(1_i32).int_bits(), None);
+        assert_eq!(LogValue::from(1_u128).int_bits(), Some(128));
+        assert!(LogValue::i32(1).is_same(&LogValue::i32(1)));
+        assert!(!LogValue::i32(1).is_same(&LogValue::i64(1)));
+    }
+
+    #[test]
+    fn test_collections() {
+        let ids = vec![1_u64, 2, 3];
+
+        let value = LogValue::from(ids.as_slice());
+        assert_eq!(value.kind(), LogValueKind::Serde);
+        assert_eq!(
+            serde_json::to_value(value.as_log_value()).unwrap(),
+            serde_json::json!([1, 2, 3])
+        );
+        let value = LogValue::from(vec!["a", "b"]);
+        assert_eq!(
+            serde_json::to_value(value.as_log_value()).unwrap(),
+            serde_json::json!(["a", "b"])
+        );
+
+        let value = LogValue::len_of(&ids);
+        assert_eq!(value.kind(), LogValueKind::Int);
+        assert_eq!(value.to_string(), "3");
+        let users = HashMap::from([(1, "alice"), (2, "bob")]);
+        assert_eq!(LogValue::len_of(&users).to_string(), "2");
+    }
+
+    #[test]
+    fn test_interned_str() {
+        let first = LogValue::interned_str(&String::from("interned-status"));
+        let second = LogValue::interned_str(&String::from("interned-status"));
+
+        assert_eq!(first.kind(), LogValueKind::String);
+        assert_eq!(first.to_string(), "interned-status");
+        // The second value reuses the allocation of the first one.
+        assert_eq!(
+            first.as_str().unwrap().as_ptr(),
+            second.as_str().unwrap().as_ptr()
+        );
+        assert!(matches!(second.0, LogValueInner::StaticStr(_)));
+        assert_ne!(
+            LogValue::interned_str("interned-other")
+                .as_str()
+                .unwrap()
+                .as_ptr(),
+            first.as_str().unwrap().as_ptr()
+        );
+    }
+
+    #[test]
+    fn test_references() {
+        let name = String::from("alice");
+        let id = -42_i64;
+
+        let value = LogValue::from(&name);
+        assert!(matches!(value.0, LogValueInner::String(_)));
+        assert_eq!(value.to_string(), "alice");
+        assert!(matches!(LogValue::from(&id).0, LogValueInner::I64(-42)));
+        assert_eq!(LogValue::from(&true).to_string(), "true");
+    }
+
+    #[test]
+    fn test_from_arguments() {
+        let (a, b) = ("shard", 3);
+        let value = LogValue::from_arguments(format_args!("{a}-{b}"));
+        assert!(matches!(value.0, LogValueInner::String(_)));
+        assert_eq!(value.to_string(), "shard-3");
+
+        let value = LogValue::from_arguments(format_args!("literal"));
+        assert!(matches!(value.0, LogValueInner::StaticStr("literal")));
+    }
+
+    #[test]
+    fn test_cloned_context_renders_identically() {
+        #[derive(Debug)]
+        struct NotClone(u32);
+
+        impl std::fmt::Display for NotClone {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "not-clone-{}", self.0)
+            }
+        }
+
+        let context = crate::LogContext::new()
+            .with_local_record("debug", LogValue::debug(NotClone(1)))
+            .with_local_record("display", LogValue::display(NotClone(2)))
+            .with_local_record("error", LogValue::error(std::fmt::Error))
+            .with_inherited_record("serde", LogValue::serde([1, 2]));
+        let cloned = context.clone();
+
+        for (records, cloned_records) in [
+            (&context.local, &cloned.local),
+            (&context.inherited, &cloned.inherited),
+        ] {
+            for (key, value) in records {
+                let cloned_value = &cloned_records[key];
+                assert_eq!(cloned_value.to_string(), value.to_string());
+                assert_eq!(
+                    serde_json::to_value(cloned_value.as_log_value()).unwrap(),
+                    serde_json::to_value(value.as_log_value()).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_option_values() {
+        assert_eq!(LogValue::from(Some(42)).to_string(), "42");
+        assert_eq!(LogValue::from(Some("text")).to_string(), "text");
+        assert!(matches!(LogValue::from(None::<i32>).0, LogValueInner::Null));
+    }
+
+    #[test]
+    fn test_result_values() {
+        let ok: Result<u32, std::fmt::Error> = Ok(7);
+        assert_eq!(LogValue::from(ok).to_string(), "7");
+
+        let err: Result<u32, _> = "x".parse::<u32>();
+        let value = LogValue::from(err);
+        assert!(value.as_log_value().to_borrowed_error().is_some());
+        assert_eq!(value.to_string(), "invalid digit found in string");
+    }
+
+    #[test]
+    fn test_network_addresses() {
+        let v4 = Ipv4Addr::new(192, 168, 0, 1);
+        let v6 = Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1);
+
+        assert_eq!(LogValue::from(v4).to_string(), "192.168.0.1");
+        assert_eq!(LogValue::from(IpAddr::V6(v6)).to_string(), "fe80::1");
+        assert_eq!(
+            LogValue::from(SocketAddr::from((v4, 8080))).to_string(),
+            "192.168.0.1:8080"
+        );
+        assert_eq!(
+            LogValue::from(SocketAddrV6::new(v6, 443, 0, 3)).to_string(),
+            "[fe80::1%3]:443"
+        );
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_serde_json_numbers() {
+        let number = |s: &str| s.parse::<serde_json::Number>().unwrap();
+
+        let value = LogValue::from(number("18446744073709551615"));
+        assert!(matches!(value.0, LogValueInner::U64(u64::MAX)));
+
+        let value = LogValue::from(number("-9223372036854775808"));
+        assert!(matches!(value.0, LogValueInner::I64(i64::MIN)));
+
+        let value = LogValue::from(number("1.5"));
+        assert!(matches!(value.0, LogValueInner::F64(1.5)));
+
+        // Integers outside of the native ranges are not rounded to floats.
+        let value = LogValue::from(number("340282366920938463463374607431768211456"));
+        assert_eq!(
+            value.as_str(),
+            Some("340282366920938463463374607431768211456")
+        );
+    }
+
+    #[test]
+    fn test_btree_map_flat_rendering_is_ordered() {
+        let map = BTreeMap::from([
+            ("b".to_owned(), 2),
+            ("a".to_owned(), 1),
+            ("c".to_owned(), 3),
+        ]);
+
+        let value = LogValue::from(map);
+        assert_eq!(value.to_string(), r#"{"a": 1, "b": 2, "c": 3}"#);
+    }
+
+    #[test]
+    fn test_as_str() {
+        assert_eq!(LogValue::from("static").as_str(), Some("static"));
+        assert_eq!(LogValue::from("owned".to_owned()).as_str(), Some("owned"));
+        assert_eq!(LogValue::static_str("const").as_str(), Some("const"));
+
+        assert_eq!(LogValue::from(42).as_str(), None);
+        assert_eq!(LogValue::from(1.5).as_str(), None);
+        assert_eq!(LogValue::display("text").as_str(), None);
+        assert_eq!(LogValue::serde(vec!["text"]).as_str(), None);
+    }
+
+    #[test]
+    fn test_serde_tagged() {
+        let user = BTreeMap::from([("id", 42)]);
+        let value = LogValue::serde_tagged("User", user);
+
+        assert_eq!(value.kind(), LogValueKind::Serde);
+        assert_eq!(
+            serde_json::to_value(value.as_log_value()).unwrap(),
+            serde_json::json!({ "__type": "User", "value": { "id": 42 } })
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! A read-only view of the records attached to a log record or a log context.
+
+use crate::{LogContext, LogValue, records::LogRecordRef};
+
+/// A read-only view of the default and scope records a log record is enriched with.
+///
+/// Records are listed in the order they are passed to the inner logger. If a key occurs
+/// more than once, [`get`](Self::get) returns the last occurrence, which is the value
+/// "last write wins" consumers observe.
+///
+/// A view can also be borrowed from a [`LogContext`] with [`LogContext::view`], e.g. to
+/// build a custom serializer. Inherited records of the context are listed before local
+/// ones, so local records shadow inherited ones. Within each group records are listed in
+/// insertion order, see [`LogRecords`](crate::LogRecords#ordering).
+///
+/// See [`ContextLogger::with_record_filter`](crate::ContextLogger::with_record_filter).
+#[derive(Debug, Clone, Default)]
+pub struct ContextView<'a> {
+    records: Vec<LogRecordRef<'a>>,
+}
+
+impl<'a> ContextView<'a> {
+    /// Creates a view over the given records.
+    pub(crate) const fn new(records: Vec<LogRecordRef<'a>>) -> Self {
+        Self { records }
+    }
+
+    /// Returns the value of the record with the given key, if any.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&'a LogValue> {
+        self.records
+            .iter()
+            .rev()
+            .find(|(record_key, _)| *record_key == key)
+            .map(|(_, value)| *value)
+    }
+
+    /// Returns `true` if the view contains a record with the given key.
+    #[must_use]
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns an iterator over the records of this view.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a LogValue)> + '_ {
+        self.records
+            .iter()
+            .map(|&(key, value)| (key.as_ref(), value))
+    }
+
+    /// Returns the number of records in this view.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns `true` if this view contains no records.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+impl<'a> From<&'a LogContext> for ContextView<'a> {
+    fn from(context: &'a LogContext) -> Self {
+        Self::new(
+            context
+                .inherited
+                .iter()
+                .chain(context.local.iter())
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::ContextView;
+    use crate::LogContext;
+
+    #[test]
+    fn test_view_of_context() {
+        let context = LogContext::new()
+            .with_local_record("user_id", 42)
+            .with_local_record("request_id", "req-1")
+            .with_inherited_record("service", "api")
+            .with_inherited_record("user_id", 7);
+        let view = context.view();
+
+        assert_eq!(view.len(), 4);
+        assert_eq!(view.get("service").unwrap().to_string(), "api");
+        // Local records shadow inherited ones.
+        assert_eq!(view.get("user_id").unwrap().to_string(), "42");
+        assert!(view.get("unknown").is_none());
+
+        // Inherited records come first, each group in insertion order.
+        let records = view
+            .iter()
+            .map(|(key, value)| (key, value.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            records,
+            [
+                ("service", "api".to_owned()),
+                ("user_id", "7".to_owned()),
+                ("user_id", "42".to_owned()),
+                ("request_id", "req-1".to_owned()),
+            ]
+        );
+
+        assert!(ContextView::from(&LogContext::new()).is_empty());
+    }
+}
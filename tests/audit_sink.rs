@@ -0,0 +1,53 @@
+use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+use log::{Level, Log as _};
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[test]
+fn test_audit_sink_routing() {
+    let main = CaptureLogger::default();
+    let audit = CaptureLogger::default();
+    let logger = ContextLogger::new(main.clone())
+        .with_default_record("service", "api")
+        .with_audit_sink("audit", audit.clone());
+
+    log_message(&logger, Level::Info, "regular");
+    LogContext::new()
+        .with_inherited_record("audit", true)
+        .in_scope(|| {
+            LogContext::new()
+                .with_local_record("user_id", 42)
+                .in_scope(|| log_message(&logger, Level::Warn, "permissions changed"));
+        });
+    LogContext::new()
+        .with_local_record("user_id", 7)
+        .in_scope(|| log_message(&logger, Level::Info, "regular in scope"));
+    logger.flush();
+
+    let main = main.records();
+    assert_eq!(main.len(), 3);
+
+    let audit = audit.records();
+    assert_eq!(audit.len(), 1);
+    assert_eq!(audit[0].message, "permissions changed");
+    assert_eq!(audit[0].level, Level::Warn);
+    assert_eq!(audit[0].get("service").unwrap(), "api");
+    assert_eq!(audit[0].get("user_id").unwrap(), 42);
+}
+
+#[test]
+fn test_audit_sink_default_record_marker() {
+    let main = CaptureLogger::default();
+    let audit = CaptureLogger::default();
+    let logger = ContextLogger::new(main.clone())
+        .with_default_record("audit", "all")
+        .with_audit_sink("audit", audit.clone());
+
+    log_message(&logger, Level::Info, "everything is audited");
+
+    assert_eq!(main.records().len(), 1);
+    assert_eq!(audit.records().len(), 1);
+}
@@ -0,0 +1,43 @@
+use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[test]
+fn test_breadcrumb_field() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_breadcrumb_field("scope");
+
+    log_message(&logger, Level::Info, "outside");
+    LogContext::named("request").in_scope(|| {
+        log_message(&logger, Level::Info, "request");
+        LogContext::new().in_scope(|| {
+            LogContext::named("db_query")
+                .with_local_record("table", "users")
+                .in_scope(|| log_message(&logger, Level::Info, "query"));
+        });
+    });
+    LogContext::new().in_scope(|| log_message(&logger, Level::Info, "unnamed"));
+
+    let records = capture.records();
+    let breadcrumbs = records
+        .iter()
+        .map(|record| {
+            let scope = record.get("scope").and_then(|value| value.as_str());
+            (record.message.as_str(), scope)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        breadcrumbs,
+        [
+            ("outside", None),
+            ("request", Some("request")),
+            ("query", Some("request>db_query")),
+            ("unnamed", None),
+        ]
+    );
+    assert_eq!(records[2].get("table").unwrap(), "users");
+}
@@ -0,0 +1,43 @@
+// Warning: Because each test initializes the logger, we need to split the
+// tests into separate files to avoid multiple initializations of the logger.
+
+use std::sync::mpsc;
+
+use context_logger::{ContextEnvelope, ContextLogger, LogContext, LogContextExt as _};
+use log::LevelFilter;
+use pretty_assertions::assert_eq;
+
+use crate::common::CaptureLogger;
+
+pub mod common;
+
+#[test]
+fn test_consumer_logs_carry_producer_context() {
+    let capture = CaptureLogger::default();
+    ContextLogger::new(capture.clone()).init(LevelFilter::Trace);
+
+    let (sender, receiver) = mpsc::channel();
+    let worker = std::thread::spawn(move || {
+        while let Ok((job, _guard)) = ContextEnvelope::recv_and_enter(&receiver) {
+            log::info!(job; "Processing job");
+        }
+        log::info!("Worker finished");
+    });
+
+    for request_id in ["req-1", "req-2"] {
+        LogContext::new()
+            .with_local_record("request_id", request_id)
+            .in_scope(|| ContextEnvelope::send_with_context(&sender, request_id.len()))
+            .unwrap();
+    }
+    drop(sender);
+    worker.join().unwrap();
+
+    let records = capture.records();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].get("request_id").unwrap(), "req-1");
+    assert_eq!(records[0].get("job").unwrap(), 5);
+    assert_eq!(records[1].get("request_id").unwrap(), "req-2");
+    // The producer's context is exited once the envelope guard is dropped.
+    assert_eq!(records[2].get("request_id"), None);
+}
@@ -0,0 +1,37 @@
+use context_logger::{ContextLogger, LogContext, LogScope};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[test]
+fn test_clear_current_thread_removes_leaked_scopes() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone());
+
+    // A task leaks its guards.
+    let outer = LogScope::enter(LogContext::new().with_inherited_record("task", 1));
+    std::mem::forget(LogScope::enter(
+        LogContext::new().with_local_record("user_id", 42),
+    ));
+    log_message(&logger, Level::Info, "leaking task");
+
+    // The worker thread cleans up at the task boundary.
+    assert_eq!(LogScope::clear_current_thread(), 2);
+    log_message(&logger, Level::Info, "next task");
+
+    // Guards of the removed scopes become no-ops and keep the scopes of the next task.
+    let next = LogScope::enter(LogContext::new().with_local_record("task", 2));
+    drop(outer);
+    log_message(&logger, Level::Info, "next task scope");
+    drop(next);
+    assert_eq!(LogScope::clear_current_thread(), 0);
+
+    let records = capture.records();
+    assert_eq!(records[0].get("task").unwrap(), 1);
+    assert_eq!(records[0].get("user_id").unwrap(), 42);
+    assert!(records[1].key_values.is_empty());
+    assert_eq!(records[2].get("task").unwrap(), 2);
+}
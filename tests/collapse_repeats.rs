@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use context_logger::{CollapseRepeats, ContextLogger, LogContext, LogContextExt as _};
+use log::{Level, Log as _};
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+fn messages(capture: &CaptureLogger) -> Vec<String> {
+    capture
+        .records()
+        .into_iter()
+        .map(|record| record.message)
+        .collect()
+}
+
+#[test]
+fn test_collapse_repeats() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_collapse_repeats(Duration::from_secs(60));
+
+    for _ in 0..10 {
+        log_message(&logger, Level::Warn, "retrying");
+    }
+    log_message(&logger, Level::Info, "connected");
+
+    assert_eq!(
+        messages(&capture),
+        ["retrying", "previous message repeated 9 times", "connected"]
+    );
+    assert_eq!(capture.records()[1].level, Level::Warn);
+}
+
+#[test]
+fn test_collapse_repeats_distinguishes_context() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_collapse_repeats(Duration::from_secs(60));
+
+    for attempt in [1, 1, 2] {
+        LogContext::new()
+            .with_local_record("attempt", attempt)
+            .in_scope(|| log_message(&logger, Level::Warn, "retrying"));
+    }
+
+    assert_eq!(
+        messages(&capture),
+        ["retrying", "previous message repeated 1 time", "retrying"]
+    );
+}
+
+#[test]
+fn test_collapse_repeats_periodic_summary() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_collapse_repeats(CollapseRepeats::new(Duration::from_secs(60)).with_summary_every(3));
+
+    for _ in 0..8 {
+        log_message(&logger, Level::Warn, "retrying");
+    }
+    log_message(&logger, Level::Info, "connected");
+
+    assert_eq!(
+        messages(&capture),
+        [
+            "retrying",
+            "previous message repeated 3 times",
+            "previous message repeated 3 times",
+            "previous message repeated 1 time",
+            "connected",
+        ]
+    );
+}
+
+#[test]
+fn test_collapse_repeats_window_expired() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_collapse_repeats(Duration::ZERO);
+
+    log_message(&logger, Level::Warn, "retrying");
+    std::thread::sleep(Duration::from_millis(5));
+    log_message(&logger, Level::Warn, "retrying");
+
+    assert_eq!(messages(&capture), ["retrying", "retrying"]);
+}
+
+#[test]
+fn test_flush_emits_pending_summary() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_collapse_repeats(Duration::from_secs(60));
+
+    for _ in 0..4 {
+        log_message(&logger, Level::Warn, "retrying");
+    }
+    logger.flush();
+    assert_eq!(
+        messages(&capture),
+        ["retrying", "previous message repeated 3 times"]
+    );
+
+    // Repeats after the flush are still collapsed.
+    log_message(&logger, Level::Warn, "retrying");
+    logger.flush();
+    logger.flush();
+    assert_eq!(
+        messages(&capture),
+        [
+            "retrying",
+            "previous message repeated 3 times",
+            "previous message repeated 1 time",
+        ]
+    );
+}
@@ -1,5 +1,7 @@
+use std::sync::{Arc, Mutex};
+
 use context_logger::ContextLogger;
-use log::{LevelFilter, Record, kv::Key};
+use log::{Level, LevelFilter, Log, Record, kv::Key};
 
 pub trait RecordExt {
     fn get_record(&self, key: &str) -> Option<serde_json::Value>;
@@ -27,3 +29,108 @@ where
     ));
     logger.init(level_filter);
 }
+
+/// A record received by the [`CaptureLogger`].
+#[derive(Debug, Clone)]
+pub struct CapturedRecord {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+    pub key_values: Vec<(String, serde_json::Value)>,
+}
+
+impl CapturedRecord {
+    /// Returns the first value with the given key.
+    #[must_use]
+    pub fn get_first(&self, key: &str) -> Option<&serde_json::Value> {
+        self.key_values
+            .iter()
+            .find_map(|(k, v)| (k == key).then_some(v))
+    }
+
+    /// Returns the last value with the given key.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&serde_json::Value> {
+        self.key_values
+            .iter()
+            .rev()
+            .find_map(|(k, v)| (k == key).then_some(v))
+    }
+}
+
+/// An inner logger that keeps every received record in memory.
+#[derive(Debug, Clone, Default)]
+pub struct CaptureLogger(Arc<Mutex<Vec<CapturedRecord>>>);
+
+impl CaptureLogger {
+    /// Returns a copy of all records received so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the records lock is poisoned.
+    #[must_use]
+    pub fn records(&self) -> Vec<CapturedRecord> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl Log for CaptureLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        struct Collect(Vec<(String, serde_json::Value)>);
+
+        impl<'kvs> log::kv::VisitSource<'kvs> for Collect {
+            fn visit_pair(
+                &mut self,
+                key: Key<'kvs>,
+                value: log::kv::Value<'kvs>,
+            ) -> Result<(), log::kv::Error> {
+                let value = serde_json::to_value(value).map_err(log::kv::Error::boxed)?;
+                self.0.push((key.to_string(), value));
+                Ok(())
+            }
+        }
+
+        let mut collect = Collect(Vec::new());
+        record.key_values().visit(&mut collect).unwrap();
+        self.0.lock().unwrap().push(CapturedRecord {
+            level: record.level(),
+            target: record.target().to_owned(),
+            message: record.args().to_string(),
+            key_values: collect.0,
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Passes a record with the given level and message directly to the logger.
+pub fn log_message(logger: &dyn Log, level: Level, message: &str) {
+    logger.log(
+        &Record::builder()
+            .level(level)
+            .target("test")
+            .args(format_args!("{message}"))
+            .build(),
+    );
+}
+
+/// Passes a record with the given level, message and key-values directly to the logger.
+pub fn log_message_with(
+    logger: &dyn Log,
+    level: Level,
+    message: &str,
+    key_values: &dyn log::kv::Source,
+) {
+    logger.log(
+        &Record::builder()
+            .level(level)
+            .target("test")
+            .args(format_args!("{message}"))
+            .key_values(key_values)
+            .build(),
+    );
+}
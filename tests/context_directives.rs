@@ -0,0 +1,52 @@
+use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+fn log_as_user(logger: &ContextLogger, user_id: u64, level: Level, message: &str) {
+    LogContext::new()
+        .with_local_record("user_id", user_id)
+        .in_scope(|| log_message(logger, level, message));
+}
+
+fn messages(capture: &CaptureLogger) -> Vec<String> {
+    capture
+        .records()
+        .into_iter()
+        .map(|record| record.message)
+        .collect()
+}
+
+#[test]
+fn test_directive_enables_matching_context() {
+    let capture = CaptureLogger::default();
+    let logger =
+        ContextLogger::new(capture.clone()).with_context_directives("info,user_id=12345:debug");
+
+    log_as_user(&logger, 12345, Level::Debug, "matching debug");
+    log_as_user(&logger, 12345, Level::Trace, "matching trace");
+    log_as_user(&logger, 1, Level::Debug, "other debug");
+    log_as_user(&logger, 1, Level::Info, "other info");
+    log_message(&logger, Level::Debug, "no context debug");
+    log_message(&logger, Level::Warn, "no context warn");
+
+    assert_eq!(
+        messages(&capture),
+        ["matching debug", "other info", "no context warn"]
+    );
+}
+
+#[test]
+fn test_directive_matches_default_records() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_default_record("tenant", "acme")
+        .with_context_directives("warn,tenant=acme");
+
+    log_message(&logger, Level::Trace, "default record trace");
+
+    assert_eq!(messages(&capture), ["default record trace"]);
+}
@@ -0,0 +1,83 @@
+use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+use log::{Level, Log as _, Record};
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[test]
+fn test_context_enabled_by_default() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_default_record("service", "api");
+
+    LogContext::new()
+        .with_local_record("user_id", 42)
+        .in_scope(|| log_message(&logger, Level::Info, "enriched"));
+
+    let records = capture.records();
+    assert_eq!(records[0].get("service").unwrap(), "api");
+    assert_eq!(records[0].get("user_id").unwrap(), 42);
+}
+
+#[test]
+fn test_context_disabled_forwards_record_unchanged() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_default_record("service", "api")
+        .with_context_disabled(true);
+
+    LogContext::new()
+        .with_local_record("user_id", 42)
+        .in_scope(|| log_message(&logger, Level::Info, "pass-through"));
+
+    let records = capture.records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].message, "pass-through");
+    assert!(records[0].key_values.is_empty());
+}
+
+#[test]
+fn test_context_excluded_targets() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_default_record("service", "api")
+        .with_context_excluded_targets(["hyper", "noisy::io"]);
+
+    LogContext::new()
+        .with_local_record("user_id", 42)
+        .in_scope(|| {
+            for target in [
+                "hyper",
+                "hyper::proto::h1",
+                "noisy::io::read",
+                "noisy",
+                "app",
+            ] {
+                logger.log(
+                    &Record::builder()
+                        .level(Level::Info)
+                        .target(target)
+                        .args(format_args!("message"))
+                        .build(),
+                );
+            }
+        });
+
+    let records = capture.records();
+    let enriched = records
+        .iter()
+        .map(|record| (record.target.as_str(), !record.key_values.is_empty()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        enriched,
+        [
+            ("hyper", false),
+            ("hyper::proto::h1", false),
+            ("noisy::io::read", false),
+            ("noisy", true),
+            ("app", true),
+        ]
+    );
+    assert_eq!(records[4].get("user_id").unwrap(), 42);
+}
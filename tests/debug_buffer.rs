@@ -0,0 +1,82 @@
+// Warning: Because each test initializes the logger, we need to split the
+// tests into separate files to avoid multiple initializations of the logger.
+
+use context_logger::{
+    ContextLogger, ContextView, LogContext, LogContextExt as _, LogValue, layer::OwnedRecord,
+};
+use log::{Level, LevelFilter};
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[test]
+fn test_buffered_records_are_flushed_on_error() {
+    let capture = CaptureLogger::default();
+    ContextLogger::new(capture.clone())
+        .with_debug_buffer(2, LevelFilter::Debug)
+        .with_record_filter(|record, _context| record.target() != "noisy")
+        // Replayed records are redacted like the records emitted right away.
+        .with_layer(|mut record: OwnedRecord, _context: &ContextView| {
+            for (key, value) in record.key_values_mut() {
+                if key == "password" {
+                    *value = LogValue::static_str("***");
+                }
+            }
+            Some(record)
+        })
+        .init(LevelFilter::Info);
+    assert_eq!(log::max_level(), LevelFilter::Debug);
+
+    LogContext::new()
+        .with_local_record("request_id", "req-1")
+        .in_scope(|| {
+            log::trace!("Below the buffer level");
+            log::debug!("Evicted from the buffer");
+            log::debug!(attempt = 1; "Connecting");
+            log::info!("Request received");
+            log::debug!(attempt = 2, password = "hunter2"; "Retrying");
+        });
+    assert_eq!(capture.records().len(), 1);
+
+    // Errors of another logger don't replay the records buffered by this one.
+    let other = ContextLogger::new(capture.clone()).with_debug_buffer(2, LevelFilter::Debug);
+    log_message(&other, Level::Error, "Other logger failed");
+    assert_eq!(capture.records().len(), 2);
+
+    log::error!("Request failed");
+    log::debug!(password = "hunter2"; "Buffered again");
+
+    let records = capture.records();
+    let messages = records
+        .iter()
+        .map(|record| record.message.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        [
+            "Request received",
+            "Other logger failed",
+            "Connecting",
+            "Retrying",
+            "Request failed"
+        ]
+    );
+
+    assert_eq!(records[2].level, Level::Debug);
+    assert_eq!(records[2].get("request_id").unwrap(), "req-1");
+    assert_eq!(records[2].get("attempt").unwrap(), 1);
+    assert_eq!(records[3].get("attempt").unwrap(), 2);
+    assert_eq!(records[3].get("password").unwrap(), "***");
+    assert_eq!(records[4].get("request_id"), None);
+
+    // Flushing emits the records buffered on the current thread, replayed records are
+    // filtered like any other record.
+    log::debug!(target: "noisy", "Filtered on replay");
+    log::logger().flush();
+    let records = capture.records();
+    assert_eq!(records.len(), 6);
+    assert_eq!(records[5].message, "Buffered again");
+    assert_eq!(records[5].get("password").unwrap(), "***");
+}
@@ -0,0 +1,71 @@
+use context_logger::{
+    ContextLogger, DefaultOverridePolicy, Inheritance, LogContext, LogContextExt as _,
+};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+fn log_with_colliding_keys(logger: &ContextLogger) {
+    LogContext::new()
+        .with_local_record("component", "worker")
+        .in_scope(|| {
+            LogContext::new()
+                .with_inherited_record("region", "eu")
+                .in_scope(|| log_message(logger, Level::Info, "nested"));
+            log_message(logger, Level::Info, "outer");
+        });
+    log_message(logger, Level::Info, "no scope");
+}
+
+fn logger(capture: &CaptureLogger, policy: DefaultOverridePolicy) -> ContextLogger {
+    ContextLogger::new(capture.clone())
+        .with_default_record("component", "api")
+        .with_default_record("region", "us")
+        .with_default_override_policy(policy)
+}
+
+#[test]
+fn test_context_wins_over_default() {
+    let capture = CaptureLogger::default();
+    log_with_colliding_keys(&logger(&capture, DefaultOverridePolicy::ContextWins));
+
+    let records = capture.records();
+    // The local `component` record is shadowed in the nested scope.
+    assert_eq!(records[0].get_first("component").unwrap(), "api");
+    assert_eq!(records[0].get_first("region").unwrap(), "eu");
+    assert_eq!(records[0].key_values.len(), 2);
+
+    assert_eq!(records[1].get_first("component").unwrap(), "worker");
+    assert_eq!(records[1].get_first("region").unwrap(), "us");
+    assert_eq!(records[1].key_values.len(), 2);
+
+    assert_eq!(records[2].get_first("component").unwrap(), "api");
+    assert_eq!(records[2].key_values.len(), 2);
+}
+
+#[test]
+fn test_context_wins_over_default_with_merge() {
+    let capture = CaptureLogger::default();
+    log_with_colliding_keys(
+        &logger(&capture, DefaultOverridePolicy::ContextWins).with_inheritance(Inheritance::Merge),
+    );
+
+    let records = capture.records();
+    assert_eq!(records[0].get_first("component").unwrap(), "worker");
+    assert_eq!(records[0].get_first("region").unwrap(), "eu");
+    assert_eq!(records[0].key_values.len(), 2);
+}
+
+#[test]
+fn test_keep_both_by_default() {
+    let capture = CaptureLogger::default();
+    log_with_colliding_keys(&logger(&capture, DefaultOverridePolicy::default()));
+
+    let records = capture.records();
+    assert_eq!(records[1].get_first("component").unwrap(), "api");
+    assert_eq!(records[1].get("component").unwrap(), "worker");
+    assert_eq!(records[1].key_values.len(), 3);
+}
@@ -0,0 +1,66 @@
+use std::sync::{Arc, Mutex};
+
+use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+use log::{Level, Log, Metadata, Record};
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+/// An inner logger that accepts only records at the `Info` level or above.
+struct InfoLogger(CaptureLogger);
+
+impl Log for InfoLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Info
+    }
+
+    fn log(&self, record: &Record) {
+        self.0.log(record);
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn test_dropped_record_observer() {
+    let capture = CaptureLogger::default();
+    let observed = Arc::new(Mutex::new(Vec::new()));
+    let logger = ContextLogger::new(InfoLogger(capture.clone())).with_dropped_record_observer({
+        let observed = observed.clone();
+        move |record, context| {
+            let user_id = context.and_then(|context| {
+                context
+                    .local
+                    .iter()
+                    .find_map(|(key, value)| (key == "user_id").then(|| value.to_string()))
+            });
+            observed
+                .lock()
+                .unwrap()
+                .push((record.level(), record.args().to_string(), user_id));
+        }
+    });
+
+    log_message(&logger, Level::Debug, "suppressed outside scope");
+    LogContext::new()
+        .with_local_record("user_id", 42)
+        .in_scope(|| {
+            log_message(&logger, Level::Info, "emitted");
+            log_message(&logger, Level::Trace, "suppressed in scope");
+        });
+
+    assert_eq!(capture.records().len(), 1);
+    assert_eq!(
+        *observed.lock().unwrap(),
+        [
+            (Level::Debug, "suppressed outside scope".to_owned(), None),
+            (
+                Level::Trace,
+                "suppressed in scope".to_owned(),
+                Some("42".to_owned())
+            ),
+        ]
+    );
+}
@@ -0,0 +1,46 @@
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use context_logger::{ContextLogger, LogContext, LogContextExt as _, fmt::env_logger_format};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::log_message;
+
+pub mod common;
+
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_env_logger_format_renders_context() {
+    let buffer = Buffer::default();
+    let env_logger = env_logger::builder()
+        .format(env_logger_format)
+        .target(env_logger::Target::Pipe(Box::new(buffer.clone())))
+        .filter_level(log::LevelFilter::Trace)
+        .build();
+    let logger = ContextLogger::new(env_logger).with_default_record("service", "api");
+
+    LogContext::new()
+        .with_local_record("request_id", 42)
+        .in_scope(|| log_message(&logger, Level::Info, "Handling request"));
+
+    let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    assert_eq!(
+        output,
+        "[INFO test] Handling request service=api request_id=42\n"
+    );
+}
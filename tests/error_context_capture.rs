@@ -0,0 +1,117 @@
+use std::sync::{Arc, Mutex};
+
+use context_logger::{ContextLogger, LogContext, LogContextExt as _, LogScope};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[test]
+fn test_error_context_capture() {
+    let captured = Arc::new(Mutex::new(Vec::new()));
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_error_context_capture({
+        let captured = captured.clone();
+        move |record, context| {
+            captured
+                .lock()
+                .unwrap()
+                .push((record.level(), record.args().to_string(), context));
+        }
+    });
+
+    LogContext::new()
+        .with_inherited_record("request_id", "req-1")
+        .in_scope(|| {
+            LogContext::new()
+                .with_local_record("attempt", 3)
+                .in_scope(|| {
+                    log_message(&logger, Level::Info, "retrying");
+                    log_message(&logger, Level::Error, "request failed");
+                });
+            log_message(&logger, Level::Warn, "slow request");
+        });
+    log_message(&logger, Level::Error, "outside");
+
+    // The snapshots are owned and can be inspected on another thread.
+    let captured = std::mem::take(&mut *captured.lock().unwrap());
+    let reports = std::thread::spawn(move || {
+        captured
+            .into_iter()
+            .map(|(level, message, context)| {
+                let frames = context
+                    .frames()
+                    .iter()
+                    .map(|frame| {
+                        let mut keys = frame
+                            .inherited
+                            .iter()
+                            .chain(frame.local.iter())
+                            .map(|(key, value)| format!("{key}={value}"))
+                            .collect::<Vec<_>>();
+                        keys.sort();
+                        keys
+                    })
+                    .collect::<Vec<_>>();
+                (level, message, frames)
+            })
+            .collect::<Vec<_>>()
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(
+        reports,
+        [
+            (
+                Level::Error,
+                "request failed".to_owned(),
+                vec![
+                    vec!["request_id=req-1".to_owned()],
+                    vec!["attempt=3".to_owned(), "request_id=req-1".to_owned()],
+                ]
+            ),
+            (
+                Level::Warn,
+                "slow request".to_owned(),
+                vec![vec!["request_id=req-1".to_owned()]]
+            ),
+            (Level::Error, "outside".to_owned(), vec![]),
+        ]
+    );
+    assert_eq!(capture.records().len(), 4);
+}
+
+#[test]
+fn test_error_context_capture_without_context() {
+    let captured = Arc::new(Mutex::new(Vec::new()));
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_context_excluded_targets(["test"])
+        .with_error_context_capture({
+            let captured = captured.clone();
+            move |_record, context| {
+                // The scope stack is not borrowed while the function runs.
+                LogScope::add_record("reported", true);
+                captured.lock().unwrap().push(context.frames().len());
+            }
+        });
+
+    LogContext::new()
+        .with_local_record("request_id", "req-1")
+        .in_scope(|| {
+            log_message(&logger, Level::Error, "excluded");
+            assert!(
+                LogScope::current_context()
+                    .local
+                    .iter()
+                    .any(|(key, _)| key == "reported")
+            );
+        });
+
+    // Context processing is disabled for the target, so the snapshot is empty.
+    assert_eq!(*captured.lock().unwrap(), [0]);
+    assert!(capture.records()[0].key_values.is_empty());
+}
@@ -0,0 +1,72 @@
+use context_logger::{
+    ContextLogger, ContextView, ErrorCounter, LogContext, LogContextExt as _, layer::OwnedRecord,
+};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+fn log_as_tenant(logger: &ContextLogger, tenant_id: &'static str, level: Level) {
+    LogContext::new()
+        .with_local_record("tenant_id", tenant_id)
+        .in_scope(|| log_message(logger, level, "message"));
+}
+
+#[test]
+fn test_errors_counted_per_tenant() {
+    let counter = ErrorCounter::new("tenant_id", 16);
+    let logger = ContextLogger::new(CaptureLogger::default()).with_error_counter(counter.clone());
+
+    log_as_tenant(&logger, "acme", Level::Error);
+    log_as_tenant(&logger, "acme", Level::Warn);
+    log_as_tenant(&logger, "acme", Level::Info);
+    log_as_tenant(&logger, "globex", Level::Error);
+    log_message(&logger, Level::Error, "no tenant");
+
+    assert_eq!(counter.get("acme"), 2);
+    assert_eq!(counter.get("globex"), 1);
+    assert_eq!(counter.get("initech"), 0);
+    assert_eq!(counter.counts().len(), 2);
+    assert_eq!(counter.overflow(), 0);
+}
+
+#[test]
+fn test_error_counter_cardinality_is_bounded() {
+    let counter = ErrorCounter::new("tenant_id", 1);
+    let logger = ContextLogger::new(CaptureLogger::default()).with_error_counter(counter.clone());
+
+    log_as_tenant(&logger, "acme", Level::Error);
+    log_as_tenant(&logger, "globex", Level::Error);
+    log_as_tenant(&logger, "initech", Level::Error);
+    log_as_tenant(&logger, "acme", Level::Error);
+
+    assert_eq!(counter.counts().len(), 1);
+    assert_eq!(counter.get("acme"), 2);
+    assert_eq!(counter.overflow(), 2);
+}
+
+#[test]
+fn test_dropped_errors_are_not_counted() {
+    let counter = ErrorCounter::new("tenant_id", 16);
+    let logger = ContextLogger::new(CaptureLogger::default())
+        .with_error_counter(counter.clone())
+        .with_record_filter(|_record, context| {
+            context
+                .get("tenant_id")
+                .is_none_or(|tenant_id| tenant_id.to_string() != "globex")
+        })
+        .with_layer(|record: OwnedRecord, context: &ContextView| {
+            let tenant_id = context.get("tenant_id")?.to_string();
+            (tenant_id != "initech").then_some(record)
+        });
+
+    log_as_tenant(&logger, "acme", Level::Error);
+    log_as_tenant(&logger, "globex", Level::Error);
+    log_as_tenant(&logger, "initech", Level::Error);
+
+    assert_eq!(counter.get("acme"), 1);
+    assert_eq!(counter.get("globex"), 0);
+    assert_eq!(counter.get("initech"), 0);
+}
@@ -0,0 +1,70 @@
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use context_logger::{ContextLogger, LogContext, LogContextExt as _, fallible::FallibleLog};
+use log::{Level, Metadata, Record};
+use pretty_assertions::assert_eq;
+
+use crate::common::log_message;
+
+pub mod common;
+
+/// Accepts a limited number of records and rejects the rest.
+struct BoundedLogger {
+    capacity: usize,
+    received: AtomicUsize,
+}
+
+impl FallibleLog for BoundedLogger {
+    type Error = &'static str;
+
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn try_log(&self, _record: &Record) -> Result<(), Self::Error> {
+        if self.received.fetch_add(1, Ordering::SeqCst) < self.capacity {
+            Ok(())
+        } else {
+            Err("buffer is full")
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn test_on_error_called_for_dropped_records() {
+    let failures = Arc::new(Mutex::new(Vec::new()));
+    let inner = BoundedLogger {
+        capacity: 1,
+        received: AtomicUsize::new(0),
+    };
+    let logger = ContextLogger::new(inner.on_error({
+        let failures = failures.clone();
+        move |record: &Record, error: &&'static str| {
+            let request_id = record
+                .key_values()
+                .get("request_id".into())
+                .map(|value| value.to_string());
+            failures
+                .lock()
+                .unwrap()
+                .push((record.args().to_string(), request_id, *error));
+        }
+    }));
+
+    LogContext::new()
+        .with_local_record("request_id", 7)
+        .in_scope(|| {
+            log_message(&logger, Level::Info, "delivered");
+            log_message(&logger, Level::Info, "dropped");
+        });
+
+    assert_eq!(
+        *failures.lock().unwrap(),
+        [("dropped".to_owned(), Some("7".to_owned()), "buffer is full")]
+    );
+}
@@ -0,0 +1,52 @@
+// Warning: Because each test initializes the logger, we need to split the
+// tests into separate files to avoid multiple initializations of the logger.
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use context_logger::ContextLogger;
+use log::LevelFilter;
+
+/// An inner logger that only counts flushes.
+#[derive(Clone, Default)]
+struct FlushCounter(Arc<AtomicUsize>);
+
+impl log::Log for FlushCounter {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, _record: &log::Record) {}
+
+    fn flush(&self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn test_flush_interval() {
+    let flushes = FlushCounter::default();
+    let logger = ContextLogger::new(flushes.clone()).with_flush_interval(Duration::from_millis(10));
+    let handle = logger.handle();
+    logger.init(LevelFilter::Info);
+
+    std::thread::sleep(Duration::from_millis(200));
+    assert!(flushes.0.load(Ordering::SeqCst) >= 3);
+
+    // Stopping flushes once more and joins the thread, so no flushes happen afterwards.
+    let before_stop = flushes.0.load(Ordering::SeqCst);
+    handle.stop_periodic_flush();
+    let after_stop = flushes.0.load(Ordering::SeqCst);
+    assert!(after_stop > before_stop);
+
+    std::thread::sleep(Duration::from_millis(50));
+    assert_eq!(flushes.0.load(Ordering::SeqCst), after_stop);
+    // Stopping again is a no-op.
+    handle.stop_periodic_flush();
+    assert_eq!(flushes.0.load(Ordering::SeqCst), after_stop);
+}
@@ -0,0 +1,39 @@
+use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[test]
+fn test_overlapping_keys_under_distinct_paths() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_default_record("service", "api")
+        .with_hierarchical_keys(true);
+
+    LogContext::new()
+        .with_local_record("op", "request")
+        .with_inherited_record("request_id", 42)
+        .in_scope(|| {
+            LogContext::new()
+                .with_local_record("op", "query")
+                .in_scope(|| log_message(&logger, Level::Info, "nested"));
+            log_message(&logger, Level::Info, "outer");
+        });
+    log_message(&logger, Level::Info, "no scope");
+
+    let records = capture.records();
+    assert_eq!(records[0].get("scope0.op").unwrap(), "request");
+    assert_eq!(records[0].get("scope1.op").unwrap(), "query");
+    assert_eq!(records[0].get("request_id").unwrap(), 42);
+    assert_eq!(records[0].get("service").unwrap(), "api");
+    assert_eq!(records[0].get("op"), None);
+    assert_eq!(records[0].key_values.len(), 4);
+
+    assert_eq!(records[1].get("scope0.op").unwrap(), "request");
+    assert_eq!(records[1].get("scope1.op"), None);
+
+    assert_eq!(records[2].key_values.len(), 1);
+}
@@ -0,0 +1,79 @@
+use context_logger::{ContextLogger, Inheritance, LogContext, LogContextExt as _};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+fn log_nested(logger: &ContextLogger) {
+    LogContext::new()
+        .with_local_record("request_id", 42)
+        .with_local_record("stage", "outer")
+        .with_inherited_record("service", "api")
+        .in_scope(|| {
+            LogContext::new()
+                .with_local_record("stage", "inner")
+                .in_scope(|| log_message(logger, Level::Info, "nested"));
+        });
+}
+
+#[test]
+fn test_shadow_inheritance() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_inheritance(Inheritance::Shadow);
+
+    log_nested(&logger);
+
+    let records = capture.records();
+    assert_eq!(records[0].get("service").unwrap(), "api");
+    assert_eq!(records[0].get("stage").unwrap(), "inner");
+    assert_eq!(records[0].get("request_id"), None);
+    assert_eq!(records[0].key_values.len(), 2);
+}
+
+#[test]
+fn test_merge_inheritance() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_inheritance(Inheritance::Merge);
+
+    log_nested(&logger);
+
+    let records = capture.records();
+    assert_eq!(records[0].get("service").unwrap(), "api");
+    assert_eq!(records[0].get("request_id").unwrap(), 42);
+    // The innermost value of a key wins.
+    assert_eq!(records[0].get("stage").unwrap(), "inner");
+}
+
+#[test]
+fn test_merge_inheritance_inner_inherited_wins() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_inheritance(Inheritance::Merge);
+
+    LogContext::new()
+        .with_local_record("user", "outer")
+        .in_scope(|| {
+            LogContext::new()
+                .with_inherited_record("user", "inner")
+                .in_scope(|| log_message(&logger, Level::Info, "nested"));
+        });
+
+    let records = capture.records();
+    assert_eq!(records[0].get("user").unwrap(), "inner");
+}
+
+#[test]
+fn test_merge_inheritance_outside_nested_scope() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_inheritance(Inheritance::Merge);
+
+    LogContext::new()
+        .with_local_record("request_id", 42)
+        .in_scope(|| log_message(&logger, Level::Info, "outer"));
+    log_message(&logger, Level::Info, "no scope");
+
+    let records = capture.records();
+    assert_eq!(records[0].get("request_id").unwrap(), 42);
+    assert!(records[1].key_values.is_empty());
+}
@@ -0,0 +1,39 @@
+use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[test]
+fn test_sampling_is_applied_per_value() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_keyed_sampling("endpoint", 0.1);
+
+    for _ in 0..100 {
+        LogContext::new()
+            .with_local_record("endpoint", "/hot")
+            .in_scope(|| log_message(&logger, Level::Info, "Request"));
+    }
+    for _ in 0..3 {
+        LogContext::new()
+            .with_local_record("endpoint", "/cold")
+            .in_scope(|| log_message(&logger, Level::Info, "Request"));
+    }
+    for _ in 0..5 {
+        log_message(&logger, Level::Info, "Without endpoint");
+    }
+
+    let count = |endpoint: Option<&str>| {
+        capture
+            .records()
+            .iter()
+            .filter(|record| record.get("endpoint").and_then(serde_json::Value::as_str) == endpoint)
+            .count()
+    };
+    assert_eq!(count(Some("/hot")), 10);
+    // The first record of a rarely seen value is always emitted.
+    assert_eq!(count(Some("/cold")), 1);
+    assert_eq!(count(None), 5);
+}
@@ -0,0 +1,110 @@
+use std::sync::{Arc, Mutex};
+
+use context_logger::{ContextLogger, LogContext, LogContextExt as _, kv::KvOrder};
+use log::{Level, Log, Metadata, Record};
+use pretty_assertions::assert_eq;
+
+use crate::common::CaptureLogger;
+
+pub mod common;
+
+fn log_with_duplicate_key(logger: &ContextLogger) {
+    let key_values = [("user_id", "from-source")];
+    LogContext::new()
+        .with_local_record("user_id", "from-context")
+        .in_scope(|| {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .target("test")
+                    .args(format_args!("duplicate key"))
+                    .key_values(&key_values)
+                    .build(),
+            );
+        });
+}
+
+#[test]
+fn test_context_first_order() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone());
+
+    log_with_duplicate_key(&logger);
+
+    let records = capture.records();
+    assert_eq!(records[0].get_first("user_id").unwrap(), "from-context");
+    assert_eq!(records[0].get("user_id").unwrap(), "from-source");
+}
+
+#[test]
+fn test_source_first_order() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_kv_order(KvOrder::SourceFirst);
+
+    log_with_duplicate_key(&logger);
+
+    let records = capture.records();
+    assert_eq!(records[0].get_first("user_id").unwrap(), "from-source");
+    assert_eq!(records[0].get("user_id").unwrap(), "from-context");
+}
+
+/// The looked up value and the total number of key-values of each record.
+type Lookup = (Option<String>, usize);
+
+/// Looks up a single key the way kv-aware formatters do, without visiting every pair.
+#[derive(Clone, Default)]
+struct LookupLogger(Arc<Mutex<Vec<Lookup>>>);
+
+impl Log for LookupLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        let key_values = record.key_values();
+        let value = key_values
+            .get("user_id".into())
+            .map(|value| value.to_string());
+        self.0.lock().unwrap().push((value, key_values.count()));
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn test_get_honors_kv_order() {
+    for (order, expected) in [
+        (KvOrder::ContextFirst, "from-source"),
+        (KvOrder::SourceFirst, "from-context"),
+    ] {
+        let lookup = LookupLogger::default();
+        let logger = ContextLogger::new(lookup.clone())
+            .with_default_record("user_id", "from-default")
+            .with_kv_order(order);
+
+        log_with_duplicate_key(&logger);
+
+        let lookups = lookup.0.lock().unwrap();
+        assert_eq!(lookups[0], (Some(expected.to_owned()), 3));
+    }
+}
+
+#[test]
+fn test_get_falls_back_to_default_records() {
+    let lookup = LookupLogger::default();
+    let logger = ContextLogger::new(lookup.clone()).with_default_record("user_id", "from-default");
+
+    LogContext::new()
+        .with_local_record("request_id", 1)
+        .in_scope(|| {
+            logger.log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("no source key"))
+                    .build(),
+            );
+        });
+
+    let lookups = lookup.0.lock().unwrap();
+    assert_eq!(lookups[0], (Some("from-default".to_owned()), 2));
+}
@@ -0,0 +1,100 @@
+// Warning: Because each test initializes the logger, we need to split the
+// tests into separate files to avoid multiple initializations of the logger.
+
+use context_logger::{
+    ContextLogger, ContextView, LogContext, LogContextExt as _, LogValue,
+    layer::{ContextLayer, OwnedRecord},
+};
+use log::{Level, LevelFilter};
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message_with};
+
+pub mod common;
+
+/// Masks the values of the given keys.
+struct Redact(&'static [&'static str]);
+
+impl ContextLayer for Redact {
+    fn process(&self, mut record: OwnedRecord, _context: &ContextView) -> Option<OwnedRecord> {
+        for (key, value) in record.key_values_mut() {
+            if self.0.contains(&key.as_ref()) {
+                *value = LogValue::static_str("***");
+            }
+        }
+        Some(record)
+    }
+}
+
+#[test]
+fn test_layers_are_applied_in_order() {
+    let capture = CaptureLogger::default();
+    ContextLogger::new(capture.clone())
+        .with_default_record("service", "api")
+        .with_layer(Redact(&["password", "token"]))
+        // Sees the values redacted by the previous layer, drops anonymous requests.
+        .with_layer(|mut record: OwnedRecord, context: &ContextView| {
+            context.get("user")?;
+            let token = record.get("token").map(ToString::to_string);
+            record.set_message(format!("{} (token={})", record.message(), token?));
+            Some(record)
+        })
+        .init(LevelFilter::Trace);
+
+    LogContext::new()
+        .with_local_record("user", "alice")
+        .with_local_record("password", "secret")
+        .in_scope(|| log::info!(token = "abc"; "Logged in"));
+    LogContext::new()
+        .with_local_record("password", "secret")
+        .in_scope(|| log::info!(token = "abc"; "Anonymous request"));
+
+    let records = capture.records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].message, "Logged in (token=***)");
+    assert_eq!(records[0].get("password").unwrap(), "***");
+    assert_eq!(records[0].get("token").unwrap(), "***");
+    assert_eq!(records[0].get("user").unwrap(), "alice");
+    assert_eq!(records[0].get("service").unwrap(), "api");
+}
+
+#[test]
+fn test_layers_process_records_outside_of_scopes() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_layer(Redact(&["password"]));
+    // Targets without context processing are passed through the layers as well.
+    let excluded = ContextLogger::new(capture.clone())
+        .with_context_excluded_targets(["test"])
+        .with_layer(Redact(&["password"]));
+
+    log_message_with(&logger, Level::Info, "Logged in", &[("password", "secret")]);
+    log_message_with(
+        &excluded,
+        Level::Info,
+        "Logged in",
+        &[("password", "secret")],
+    );
+
+    let records = capture.records();
+    assert_eq!(records.len(), 2);
+    for record in records {
+        assert_eq!(record.get("password").unwrap(), "***");
+    }
+}
+
+#[test]
+fn test_layers_keep_structured_values() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_layer(|record: OwnedRecord, _context: &ContextView| Some(record));
+
+    let user = serde_json::json!({"id": 42, "roles": ["admin"], "active": true});
+    log_message_with(
+        &logger,
+        Level::Info,
+        "Logged in",
+        &[("user", log::kv::Value::from_serde(&user))],
+    );
+
+    assert_eq!(capture.records()[0].get("user").unwrap(), &user);
+}
@@ -0,0 +1,56 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use context_logger::ContextLogger;
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+fn counting_logger(capture: &CaptureLogger, calls: &Arc<AtomicUsize>) -> ContextLogger {
+    let calls = calls.clone();
+    ContextLogger::new(capture.clone()).with_lazy_default_record("hostname", move || {
+        calls.fetch_add(1, Ordering::SeqCst);
+        "host-1"
+    })
+}
+
+#[test]
+fn test_lazy_default_not_computed_without_records() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let logger = counting_logger(&CaptureLogger::default(), &calls);
+
+    drop(logger);
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_lazy_default_computed_once() {
+    let capture = CaptureLogger::default();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let logger = Arc::new(counting_logger(&capture, &calls));
+
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            let logger = logger.clone();
+            scope.spawn(move || {
+                for _ in 0..10 {
+                    log_message(&*logger, Level::Info, "message");
+                }
+            });
+        }
+    });
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    let records = capture.records();
+    assert_eq!(records.len(), 40);
+    assert!(
+        records
+            .iter()
+            .all(|record| record.get("hostname").unwrap() == "host-1")
+    );
+}
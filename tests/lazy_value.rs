@@ -0,0 +1,33 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+use context_logger::{ContextLogger, LogContext, LogContextExt as _, LogValue};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[test]
+fn test_lazy_value_reflects_state_changes() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone());
+    let depth = Arc::new(AtomicU64::new(1));
+
+    let context = LogContext::new().with_local_record("queue_depth", {
+        let depth = depth.clone();
+        LogValue::lazy(move || depth.load(Ordering::SeqCst))
+    });
+    context.in_scope(|| {
+        log_message(&logger, Level::Info, "first");
+        depth.store(5, Ordering::SeqCst);
+        log_message(&logger, Level::Info, "second");
+    });
+
+    let records = capture.records();
+    assert_eq!(records[0].get("queue_depth").unwrap(), 1);
+    assert_eq!(records[1].get("queue_depth").unwrap(), 5);
+}
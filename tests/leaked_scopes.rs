@@ -0,0 +1,57 @@
+// Warning: The leaked scope policy is process-wide, so these tests are kept in a
+// separate file to avoid affecting scope groups of the other tests.
+
+use context_logger::{
+    CapturedContext, LeakedScopePolicy, LogContext, LogContextExt as _, LogScope,
+};
+use pretty_assertions::assert_eq;
+
+fn task_context() -> CapturedContext {
+    LogContext::new()
+        .with_local_record("task_id", 1)
+        .in_scope(CapturedContext::with_frames)
+}
+
+fn leak_scope() {
+    std::mem::forget(LogScope::enter(
+        LogContext::new().with_local_record("stale", true),
+    ));
+}
+
+#[test]
+fn test_leaked_scope_policy() {
+    assert_eq!(LogScope::leaked_scope_policy(), LeakedScopePolicy::Keep);
+
+    // By default, the leaked scope outlives the task.
+    let guard = task_context().enter_all();
+    leak_scope();
+    drop(guard);
+    assert_eq!(LogScope::depth(), 1);
+    assert!(
+        LogScope::current_context()
+            .local
+            .iter()
+            .any(|(key, _)| key == "stale")
+    );
+    assert_eq!(LogScope::clear_current_thread(), 1);
+
+    LogScope::set_leaked_scope_policy(LeakedScopePolicy::Clear);
+    let guard = task_context().enter_all();
+    leak_scope();
+    drop(guard);
+    assert_eq!(LogScope::depth(), 0);
+
+    // Scopes entered and exited properly are not affected.
+    let guard = task_context().enter_all();
+    LogContext::new().in_scope(|| assert_eq!(LogScope::depth(), 2));
+    drop(guard);
+    assert_eq!(LogScope::depth(), 0);
+
+    LogScope::set_leaked_scope_policy(LeakedScopePolicy::Panic);
+    let result = std::panic::catch_unwind(|| {
+        let _guard = task_context().enter_all();
+        leak_scope();
+    });
+    assert!(result.is_err());
+    assert_eq!(LogScope::depth(), 0);
+}
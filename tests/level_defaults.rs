@@ -0,0 +1,43 @@
+// Warning: Because each test initializes the logger, we need to split the
+// tests into separate files to avoid multiple initializations of the logger.
+
+use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+use log::{Level, LevelFilter};
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+use crate::common::CaptureLogger;
+
+pub mod common;
+
+#[test]
+fn test_level_defaults_are_added_to_matching_levels_only() {
+    let capture = CaptureLogger::default();
+    ContextLogger::new(capture.clone())
+        .with_default_record("service", "api")
+        .with_level_default(Level::Error, "alert", true)
+        .with_level_default(Level::Error, "team", "oncall")
+        .with_level_default(Level::Warn, "review", true)
+        .init(LevelFilter::Trace);
+
+    log::info!("Request processed");
+    log::warn!("Request is slow");
+    LogContext::new()
+        .with_local_record("request_id", "req-1")
+        .in_scope(|| log::error!("Request failed"));
+
+    let records = capture.records();
+    assert_eq!(
+        records[0].key_values,
+        vec![("service".to_owned(), json!("api"))]
+    );
+
+    assert_eq!(records[1].get("review").unwrap(), true);
+    assert_eq!(records[1].get("alert"), None);
+
+    assert_eq!(records[2].get("service").unwrap(), "api");
+    assert_eq!(records[2].get("alert").unwrap(), true);
+    assert_eq!(records[2].get("team").unwrap(), "oncall");
+    assert_eq!(records[2].get("review"), None);
+    assert_eq!(records[2].get("request_id").unwrap(), "req-1");
+}
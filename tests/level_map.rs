@@ -0,0 +1,45 @@
+use context_logger::ContextLogger;
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[test]
+fn test_syslog_severity_is_added() {
+    let capture = CaptureLogger::default();
+    let logger =
+        ContextLogger::new(capture.clone()).with_level_map(context_logger::syslog_severity);
+
+    for level in [
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ] {
+        log_message(&logger, level, "Message");
+    }
+
+    let severities = capture
+        .records()
+        .iter()
+        .map(|record| {
+            (
+                record.level,
+                record.get("severity").unwrap().as_i64().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        severities,
+        [
+            (Level::Error, 3),
+            (Level::Warn, 4),
+            (Level::Info, 6),
+            (Level::Debug, 7),
+            (Level::Trace, 7),
+        ]
+    );
+}
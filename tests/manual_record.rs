@@ -0,0 +1,46 @@
+// Warning: Because each test initializes the logger, we need to split the
+// tests into separate files to avoid multiple initializations of the logger.
+
+use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+use log::{Level, LevelFilter, Record};
+use pretty_assertions::assert_eq;
+
+use crate::common::CaptureLogger;
+
+pub mod common;
+
+#[test]
+fn test_manually_built_records_are_enriched() {
+    let capture = CaptureLogger::default();
+    ContextLogger::new(capture.clone()).init(LevelFilter::Trace);
+
+    let key_values = [("attempt", 3)];
+    LogContext::new()
+        .with_local_record("request_id", 42)
+        .in_scope(|| {
+            // A bare record without key-values, file or module path.
+            log::logger().log(
+                &Record::builder()
+                    .level(Level::Info)
+                    .args(format_args!("bare record"))
+                    .build(),
+            );
+            log::logger().log(
+                &Record::builder()
+                    .level(Level::Warn)
+                    .target("manual")
+                    .args(format_args!("record with key-values"))
+                    .key_values(&key_values)
+                    .build(),
+            );
+        });
+
+    let records = capture.records();
+    assert_eq!(records[0].message, "bare record");
+    assert_eq!(records[0].get("request_id").unwrap(), 42);
+    assert_eq!(records[0].key_values.len(), 1);
+
+    assert_eq!(records[1].target, "manual");
+    assert_eq!(records[1].get("request_id").unwrap(), 42);
+    assert_eq!(records[1].get("attempt").unwrap(), 3);
+}
@@ -0,0 +1,37 @@
+use std::collections::{BTreeMap, HashMap};
+
+use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+use log::Level;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[test]
+fn test_map_records_are_nested_objects() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone());
+
+    let attributes = HashMap::from([
+        ("region".to_owned(), json!("eu-west-1")),
+        ("replicas".to_owned(), json!(3)),
+    ]);
+    let limits = BTreeMap::from([("cpu".to_owned(), 2), ("memory".to_owned(), 512)]);
+
+    LogContext::new()
+        .with_local_record("attributes", attributes)
+        .with_local_record("limits", limits)
+        .in_scope(|| log_message(&logger, Level::Info, "Deployed"));
+
+    let records = capture.records();
+    assert_eq!(
+        records[0].get("attributes").unwrap(),
+        &json!({ "region": "eu-west-1", "replicas": 3 })
+    );
+    assert_eq!(
+        records[0].get("limits").unwrap(),
+        &json!({ "cpu": 2, "memory": 512 })
+    );
+}
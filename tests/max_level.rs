@@ -0,0 +1,29 @@
+use context_logger::ContextLogger;
+use log::LevelFilter;
+use pretty_assertions::assert_eq;
+
+use crate::common::CaptureLogger;
+
+pub mod common;
+
+#[test]
+fn test_max_level_matches_init() {
+    let logger = ContextLogger::new(CaptureLogger::default());
+    let handle = logger.handle();
+    assert_eq!(handle.max_level(), None);
+
+    logger.init(LevelFilter::Debug);
+    assert_eq!(handle.max_level(), Some(LevelFilter::Debug));
+    assert_eq!(context_logger::max_level(), LevelFilter::Debug);
+
+    // A failed initialization does not change the reported level.
+    let other = ContextLogger::new(CaptureLogger::default());
+    let other_handle = other.handle();
+    assert!(other.try_init(LevelFilter::Trace).is_err());
+    assert_eq!(other_handle.max_level(), None);
+    assert_eq!(handle.max_level(), Some(LevelFilter::Debug));
+    assert_eq!(context_logger::max_level(), LevelFilter::Debug);
+
+    log::set_max_level(LevelFilter::Warn);
+    assert_eq!(context_logger::max_level(), LevelFilter::Warn);
+}
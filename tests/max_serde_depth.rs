@@ -0,0 +1,70 @@
+use context_logger::{ContextLogger, LogContext, LogContextExt as _, LogValue};
+use log::Level;
+use pretty_assertions::assert_eq;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+/// A recursive structure, e.g. a chain of wrapped errors.
+#[derive(Serialize)]
+struct Node {
+    name: String,
+    child: Option<Box<Self>>,
+}
+
+fn chain(len: usize) -> Node {
+    (1..len).fold(
+        Node {
+            name: format!("node{len}"),
+            child: None,
+        },
+        |child, index| Node {
+            name: format!("node{}", len - index),
+            child: Some(Box::new(child)),
+        },
+    )
+}
+
+#[test]
+fn test_max_serde_depth() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_default_record("shallow", LogValue::serde(chain(1)))
+        .with_max_serde_depth(2);
+
+    LogContext::new()
+        .with_local_record("chain", LogValue::serde(chain(100)))
+        .with_local_record("list", LogValue::serde(vec![[1, 2], [3, 4]]))
+        .with_local_record("text", "not affected")
+        .in_scope(|| log_message(&logger, Level::Info, "Nested"));
+
+    let record = &capture.records()[0];
+    assert_eq!(
+        record.get("chain").unwrap(),
+        &json!({"name": "node1", "child": {"name": "node2", "child": "..."}})
+    );
+    assert_eq!(record.get("list").unwrap(), &json!([[1, 2], [3, 4]]));
+    assert_eq!(
+        record.get("shallow").unwrap(),
+        &json!({"name": "node1", "child": null})
+    );
+    assert_eq!(record.get("text").unwrap(), "not affected");
+}
+
+#[test]
+fn test_serde_depth_is_unlimited_by_default() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone());
+
+    LogContext::new()
+        .with_local_record("chain", LogValue::serde(chain(3)))
+        .in_scope(|| log_message(&logger, Level::Info, "Nested"));
+
+    assert_eq!(
+        capture.records()[0].get("chain").unwrap(),
+        &json!({"name": "node1", "child": {"name": "node2", "child": {"name": "node3", "child": null}}})
+    );
+}
@@ -0,0 +1,52 @@
+use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+use log::Level;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+use crate::common::{CaptureLogger, log_message, log_message_with};
+
+pub mod common;
+
+#[test]
+fn test_message_is_added_as_field() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_message_field("message");
+
+    let attempt = 3;
+    log_message(&logger, Level::Info, "Connection established");
+    LogContext::new()
+        .with_local_record("request_id", "req-1")
+        .in_scope(|| {
+            log_message(
+                &logger,
+                Level::Warn,
+                &format!("Retrying request, attempt {attempt}"),
+            );
+        });
+    log_message_with(
+        &logger,
+        Level::Error,
+        "Request failed",
+        &[("message", "custom")],
+    );
+
+    let records = capture.records();
+    assert_eq!(
+        records[0].get("message"),
+        Some(&json!("Connection established"))
+    );
+    assert_eq!(
+        records[1].get("message"),
+        Some(&json!("Retrying request, attempt 3"))
+    );
+    assert_eq!(records[1].get("request_id"), Some(&json!("req-1")));
+    // An explicit field is not duplicated.
+    let messages = records[2]
+        .key_values
+        .iter()
+        .filter(|(key, _)| key == "message")
+        .count();
+    assert_eq!(messages, 1);
+    assert_eq!(records[2].get("message"), Some(&json!("custom")));
+    assert_eq!(records[2].message, "Request failed");
+}
@@ -0,0 +1,48 @@
+use context_logger::{ContextLogger, LogContext, LogContextExt as _, LogValue, NullRepr};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, CapturedRecord, log_message};
+
+pub mod common;
+
+fn log_with_nulls(repr: NullRepr) -> CapturedRecord {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_default_record("region", LogValue::null())
+        .with_null_repr(repr);
+
+    LogContext::new()
+        .with_local_record("user_id", None::<u64>)
+        .with_local_record("request_id", 42)
+        .in_scope(|| log_message(&logger, Level::Info, "message"));
+
+    capture.records().remove(0)
+}
+
+#[test]
+fn test_null_repr_omit() {
+    let record = log_with_nulls(NullRepr::Omit);
+
+    assert_eq!(record.get("region"), None);
+    assert_eq!(record.get("user_id"), None);
+    assert_eq!(record.get("request_id").unwrap(), 42);
+}
+
+#[test]
+fn test_null_repr_null() {
+    let record = log_with_nulls(NullRepr::Null);
+
+    assert_eq!(record.get("region").unwrap(), &serde_json::Value::Null);
+    assert_eq!(record.get("user_id").unwrap(), &serde_json::Value::Null);
+    assert_eq!(record.get("request_id").unwrap(), 42);
+}
+
+#[test]
+fn test_null_repr_empty_string() {
+    let record = log_with_nulls(NullRepr::EmptyString);
+
+    assert_eq!(record.get("region").unwrap(), "");
+    assert_eq!(record.get("user_id").unwrap(), "");
+    assert_eq!(record.get("request_id").unwrap(), 42);
+}
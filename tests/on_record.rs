@@ -0,0 +1,127 @@
+use std::sync::{Arc, Mutex};
+
+use context_logger::{
+    ContextLogger, ContextView, LogContext, LogContextExt as _, LogScope, layer::OwnedRecord,
+};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[test]
+fn test_on_record_observes_records_with_context() {
+    let observed = Arc::new(Mutex::new(Vec::new()));
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_default_record("service", "api")
+        .with_record_filter(|record, _| record.level() <= Level::Info)
+        .with_on_record({
+            let observed = observed.clone();
+            move |record, context| {
+                let mut keys = context
+                    .iter()
+                    .map(|(key, _)| key.to_owned())
+                    .collect::<Vec<_>>();
+                keys.sort();
+                observed
+                    .lock()
+                    .unwrap()
+                    .push((record.args().to_string(), keys));
+            }
+        });
+
+    log_message(&logger, Level::Info, "outside");
+    LogContext::new()
+        .with_local_record("request_id", "req-1")
+        .in_scope(|| {
+            log_message(&logger, Level::Warn, "inside");
+            log_message(&logger, Level::Debug, "filtered");
+        });
+
+    // The observer sees exactly the records forwarded to the inner logger.
+    assert_eq!(
+        *observed.lock().unwrap(),
+        [
+            ("outside".to_owned(), vec!["service".to_owned()]),
+            (
+                "inside".to_owned(),
+                vec!["request_id".to_owned(), "service".to_owned()]
+            ),
+        ]
+    );
+    assert_eq!(capture.records().len(), 2);
+}
+
+#[test]
+fn test_on_record_without_context() {
+    let observed = Arc::new(Mutex::new(Vec::new()));
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_context_excluded_targets(["test"])
+        .with_on_record({
+            let observed = observed.clone();
+            move |record, context| {
+                observed
+                    .lock()
+                    .unwrap()
+                    .push((record.args().to_string(), context.len()));
+            }
+        });
+
+    LogContext::new()
+        .with_local_record("request_id", "req-1")
+        .in_scope(|| log_message(&logger, Level::Info, "excluded"));
+
+    assert_eq!(*observed.lock().unwrap(), [("excluded".to_owned(), 0)]);
+    assert_eq!(capture.records().len(), 1);
+}
+
+#[test]
+fn test_on_record_may_use_scope_api() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_on_record(|_record, context| {
+        // The scope stack is not borrowed while the observer runs.
+        let count = context.len();
+        LogScope::add_record("observed", count);
+        LogContext::new().in_scope(|| LogScope::add_record("nested", true));
+    });
+
+    LogContext::new()
+        .with_local_record("request_id", "req-1")
+        .in_scope(|| {
+            log_message(&logger, Level::Info, "first");
+            log_message(&logger, Level::Info, "second");
+        });
+
+    let records = capture.records();
+    assert_eq!(records[0].get("observed"), None);
+    assert_eq!(records[1].get("observed").unwrap(), 1);
+    assert_eq!(records[1].get("nested"), None);
+}
+
+#[test]
+fn test_on_record_skips_records_dropped_by_layers() {
+    let observed = Arc::new(Mutex::new(Vec::new()));
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_layer(|record: OwnedRecord, _context: &ContextView| {
+            (record.message() != "dropped").then_some(record)
+        })
+        .with_on_record({
+            let observed = observed.clone();
+            move |record, _context| observed.lock().unwrap().push(record.args().to_string())
+        });
+
+    log_message(&logger, Level::Info, "dropped");
+    LogContext::new()
+        .with_local_record("request_id", "req-1")
+        .in_scope(|| {
+            log_message(&logger, Level::Info, "dropped");
+            log_message(&logger, Level::Info, "kept");
+        });
+
+    assert_eq!(*observed.lock().unwrap(), ["kept"]);
+    assert_eq!(capture.records().len(), 1);
+}
@@ -0,0 +1,34 @@
+use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[cfg(feature = "origin")]
+#[test]
+fn test_origin_reflects_capture_site() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone());
+
+    let (context, line) = (LogContext::new().with_origin(), line!());
+    context.in_scope(|| log_message(&logger, Level::Info, "with origin"));
+
+    let origin = capture.records()[0].get("ctx.origin").unwrap().clone();
+    let (location, _column) = origin.as_str().unwrap().rsplit_once(':').unwrap();
+    assert_eq!(location, format!("{}:{line}", file!()));
+}
+
+#[cfg(not(feature = "origin"))]
+#[test]
+fn test_origin_is_noop_without_feature() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone());
+
+    LogContext::new()
+        .with_origin()
+        .in_scope(|| log_message(&logger, Level::Info, "without origin"));
+
+    assert_eq!(capture.records()[0].get("ctx.origin"), None);
+}
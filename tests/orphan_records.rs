@@ -0,0 +1,38 @@
+use std::sync::{Arc, Mutex};
+
+use context_logger::{LogContext, LogScope, OrphanRecordPolicy};
+use pretty_assertions::assert_eq;
+
+// The policy is process-wide, so all policies are checked in a single test.
+#[test]
+fn test_orphan_record_policies() {
+    assert_eq!(LogScope::orphan_record_policy(), OrphanRecordPolicy::Ignore);
+    LogScope::add_record("user_id", 1);
+    assert!(LogScope::current_context().is_empty());
+
+    let reported = Arc::new(Mutex::new(Vec::new()));
+    LogScope::set_orphan_record_hook({
+        let reported = reported.clone();
+        move |key| reported.lock().unwrap().push(key.to_owned())
+    });
+    LogScope::set_orphan_record_policy(OrphanRecordPolicy::Warn);
+    assert_eq!(LogScope::orphan_record_policy(), OrphanRecordPolicy::Warn);
+    LogScope::add_record("user_id", 2);
+    LogScope::add_record("request_id", 3);
+    assert!(LogScope::current_context().is_empty());
+    // Only the first orphan record is reported.
+    assert_eq!(*reported.lock().unwrap(), ["user_id"]);
+
+    LogScope::set_orphan_record_policy(OrphanRecordPolicy::AutoCreateFrame);
+    LogScope::add_record("user_id", 4);
+    LogScope::add_record("request_id", "req-1");
+    let context = LogScope::current_context();
+    assert_eq!(context.local.iter().count(), 2);
+    assert_eq!(LogScope::clear_current_thread(), 1);
+
+    // Records added within an active scope are not affected by the policy.
+    LogScope::set_orphan_record_policy(OrphanRecordPolicy::Ignore);
+    let guard = LogScope::enter(LogContext::new());
+    LogScope::add_record("user_id", 5);
+    assert_eq!(guard.exit().unwrap().local.iter().count(), 1);
+}
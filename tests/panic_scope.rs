@@ -0,0 +1,37 @@
+// Warning: Because the test installs a panic hook, it has to be kept in a separate
+// file to avoid observing panics of other tests.
+
+use std::sync::{Arc, Mutex};
+
+use context_logger::{LogContext, LogContextExt as _, LogScope};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_panicking_scope_is_exited_after_the_hook_runs() {
+    let observed = Arc::new(Mutex::new(None));
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new({
+        let observed = observed.clone();
+        move |_info| {
+            let context = LogScope::current_context();
+            *observed.lock().unwrap() =
+                Some(context.view().get("request_id").map(ToString::to_string));
+        }
+    }));
+
+    let result = std::panic::catch_unwind(|| {
+        LogContext::new()
+            .with_local_record("request_id", "req-1")
+            .in_scope(|| panic!("request failed"));
+    });
+    std::panic::set_hook(previous_hook);
+
+    assert!(result.is_err());
+    // The hook observed the context of the panicking scope.
+    assert_eq!(
+        observed.lock().unwrap().clone(),
+        Some(Some("req-1".to_owned()))
+    );
+    // The scope was exited during unwinding.
+    assert!(LogScope::current_context().is_empty());
+}
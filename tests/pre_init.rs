@@ -0,0 +1,59 @@
+// Warning: Because each test initializes the logger, we need to split the
+// tests into separate files to avoid multiple initializations of the logger.
+
+use context_logger::{
+    CapturedContext, ContextLogger, LogContext, LogContextExt as _, LogScope, kv::ContextSource,
+    mdc,
+};
+use log::LevelFilter;
+use pretty_assertions::assert_eq;
+
+use crate::common::CaptureLogger;
+
+pub mod common;
+
+#[test]
+fn test_usage_before_init() {
+    // Nothing is installed yet: records are dropped by `log`, context APIs keep working.
+    assert_eq!(context_logger::max_level(), LevelFilter::Off);
+    assert!(LogScope::current_context().is_empty());
+    assert!(CapturedContext::with_frames().frames().is_empty());
+    assert!(ContextSource::current().context().is_empty());
+    assert_eq!(LogScope::depth(), 0);
+    assert!(!LogContext::is_active("request_id"));
+    assert!(LogScope::replace_current(LogContext::new()).is_none());
+    assert_eq!(LogScope::clear_current_thread(), 0);
+    assert!(mdc::get("request_id").is_none());
+    LogScope::add_record("orphan", true);
+    LogScope::checkpoint("orphan_ms");
+    assert!(LogScope::current_context().is_empty());
+
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone());
+    let handle = logger.handle();
+    assert_eq!(handle.max_level(), None);
+    handle.stop_periodic_flush();
+
+    // A scope entered before the logger is installed enriches records logged afterwards.
+    let guard = LogScope::enter(LogContext::new().with_local_record("request_id", "req-1"));
+    log::info!("Dropped before init");
+    LogScope::add_record("user_id", 42);
+    assert!(LogContext::is_active("request_id"));
+    assert_eq!(CapturedContext::with_frames().frames().len(), 1);
+
+    logger.init(LevelFilter::Info);
+    assert_eq!(handle.max_level(), Some(LevelFilter::Info));
+    log::info!("Logged after init");
+    drop(guard);
+    LogContext::new().in_scope(|| log::info!("Empty scope"));
+
+    let records = capture.records();
+    let messages = records
+        .iter()
+        .map(|record| record.message.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(messages, ["Logged after init", "Empty scope"]);
+    assert_eq!(records[0].get("request_id").unwrap(), "req-1");
+    assert_eq!(records[0].get("user_id").unwrap(), 42);
+    assert!(records[1].key_values.is_empty());
+}
@@ -0,0 +1,54 @@
+use context_logger::{ContextLogger, LogContext};
+use log::{Level, Log as _, Metadata};
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+fn noisy_library_call(logger: &ContextLogger) {
+    log_message(logger, Level::Debug, "Noise");
+    log_message(logger, Level::Info, "More noise");
+    log_message(logger, Level::Warn, "Warning");
+    log_message(logger, Level::Error, "Failure");
+}
+
+#[test]
+fn test_quiet_scope_suppresses_records() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone());
+
+    log_message(&logger, Level::Info, "Before");
+    {
+        let _quiet = LogContext::quiet();
+        noisy_library_call(&logger);
+        assert!(!logger.enabled(&Metadata::builder().level(Level::Error).build()));
+    }
+    {
+        let _quiet = LogContext::quiet_below(Level::Warn);
+        noisy_library_call(&logger);
+        {
+            // Nested guards cannot make the thread more verbose.
+            let _verbose = LogContext::quiet_below(Level::Trace);
+            log_message(&logger, Level::Info, "Still suppressed");
+        }
+        log_message(&logger, Level::Info, "Suppressed again");
+    }
+    // Other threads are not affected.
+    let quiet = LogContext::quiet();
+    std::thread::scope(|scope| {
+        scope.spawn(|| log_message(&logger, Level::Info, "Another thread"));
+    });
+    drop(quiet);
+    log_message(&logger, Level::Info, "After");
+
+    let messages = capture
+        .records()
+        .into_iter()
+        .map(|record| record.message)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        ["Before", "Warning", "Failure", "Another thread", "After"]
+    );
+}
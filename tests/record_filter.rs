@@ -0,0 +1,78 @@
+use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+fn debug_user_logger(capture: &CaptureLogger) -> ContextLogger {
+    ContextLogger::new(capture.clone())
+        .with_default_record("service", "api")
+        .with_record_filter(|record, context| {
+            record.level() <= Level::Warn
+                || context
+                    .get("debug_user")
+                    .is_some_and(|value| value.to_string() == "true")
+        })
+}
+
+#[test]
+fn test_record_filter_depends_on_context() {
+    let capture = CaptureLogger::default();
+    let logger = debug_user_logger(&capture);
+
+    log_message(&logger, Level::Info, "dropped");
+    log_message(&logger, Level::Warn, "kept");
+    LogContext::new()
+        .with_local_record("debug_user", true)
+        .in_scope(|| log_message(&logger, Level::Debug, "debug user"));
+    LogContext::new()
+        .with_local_record("debug_user", false)
+        .in_scope(|| log_message(&logger, Level::Debug, "regular user"));
+
+    let messages = capture
+        .records()
+        .into_iter()
+        .map(|record| record.message)
+        .collect::<Vec<_>>();
+    assert_eq!(messages, ["kept", "debug user"]);
+}
+
+#[test]
+fn test_record_filter_sees_enriched_context() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_default_record("service", "api")
+        .with_record_filter(|_, context| {
+            assert_eq!(context.len(), 3);
+            assert!(context.contains_key("service"));
+            assert_eq!(context.get("stage").unwrap().to_string(), "inner");
+            true
+        });
+
+    LogContext::new()
+        .with_inherited_record("stage", "outer")
+        .in_scope(|| {
+            LogContext::new()
+                .with_local_record("stage", "inner")
+                .in_scope(|| log_message(&logger, Level::Info, "message"));
+        });
+
+    assert_eq!(capture.records().len(), 1);
+}
+
+#[test]
+fn test_record_filter_applies_to_disabled_context() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_context_disabled(true)
+        .with_record_filter(|record, context| context.is_empty() && record.level() <= Level::Warn);
+
+    log_message(&logger, Level::Info, "dropped");
+    log_message(&logger, Level::Error, "kept");
+
+    let records = capture.records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].message, "kept");
+}
@@ -0,0 +1,61 @@
+use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message, log_message_with};
+
+pub mod common;
+
+#[test]
+fn test_record_once() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_record_filter(|record, _| record.level() <= Level::Info);
+
+    LogContext::new()
+        .with_local_record("request_id", "req-123")
+        .in_scope(|| {
+            LogContext::record_once("retry_after_ms", 250);
+            log_message_with(&logger, Level::Warn, "Rate limited", &[("answer", 42)]);
+            log_message(&logger, Level::Info, "Retrying");
+        });
+
+    // A record filtered out by the logger still consumes the one-shot record.
+    LogContext::record_once("lost", true);
+    log_message(&logger, Level::Debug, "Filtered");
+    log_message(&logger, Level::Info, "After filtered");
+
+    // One-shot records are per thread.
+    LogContext::record_once("thread", "main");
+    std::thread::scope(|scope| {
+        scope.spawn(|| log_message(&logger, Level::Info, "Another thread"));
+    });
+    log_message(&logger, Level::Info, "Main thread");
+
+    let records = capture.records();
+    let with_keys = records
+        .iter()
+        .map(|record| {
+            let keys = record
+                .key_values
+                .iter()
+                .map(|(key, _)| key.as_str())
+                .collect::<Vec<_>>();
+            (record.message.as_str(), keys)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        with_keys,
+        [
+            (
+                "Rate limited",
+                vec!["request_id", "retry_after_ms", "answer"]
+            ),
+            ("Retrying", vec!["request_id"]),
+            ("After filtered", vec![]),
+            ("Another thread", vec![]),
+            ("Main thread", vec!["thread"]),
+        ]
+    );
+    assert_eq!(records[0].get("retry_after_ms"), Some(&250.into()));
+}
@@ -0,0 +1,64 @@
+// Warning: Because each test initializes the logger, we need to split the
+// tests into separate files to avoid multiple initializations of the logger.
+
+use std::fmt;
+
+use context_logger::{ContextLogger, LogContext, LogScope, LogValue, mdc};
+use log::LevelFilter;
+use pretty_assertions::assert_eq;
+
+use crate::common::CaptureLogger;
+
+pub mod common;
+
+/// A value that logs when it is dropped.
+struct LogOnDrop;
+
+impl fmt::Display for LogOnDrop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("log-on-drop")
+    }
+}
+
+impl Drop for LogOnDrop {
+    fn drop(&mut self) {
+        log::info!(target: "noisy", "Filtered out");
+        log::info!("Value dropped");
+    }
+}
+
+/// A value that modifies the current scope while it is rendered.
+struct ClearOnRender;
+
+impl fmt::Display for ClearOnRender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        mdc::clear();
+        f.write_str("clear-on-render")
+    }
+}
+
+#[test]
+fn test_borrow_conflicts_fall_back_gracefully() {
+    let capture = CaptureLogger::default();
+    ContextLogger::new(capture.clone())
+        .with_record_filter(|record, _| record.target() != "noisy")
+        .init(LevelFilter::Trace);
+
+    let _guard = LogScope::enter(LogContext::new().with_local_record("request_id", "req-1"));
+
+    // The value is dropped while the scope stack is mutably borrowed by `mdc::clear`,
+    // so its records are forwarded without the context, but still filtered.
+    mdc::put("value", LogValue::display(LogOnDrop));
+    mdc::clear();
+
+    // Modifying the scope while the logger renders it is ignored.
+    mdc::put("value", LogValue::display(ClearOnRender));
+    log::info!("Rendered");
+
+    let records = capture.records();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].message, "Value dropped");
+    assert!(records[0].key_values.is_empty());
+    assert_eq!(records[1].message, "Rendered");
+    assert_eq!(records[1].get("value").unwrap(), "clear-on-render");
+}
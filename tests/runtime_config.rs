@@ -0,0 +1,55 @@
+// Warning: Because each test initializes the logger, we need to split the
+// tests into separate files to avoid multiple initializations of the logger.
+
+use context_logger::{ContextLogger, ContextLoggerConfig, LogContext, LogContextExt as _};
+use log::LevelFilter;
+use pretty_assertions::assert_eq;
+
+use crate::common::CaptureLogger;
+
+pub mod common;
+
+#[test]
+fn test_records_reflect_runtime_config_changes() {
+    let capture = CaptureLogger::default();
+    ContextLogger::new(capture.clone())
+        .with_default_record("service", "api")
+        .init(LevelFilter::Trace);
+
+    let in_context = || {
+        LogContext::new()
+            .with_local_record("request_id", "req-1")
+            .in_scope(|| log::info!("Handling request"));
+    };
+
+    in_context();
+    context_logger::update_config(|config| config.with_default_record("maintenance", true));
+    in_context();
+    log::info!("Outside of a scope");
+    context_logger::update_config(|config| config.with_context_enabled(false));
+    in_context();
+    context_logger::set_config(ContextLoggerConfig::new());
+    in_context();
+
+    let records = capture.records();
+    assert_eq!(records[0].get("maintenance"), None);
+    assert_eq!(records[1].get("maintenance").unwrap(), true);
+    assert_eq!(records[1].get("service").unwrap(), "api");
+    assert_eq!(records[1].get("request_id").unwrap(), "req-1");
+    assert_eq!(records[2].get("maintenance").unwrap(), true);
+    // Enrichment is disabled as a whole.
+    assert!(records[3].key_values.is_empty());
+    assert_eq!(records[4].key_values.len(), 2);
+    assert_eq!(records[4].get("maintenance"), None);
+}
+
+#[test]
+fn test_config_snapshot() {
+    let config = ContextLoggerConfig::new()
+        .with_default_record("region", "eu")
+        .with_default_record("maintenance", true)
+        .without_default_record("maintenance");
+
+    assert_eq!(config.default_records().iter().count(), 1);
+    assert!(config.is_context_enabled());
+}
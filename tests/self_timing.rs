@@ -0,0 +1,79 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[test]
+fn test_self_timing_reports_samples() {
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_self_timing(Duration::ZERO, {
+        let reports = reports.clone();
+        move |stats| reports.lock().unwrap().push(*stats)
+    });
+
+    log_message(&logger, Level::Info, "Outside of a scope");
+    LogContext::new()
+        .with_local_record("request_id", "req-1")
+        .in_scope(|| log_message(&logger, Level::Info, "Inside a scope"));
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(capture.records().len(), 2);
+    // With a zero interval every record is reported separately.
+    assert_eq!(reports.len(), 2);
+    for stats in reports.iter() {
+        assert_eq!(stats.records(), 1);
+        assert!(stats.total() > Duration::ZERO);
+        assert_eq!(stats.max(), stats.total());
+        assert_eq!(stats.mean(), stats.total());
+    }
+}
+
+/// An inner logger that takes a while to log a record.
+struct SlowLogger;
+
+impl log::Log for SlowLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, _record: &log::Record) {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn test_self_timing_excludes_inner_logger() {
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let logger = ContextLogger::new(SlowLogger)
+        .with_filter("noisy=off")
+        .with_self_timing(Duration::ZERO, {
+            let reports = reports.clone();
+            move |stats| reports.lock().unwrap().push(*stats)
+        });
+
+    log_message(&logger, Level::Info, "Slow to write");
+    log::Log::log(
+        &logger,
+        &log::Record::builder()
+            .level(Level::Info)
+            .target("noisy")
+            .args(format_args!("Filtered out"))
+            .build(),
+    );
+
+    let reports = reports.lock().unwrap().clone();
+    // Records rejected by the target filter are not measured.
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].total() < Duration::from_millis(50));
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use context_logger::ContextLogger;
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[test]
+fn test_sequence_field_is_strictly_increasing() {
+    let capture = CaptureLogger::default();
+    let logger = Arc::new(ContextLogger::new(capture.clone()).with_sequence_field("seq"));
+
+    std::thread::scope(|scope| {
+        for thread in 0..4 {
+            let logger = logger.clone();
+            scope.spawn(move || {
+                for _ in 0..100 {
+                    log_message(&*logger, Level::Info, &thread.to_string());
+                }
+            });
+        }
+    });
+
+    let records = capture.records();
+    let sequence = |thread: &str| {
+        records
+            .iter()
+            .filter(|record| record.message == thread)
+            .map(|record| record.get("seq").unwrap().as_u64().unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    let mut all = Vec::new();
+    for thread in ["0", "1", "2", "3"] {
+        let sequence = sequence(thread);
+        assert!(sequence.is_sorted_by(|a, b| a < b), "{sequence:?}");
+        all.extend(sequence);
+    }
+
+    all.sort_unstable();
+    all.dedup();
+    assert_eq!(all.len(), 400);
+}
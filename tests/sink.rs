@@ -0,0 +1,41 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use context_logger::{ContextLogger, LogContext, LogContextExt as _, LogValue};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::log_message;
+
+pub mod common;
+
+/// A value that counts how many times it has been rendered.
+#[derive(Debug, Clone, Default)]
+struct RenderCounter(Arc<AtomicUsize>);
+
+impl std::fmt::Display for RenderCounter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        f.write_str("rendered")
+    }
+}
+
+#[test]
+fn test_sink_renders_context_records() {
+    let logger = ContextLogger::sink();
+    let default_counter = RenderCounter::default();
+    let scope_counter = RenderCounter::default();
+    let logger = logger.with_default_record("default", LogValue::display(default_counter.clone()));
+
+    LogContext::new()
+        .with_local_record("scope", LogValue::display(scope_counter.clone()))
+        .in_scope(|| {
+            log_message(&logger, Level::Info, "first");
+            log_message(&logger, Level::Trace, "second");
+        });
+
+    assert_eq!(default_counter.0.load(Ordering::Relaxed), 2);
+    assert_eq!(scope_counter.0.load(Ordering::Relaxed), 2);
+}
@@ -0,0 +1,62 @@
+use context_logger::ContextLogger;
+use log::{Level, Log as _, Record};
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[test]
+fn test_source_location_records() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_source_location(true);
+
+    let line = line!();
+    logger.log(
+        &Record::builder()
+            .level(Level::Info)
+            .args(format_args!("located"))
+            .file_static(Some(file!()))
+            .line(Some(line))
+            .module_path_static(Some(module_path!()))
+            .build(),
+    );
+
+    let records = capture.records();
+    assert_eq!(
+        records[0].get("src.file").unwrap(),
+        "tests/source_location.rs"
+    );
+    assert_eq!(records[0].get("src.line").unwrap(), line);
+    assert_eq!(records[0].get("src.module").unwrap(), "source_location");
+}
+
+#[test]
+fn test_source_location_missing_fields_are_omitted() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_source_location(true);
+
+    log_message(&logger, Level::Info, "unknown location");
+
+    let records = capture.records();
+    assert!(records[0].get("src.file").is_none());
+    assert!(records[0].get("src.line").is_none());
+    assert!(records[0].get("src.module").is_none());
+}
+
+#[test]
+fn test_source_location_disabled_by_default() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone());
+
+    logger.log(
+        &Record::builder()
+            .level(Level::Info)
+            .args(format_args!("located"))
+            .file_static(Some(file!()))
+            .line(Some(line!()))
+            .build(),
+    );
+
+    assert!(capture.records()[0].key_values.is_empty());
+}
@@ -0,0 +1,67 @@
+use std::sync::{Arc, Mutex};
+
+use context_logger::{ContextLogger, KeyPolicy, LogContext, LogContextExt as _};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+fn log_with_invalid_keys(policy: KeyPolicy) -> Vec<String> {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_default_record("service name", "api")
+        .with_strict_keys(policy);
+
+    LogContext::new()
+        .with_local_record("", 1)
+        .with_local_record("user_id", 42)
+        .in_scope(|| log_message(&logger, Level::Info, "strict"));
+
+    let mut keys = capture.records()[0]
+        .key_values
+        .iter()
+        .map(|(key, _)| key.clone())
+        .collect::<Vec<_>>();
+    keys.sort();
+    keys
+}
+
+#[test]
+fn test_strict_keys_allow() {
+    assert_eq!(
+        log_with_invalid_keys(KeyPolicy::Allow),
+        ["", "service name", "user_id"]
+    );
+}
+
+#[test]
+fn test_strict_keys_sanitize() {
+    assert_eq!(
+        log_with_invalid_keys(KeyPolicy::Sanitize),
+        ["_", "service_name", "user_id"]
+    );
+}
+
+#[test]
+fn test_strict_keys_drop() {
+    assert_eq!(log_with_invalid_keys(KeyPolicy::Drop), ["user_id"]);
+}
+
+#[test]
+fn test_dropped_keys_are_observed() {
+    let dropped = Arc::new(Mutex::new(Vec::new()));
+    let logger = ContextLogger::new(CaptureLogger::default())
+        .with_default_record("service name", "api")
+        .with_strict_keys(KeyPolicy::Drop)
+        .with_invalid_key_observer({
+            let dropped = dropped.clone();
+            move |key| dropped.lock().unwrap().push(key.to_owned())
+        });
+
+    log_message(&logger, Level::Info, "first");
+    log_message(&logger, Level::Info, "second");
+
+    assert_eq!(*dropped.lock().unwrap(), ["service name", "service name"]);
+}
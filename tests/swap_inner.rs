@@ -0,0 +1,74 @@
+use context_logger::{ContextLogger, LogContext, LogContextExt as _};
+use log::Level;
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+#[test]
+fn test_swap_inner_logger() {
+    let first = CaptureLogger::default();
+    let second = CaptureLogger::default();
+
+    let logger = ContextLogger::new(first.clone()).with_default_record("service", "api");
+    let handle = logger.handle();
+
+    log_message(&logger, Level::Info, "before swap");
+    handle.set_inner(second.clone());
+    LogContext::new()
+        .with_local_record("user_id", 42)
+        .in_scope(|| log_message(&logger, Level::Info, "after swap"));
+
+    let first = first.records();
+    assert_eq!(first.len(), 1);
+    assert_eq!(first[0].message, "before swap");
+
+    let second = second.records();
+    assert_eq!(second.len(), 1);
+    assert_eq!(second[0].message, "after swap");
+    assert_eq!(second[0].get("service").unwrap(), "api");
+    assert_eq!(second[0].get("user_id").unwrap(), 42);
+}
+
+#[test]
+fn test_swap_inner_logger_concurrently() {
+    let first = CaptureLogger::default();
+    let second = CaptureLogger::default();
+
+    let logger = ContextLogger::new(first.clone());
+    let handle = logger.handle();
+
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..100 {
+                    log_message(&logger, Level::Info, "message");
+                }
+            });
+        }
+        scope.spawn(|| handle.set_inner(second.clone()));
+    });
+
+    assert_eq!(first.records().len() + second.records().len(), 400);
+}
+
+#[test]
+fn test_take_inner_logger() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_default_record("service", "api");
+    let handle = logger.handle();
+
+    log_message(&logger, Level::Info, "through context logger");
+    let inner = logger.take_inner();
+    log_message(&*inner, Level::Info, "through inner logger");
+
+    let records = capture.records();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].get("service").unwrap(), "api");
+    assert!(records[1].key_values.is_empty());
+
+    // The handle no longer refers to the recovered logger.
+    handle.set_inner(CaptureLogger::default());
+    assert_eq!(capture.records().len(), 2);
+}
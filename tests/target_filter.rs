@@ -0,0 +1,41 @@
+use context_logger::ContextLogger;
+use log::{Level, Log as _, Metadata, Record};
+use pretty_assertions::assert_eq;
+
+use crate::common::CaptureLogger;
+
+pub mod common;
+
+fn log_to(logger: &ContextLogger, target: &str, level: Level) {
+    logger.log(
+        &Record::builder()
+            .level(level)
+            .target(target)
+            .args(format_args!("{target} {level}"))
+            .build(),
+    );
+}
+
+#[test]
+fn test_levels_per_target() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone()).with_filter("warn,app=info,app::db=debug");
+
+    log_to(&logger, "app::db", Level::Debug);
+    log_to(&logger, "app::db", Level::Trace);
+    log_to(&logger, "app::http", Level::Info);
+    log_to(&logger, "app::http", Level::Debug);
+    log_to(&logger, "hyper", Level::Info);
+    log_to(&logger, "hyper", Level::Warn);
+
+    let messages = capture
+        .records()
+        .into_iter()
+        .map(|record| record.message)
+        .collect::<Vec<_>>();
+    assert_eq!(messages, ["app::db DEBUG", "app::http INFO", "hyper WARN"]);
+
+    let metadata = |target, level| Metadata::builder().target(target).level(level).build();
+    assert!(logger.enabled(&metadata("app::db", Level::Debug)));
+    assert!(!logger.enabled(&metadata("hyper", Level::Info)));
+}
@@ -0,0 +1,37 @@
+use context_logger::{ContextLogger, LogContext, LogContextExt as _, LogValue, LogValueKind};
+use log::{
+    Level,
+    kv::{ToValue, Value},
+};
+use pretty_assertions::assert_eq;
+
+use crate::common::{CaptureLogger, log_message};
+
+pub mod common;
+
+/// A type from another crate that only supports the `log` key-values.
+struct Port(u16);
+
+impl ToValue for Port {
+    fn to_value(&self) -> Value<'_> {
+        Value::from(self.0)
+    }
+}
+
+#[test]
+fn test_to_value_survives_enrichment() {
+    let capture = CaptureLogger::default();
+    let logger = ContextLogger::new(capture.clone())
+        .with_default_record("port", LogValue::to_value(Port(8080)));
+
+    let value = LogValue::to_value(Port(443));
+    assert_eq!(value.kind(), LogValueKind::ToValue);
+    LogContext::new()
+        .with_local_record("upstream_port", value)
+        .in_scope(|| log_message(&logger, Level::Info, "Connected"));
+
+    let records = capture.records();
+    // The values keep their numeric representation instead of being rendered as text.
+    assert_eq!(records[0].get("port"), Some(&8080.into()));
+    assert_eq!(records[0].get("upstream_port"), Some(&443.into()));
+}
@@ -0,0 +1,6 @@
+use context_logger::{FutureExt, LogContext};
+
+fn main() {
+    let stream = futures_util::stream::iter([1, 2, 3]);
+    let _ = FutureExt::in_log_context(stream, LogContext::new());
+}
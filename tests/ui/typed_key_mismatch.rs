@@ -0,0 +1,7 @@
+use context_logger::{ContextKey, LogContext};
+
+const USER_ID: ContextKey<u64> = ContextKey::new("user_id");
+
+fn main() {
+    let _ = LogContext::new().with_typed_record(USER_ID, "42");
+}
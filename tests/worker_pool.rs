@@ -0,0 +1,88 @@
+use std::sync::{Arc, Mutex, mpsc};
+
+use context_logger::{
+    CapturedContext, ContextEnvelope, ContextLogger, LogContext, LogContextExt as _,
+};
+use log::Level;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+use crate::common::{CaptureLogger, log_message_with};
+
+pub mod common;
+
+#[test]
+fn test_worker_pool_logs_carry_producer_context() {
+    let capture = CaptureLogger::default();
+    let logger = Arc::new(ContextLogger::new(capture.clone()));
+
+    let (sender, receiver) = mpsc::channel::<ContextEnvelope<u64>>();
+    let receiver = Arc::new(Mutex::new(receiver));
+    let pool_context = LogContext::new()
+        .with_inherited_record("pool", "test")
+        .in_scope(CapturedContext::with_frames);
+    let workers = (0..3_u64)
+        .map(|worker| {
+            let logger = Arc::clone(&logger);
+            let receiver = Arc::clone(&receiver);
+            let pool_context = pool_context.clone();
+            std::thread::spawn(move || {
+                let _pool_guard = pool_context.enter_all();
+                loop {
+                    let envelope = receiver.lock().unwrap().recv();
+                    let Ok(envelope) = envelope else {
+                        break;
+                    };
+                    envelope.in_context(|input| {
+                        log_message_with(
+                            &*logger,
+                            Level::Info,
+                            "Processing job",
+                            &[("worker", worker), ("input", input)],
+                        );
+                    });
+                }
+                log_message_with(
+                    &*logger,
+                    Level::Info,
+                    "Worker stopped",
+                    &[("worker", worker)],
+                );
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for job_id in 1..=20_u64 {
+        LogContext::new()
+            .with_local_record("job_id", job_id)
+            .in_scope(|| ContextEnvelope::send_with_context(&sender, job_id * 10))
+            .unwrap();
+    }
+    drop(sender);
+    for worker in workers {
+        worker.join().unwrap();
+    }
+
+    let records = capture.records();
+    let jobs = records
+        .iter()
+        .filter(|record| record.message == "Processing job")
+        .collect::<Vec<_>>();
+    assert_eq!(jobs.len(), 20);
+    for record in jobs {
+        // Each worker log shows the job id of the producer that submitted the input.
+        let job_id = record.get("job_id").unwrap().as_u64().unwrap();
+        assert_eq!(record.get("input"), Some(&json!(job_id * 10)));
+        assert_eq!(record.get("pool"), Some(&json!("test")));
+    }
+
+    let stopped = records
+        .iter()
+        .filter(|record| record.message == "Worker stopped")
+        .collect::<Vec<_>>();
+    assert_eq!(stopped.len(), 3);
+    for record in stopped {
+        assert_eq!(record.get("job_id"), None);
+        assert_eq!(record.get("pool"), Some(&json!("test")));
+    }
+}